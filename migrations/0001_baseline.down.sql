@@ -0,0 +1,7 @@
+-- Rolling back the baseline drops every table and all trading history
+DROP TABLE IF EXISTS collateral_transfers;
+DROP TABLE IF EXISTS kill_switch;
+DROP TABLE IF EXISTS circuit_breaker;
+DROP TABLE IF EXISTS equity_snapshots;
+DROP TABLE IF EXISTS strategies;
+DROP TABLE IF EXISTS positions;
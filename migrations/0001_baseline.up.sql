@@ -0,0 +1,93 @@
+-- Baseline: the schema previously created by the storages at startup.
+-- Every statement is idempotent so databases created before migrations adopt it as is.
+
+CREATE TABLE IF NOT EXISTS positions (
+    id TEXT PRIMARY KEY,
+    wallet_id SMALLINT NOT NULL,
+    strategy_id TEXT,
+    exchange TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL,
+    size TEXT NOT NULL,
+    status TEXT NOT NULL,
+    opened_at TIMESTAMPTZ NOT NULL,
+    close_at TIMESTAMPTZ NOT NULL,
+    closed_at TIMESTAMPTZ,
+    realized_pnl TEXT,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_status ON positions(exchange, status);
+CREATE INDEX IF NOT EXISTS idx_strategy_id ON positions(strategy_id);
+
+CREATE TABLE IF NOT EXISTS strategies (
+    id TEXT PRIMARY KEY,
+    token_symbol TEXT NOT NULL,
+    wallet_ids TEXT NOT NULL,
+    longs_size TEXT NOT NULL,
+    shorts_size TEXT NOT NULL,
+    status TEXT NOT NULL,
+    opened_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    close_at TIMESTAMPTZ NOT NULL,
+    closed_at TIMESTAMPTZ,
+    realized_pnl TEXT,
+    long_position_ids TEXT NOT NULL,
+    short_position_ids TEXT NOT NULL
+);
+
+-- Columns added after the initial schema
+ALTER TABLE strategies
+    ADD COLUMN IF NOT EXISTS strategy_type TEXT NOT NULL DEFAULT 'DELTA_NEUTRAL',
+    ADD COLUMN IF NOT EXISTS hedge_symbols TEXT NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS hedge_ratio TEXT,
+    ADD COLUMN IF NOT EXISTS estimated_cost TEXT,
+    ADD COLUMN IF NOT EXISTS estimated_cost_bps TEXT,
+    ADD COLUMN IF NOT EXISTS entry_slippage_bps TEXT,
+    ADD COLUMN IF NOT EXISTS max_leg_slippage_bps TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_strategy_status ON strategies(status);
+CREATE INDEX IF NOT EXISTS idx_strategy_close_at ON strategies(close_at);
+
+CREATE TABLE IF NOT EXISTS equity_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    taken_at TIMESTAMPTZ NOT NULL,
+    account_value TEXT NOT NULL,
+    unrealized_pnl TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_equity_snapshots_taken_at ON equity_snapshots(taken_at);
+
+-- Single-row table: the breaker stays tripped across restarts until reset
+CREATE TABLE IF NOT EXISTS circuit_breaker (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    tripped BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    tripped_at TIMESTAMPTZ,
+    reset_at TIMESTAMPTZ
+);
+
+INSERT INTO circuit_breaker (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
+
+-- Single-row table: the kill switch stays active across restarts until cleared
+CREATE TABLE IF NOT EXISTS kill_switch (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    source TEXT,
+    triggered_at TIMESTAMPTZ,
+    cleared_at TIMESTAMPTZ
+);
+
+INSERT INTO kill_switch (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS collateral_transfers (
+    id BIGSERIAL PRIMARY KEY,
+    strategy_id TEXT NOT NULL,
+    from_wallet_id SMALLINT NOT NULL,
+    to_wallet_id SMALLINT NOT NULL,
+    amount_usdc TEXT NOT NULL,
+    tx_hash TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL
+);
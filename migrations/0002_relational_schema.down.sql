@@ -0,0 +1,61 @@
+ALTER TABLE strategies
+    ADD COLUMN wallet_ids TEXT NOT NULL DEFAULT '',
+    ADD COLUMN long_position_ids TEXT NOT NULL DEFAULT '',
+    ADD COLUMN short_position_ids TEXT NOT NULL DEFAULT '';
+
+UPDATE strategies s SET
+    wallet_ids = COALESCE((
+        SELECT string_agg(wallet_id::TEXT, ',' ORDER BY wallet_id)
+        FROM strategy_wallets w WHERE w.strategy_id = s.id
+    ), ''),
+    long_position_ids = COALESCE((
+        SELECT string_agg(position_id, ',' ORDER BY position_id)
+        FROM (
+            SELECT position_id FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'LONG'
+            UNION ALL
+            SELECT position_id FROM unnest(string_to_array(s.legacy_long_position_ids, ',')) AS position_id
+            WHERE position_id <> ''
+        ) ids
+    ), ''),
+    short_position_ids = COALESCE((
+        SELECT string_agg(position_id, ',' ORDER BY position_id)
+        FROM (
+            SELECT position_id FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'SHORT'
+            UNION ALL
+            SELECT position_id FROM unnest(string_to_array(s.legacy_short_position_ids, ',')) AS position_id
+            WHERE position_id <> ''
+        ) ids
+    ), '');
+
+ALTER TABLE strategies
+    ALTER COLUMN wallet_ids DROP DEFAULT,
+    ALTER COLUMN long_position_ids DROP DEFAULT,
+    ALTER COLUMN short_position_ids DROP DEFAULT;
+
+ALTER TABLE strategies
+    DROP COLUMN legacy_long_position_ids,
+    DROP COLUMN legacy_short_position_ids;
+
+DROP TABLE strategy_positions;
+DROP TABLE strategy_wallets;
+
+ALTER TABLE collateral_transfers
+    ALTER COLUMN amount_usdc TYPE TEXT;
+
+ALTER TABLE equity_snapshots
+    ALTER COLUMN account_value TYPE TEXT,
+    ALTER COLUMN unrealized_pnl TYPE TEXT;
+
+ALTER TABLE strategies
+    ALTER COLUMN longs_size TYPE TEXT,
+    ALTER COLUMN shorts_size TYPE TEXT,
+    ALTER COLUMN realized_pnl TYPE TEXT,
+    ALTER COLUMN hedge_ratio TYPE TEXT,
+    ALTER COLUMN estimated_cost TYPE TEXT,
+    ALTER COLUMN estimated_cost_bps TYPE TEXT,
+    ALTER COLUMN entry_slippage_bps TYPE TEXT,
+    ALTER COLUMN max_leg_slippage_bps TYPE TEXT;
+
+ALTER TABLE positions
+    ALTER COLUMN size TYPE TEXT,
+    ALTER COLUMN realized_pnl TYPE TEXT;
@@ -0,0 +1,80 @@
+-- Money and ratios as NUMERIC instead of TEXT
+ALTER TABLE positions
+    ALTER COLUMN size TYPE NUMERIC USING size::NUMERIC,
+    ALTER COLUMN realized_pnl TYPE NUMERIC USING NULLIF(realized_pnl, '')::NUMERIC;
+
+ALTER TABLE strategies
+    ALTER COLUMN longs_size TYPE NUMERIC USING longs_size::NUMERIC,
+    ALTER COLUMN shorts_size TYPE NUMERIC USING shorts_size::NUMERIC,
+    ALTER COLUMN realized_pnl TYPE NUMERIC USING NULLIF(realized_pnl, '')::NUMERIC,
+    ALTER COLUMN hedge_ratio TYPE NUMERIC USING NULLIF(hedge_ratio, '')::NUMERIC,
+    ALTER COLUMN estimated_cost TYPE NUMERIC USING NULLIF(estimated_cost, '')::NUMERIC,
+    ALTER COLUMN estimated_cost_bps TYPE NUMERIC USING NULLIF(estimated_cost_bps, '')::NUMERIC,
+    ALTER COLUMN entry_slippage_bps TYPE NUMERIC USING NULLIF(entry_slippage_bps, '')::NUMERIC,
+    ALTER COLUMN max_leg_slippage_bps TYPE NUMERIC USING NULLIF(max_leg_slippage_bps, '')::NUMERIC;
+
+ALTER TABLE equity_snapshots
+    ALTER COLUMN account_value TYPE NUMERIC USING account_value::NUMERIC,
+    ALTER COLUMN unrealized_pnl TYPE NUMERIC USING unrealized_pnl::NUMERIC;
+
+ALTER TABLE collateral_transfers
+    ALTER COLUMN amount_usdc TYPE NUMERIC USING amount_usdc::NUMERIC;
+
+-- Wallets used by each strategy
+CREATE TABLE strategy_wallets (
+    strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+    wallet_id SMALLINT NOT NULL,
+    PRIMARY KEY (strategy_id, wallet_id)
+);
+
+CREATE INDEX idx_strategy_wallets_wallet_id ON strategy_wallets(wallet_id);
+
+-- Positions (legs) of each strategy
+CREATE TABLE strategy_positions (
+    strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+    position_id TEXT NOT NULL REFERENCES positions(id) ON DELETE CASCADE,
+    side TEXT NOT NULL CHECK (side IN ('LONG', 'SHORT')),
+    PRIMARY KEY (strategy_id, position_id)
+);
+
+CREATE INDEX idx_strategy_positions_position_id ON strategy_positions(position_id);
+
+INSERT INTO strategy_wallets (strategy_id, wallet_id)
+SELECT DISTINCT id, wallet_id::SMALLINT
+FROM strategies, unnest(string_to_array(wallet_ids, ',')) AS wallet_id
+WHERE wallet_id <> '';
+
+INSERT INTO strategy_positions (strategy_id, position_id, side)
+SELECT DISTINCT id, position_id, 'LONG'
+FROM strategies, unnest(string_to_array(long_position_ids, ',')) AS position_id
+WHERE position_id <> '' AND EXISTS (SELECT 1 FROM positions p WHERE p.id = position_id)
+ON CONFLICT DO NOTHING;
+
+INSERT INTO strategy_positions (strategy_id, position_id, side)
+SELECT DISTINCT id, position_id, 'SHORT'
+FROM strategies, unnest(string_to_array(short_position_ids, ',')) AS position_id
+WHERE position_id <> '' AND EXISTS (SELECT 1 FROM positions p WHERE p.id = position_id)
+ON CONFLICT DO NOTHING;
+
+-- Legacy strategies list positions that were never stored, which strategy_positions cannot
+-- reference: their IDs are kept as they were, comma-separated, and restored on rollback
+ALTER TABLE strategies
+    ADD COLUMN legacy_long_position_ids TEXT NOT NULL DEFAULT '',
+    ADD COLUMN legacy_short_position_ids TEXT NOT NULL DEFAULT '';
+
+UPDATE strategies s SET
+    legacy_long_position_ids = COALESCE((
+        SELECT string_agg(position_id, ',' ORDER BY ordinality)
+        FROM unnest(string_to_array(s.long_position_ids, ',')) WITH ORDINALITY AS ids(position_id, ordinality)
+        WHERE position_id <> '' AND NOT EXISTS (SELECT 1 FROM positions p WHERE p.id = position_id)
+    ), ''),
+    legacy_short_position_ids = COALESCE((
+        SELECT string_agg(position_id, ',' ORDER BY ordinality)
+        FROM unnest(string_to_array(s.short_position_ids, ',')) WITH ORDINALITY AS ids(position_id, ordinality)
+        WHERE position_id <> '' AND NOT EXISTS (SELECT 1 FROM positions p WHERE p.id = position_id)
+    ), '');
+
+ALTER TABLE strategies
+    DROP COLUMN wallet_ids,
+    DROP COLUMN long_position_ids,
+    DROP COLUMN short_position_ids;
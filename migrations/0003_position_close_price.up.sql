@@ -0,0 +1,2 @@
+-- Mark price of each position when it was closed
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS close_price NUMERIC;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS strategy_events;
+
+DROP FUNCTION IF EXISTS strategy_events_append_only();
@@ -0,0 +1,24 @@
+-- Append-only journal of everything that happened to a strategy, for post-mortems.
+-- Plans that were rolled back never get a `strategies` row, so there is no foreign key.
+CREATE TABLE IF NOT EXISTS strategy_events (
+    id BIGSERIAL PRIMARY KEY,
+    strategy_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    wallet_id SMALLINT,
+    symbol TEXT,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_events_strategy_id ON strategy_events(strategy_id, id);
+CREATE INDEX IF NOT EXISTS idx_strategy_events_kind ON strategy_events(kind, created_at);
+
+CREATE OR REPLACE FUNCTION strategy_events_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'strategy_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER strategy_events_append_only
+    BEFORE UPDATE OR DELETE ON strategy_events
+    FOR EACH ROW EXECUTE FUNCTION strategy_events_append_only();
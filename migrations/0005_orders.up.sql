@@ -0,0 +1,25 @@
+-- Ledger of every order sent to an exchange, updated as it resolves
+CREATE TABLE IF NOT EXISTS orders (
+    id BIGSERIAL PRIMARY KEY,
+    exchange TEXT NOT NULL,
+    wallet_id SMALLINT NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL CHECK (side IN ('LONG', 'SHORT')),
+    reduce_only BOOLEAN NOT NULL,
+    base_amount BIGINT NOT NULL,
+    limit_price NUMERIC NOT NULL,
+    nonce BIGINT NOT NULL,
+    client_order_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL UNIQUE,
+    submitted_at TIMESTAMPTZ NOT NULL,
+    status TEXT NOT NULL,
+    tx_status BIGINT,
+    executed_at BIGINT,
+    fill_price NUMERIC,
+    fee_usdc NUMERIC,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_wallet_submitted_at ON orders(wallet_id, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_orders_symbol_submitted_at ON orders(symbol, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS wallet_points_snapshots;
+DROP TABLE IF EXISTS wallet_balance_snapshots;
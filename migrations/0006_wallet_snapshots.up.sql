@@ -0,0 +1,24 @@
+-- Time series of every wallet's balances
+CREATE TABLE IF NOT EXISTS wallet_balance_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    wallet_id SMALLINT NOT NULL,
+    taken_at TIMESTAMPTZ NOT NULL,
+    available_balance NUMERIC NOT NULL,
+    collateral NUMERIC NOT NULL,
+    total_asset_value NUMERIC NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_balance_snapshots_wallet_taken_at ON wallet_balance_snapshots(wallet_id, taken_at);
+CREATE INDEX IF NOT EXISTS idx_wallet_balance_snapshots_taken_at ON wallet_balance_snapshots(taken_at);
+
+-- Time series of every wallet's Lighter points
+CREATE TABLE IF NOT EXISTS wallet_points_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    wallet_id SMALLINT NOT NULL,
+    taken_at TIMESTAMPTZ NOT NULL,
+    total_points NUMERIC NOT NULL,
+    last_week_points NUMERIC NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_points_snapshots_wallet_taken_at ON wallet_points_snapshots(wallet_id, taken_at);
+CREATE INDEX IF NOT EXISTS idx_wallet_points_snapshots_taken_at ON wallet_points_snapshots(taken_at);
@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS wallet_points_snapshots;
+DROP TABLE IF EXISTS wallet_balance_snapshots;
+DROP TABLE IF EXISTS orders;
+DROP TABLE IF EXISTS strategy_events;
+DROP TABLE IF EXISTS collateral_transfers;
+DROP TABLE IF EXISTS kill_switch;
+DROP TABLE IF EXISTS circuit_breaker;
+DROP TABLE IF EXISTS equity_snapshots;
+DROP TABLE IF EXISTS strategy_positions;
+DROP TABLE IF EXISTS strategy_wallets;
+DROP TABLE IF EXISTS strategies;
+DROP TABLE IF EXISTS positions;
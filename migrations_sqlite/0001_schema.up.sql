@@ -0,0 +1,178 @@
+-- SQLite equivalent of the Postgres schema in `migrations/`.
+-- Money and ratios are stored as TEXT, so they round-trip exactly as decimals.
+
+CREATE TABLE IF NOT EXISTS positions (
+    id TEXT PRIMARY KEY,
+    wallet_id INTEGER NOT NULL,
+    strategy_id TEXT,
+    exchange TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL,
+    size TEXT NOT NULL,
+    status TEXT NOT NULL,
+    opened_at TEXT NOT NULL,
+    close_at TEXT NOT NULL,
+    closed_at TEXT,
+    close_price TEXT,
+    realized_pnl TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_status ON positions(exchange, status);
+CREATE INDEX IF NOT EXISTS idx_strategy_id ON positions(strategy_id);
+
+CREATE TABLE IF NOT EXISTS strategies (
+    id TEXT PRIMARY KEY,
+    token_symbol TEXT NOT NULL,
+    longs_size TEXT NOT NULL,
+    shorts_size TEXT NOT NULL,
+    status TEXT NOT NULL,
+    opened_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    close_at TEXT NOT NULL,
+    closed_at TEXT,
+    realized_pnl TEXT,
+    strategy_type TEXT NOT NULL DEFAULT 'DELTA_NEUTRAL',
+    hedge_symbols TEXT NOT NULL DEFAULT '',
+    hedge_ratio TEXT,
+    estimated_cost TEXT,
+    estimated_cost_bps TEXT,
+    entry_slippage_bps TEXT,
+    max_leg_slippage_bps TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_status ON strategies(status);
+CREATE INDEX IF NOT EXISTS idx_strategy_close_at ON strategies(close_at);
+
+-- Wallets used by each strategy
+CREATE TABLE IF NOT EXISTS strategy_wallets (
+    strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+    wallet_id INTEGER NOT NULL,
+    PRIMARY KEY (strategy_id, wallet_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_wallets_wallet_id ON strategy_wallets(wallet_id);
+
+-- Positions (legs) of each strategy
+CREATE TABLE IF NOT EXISTS strategy_positions (
+    strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+    position_id TEXT NOT NULL REFERENCES positions(id) ON DELETE CASCADE,
+    side TEXT NOT NULL CHECK (side IN ('LONG', 'SHORT')),
+    PRIMARY KEY (strategy_id, position_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_positions_position_id ON strategy_positions(position_id);
+
+CREATE TABLE IF NOT EXISTS equity_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    taken_at TEXT NOT NULL,
+    account_value TEXT NOT NULL,
+    unrealized_pnl TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_equity_snapshots_taken_at ON equity_snapshots(taken_at);
+
+-- Single-row table: the breaker stays tripped across restarts until reset
+CREATE TABLE IF NOT EXISTS circuit_breaker (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    tripped BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    tripped_at TEXT,
+    reset_at TEXT
+);
+
+INSERT INTO circuit_breaker (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
+
+-- Single-row table: the kill switch stays active across restarts until cleared
+CREATE TABLE IF NOT EXISTS kill_switch (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    source TEXT,
+    triggered_at TEXT,
+    cleared_at TEXT
+);
+
+INSERT INTO kill_switch (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS collateral_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    strategy_id TEXT NOT NULL,
+    from_wallet_id INTEGER NOT NULL,
+    to_wallet_id INTEGER NOT NULL,
+    amount_usdc TEXT NOT NULL,
+    tx_hash TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL
+);
+
+-- Append-only journal of everything that happened to a strategy
+CREATE TABLE IF NOT EXISTS strategy_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    strategy_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    wallet_id INTEGER,
+    symbol TEXT,
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_events_strategy_id ON strategy_events(strategy_id, id);
+CREATE INDEX IF NOT EXISTS idx_strategy_events_kind ON strategy_events(kind, created_at);
+
+CREATE TRIGGER IF NOT EXISTS strategy_events_no_update
+    BEFORE UPDATE ON strategy_events
+    BEGIN SELECT RAISE(ABORT, 'strategy_events is append-only'); END;
+
+CREATE TRIGGER IF NOT EXISTS strategy_events_no_delete
+    BEFORE DELETE ON strategy_events
+    BEGIN SELECT RAISE(ABORT, 'strategy_events is append-only'); END;
+
+-- Ledger of every order sent to an exchange, updated as it resolves
+CREATE TABLE IF NOT EXISTS orders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    exchange TEXT NOT NULL,
+    wallet_id INTEGER NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL CHECK (side IN ('LONG', 'SHORT')),
+    reduce_only BOOLEAN NOT NULL,
+    base_amount INTEGER NOT NULL,
+    limit_price TEXT NOT NULL,
+    nonce INTEGER NOT NULL,
+    client_order_index INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL UNIQUE,
+    submitted_at TEXT NOT NULL,
+    status TEXT NOT NULL,
+    tx_status INTEGER,
+    executed_at INTEGER,
+    fill_price TEXT,
+    fee_usdc TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_wallet_submitted_at ON orders(wallet_id, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_orders_symbol_submitted_at ON orders(symbol, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
+
+-- Time series of every wallet's balances
+CREATE TABLE IF NOT EXISTS wallet_balance_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_id INTEGER NOT NULL,
+    taken_at TEXT NOT NULL,
+    available_balance TEXT NOT NULL,
+    collateral TEXT NOT NULL,
+    total_asset_value TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_balance_snapshots_wallet_taken_at ON wallet_balance_snapshots(wallet_id, taken_at);
+
+-- Time series of every wallet's Lighter points
+CREATE TABLE IF NOT EXISTS wallet_points_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_id INTEGER NOT NULL,
+    taken_at TEXT NOT NULL,
+    total_points TEXT NOT NULL,
+    last_week_points TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_points_snapshots_wallet_taken_at ON wallet_points_snapshots(wallet_id, taken_at);
@@ -0,0 +1,9 @@
+-- Lease of each wallet by the bot instance allowed to trade it, renewed by heartbeat
+CREATE TABLE IF NOT EXISTS wallet_leases (
+    wallet_id INTEGER PRIMARY KEY,
+    holder TEXT NOT NULL,
+    acquired_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_leases_holder ON wallet_leases(holder);
@@ -1,5 +1,9 @@
 use crate::config::AppConfig;
 use crate::storage::storage_strategy::StrategyMetadata;
+use crate::risk::rescue::PlannedTransfer;
+use crate::trader::execution::SlippageBreach;
+use crate::trader::reconcile::ReconciliationReport;
+use rust_decimal::Decimal;
 use teloxide::prelude::*;
 use teloxide::types::ParseMode;
 use anyhow::Result;
@@ -33,7 +37,7 @@ impl TelegramAlerter {
         let error_msg = format!("{}", error);
         
         // Escape special characters for MarkdownV2
-        let escaped_symbol = Self::escape_markdown(&strategy.token_symbol);
+        let escaped_symbol = Self::escape_markdown(&strategy.market_label());
         let escaped_error = Self::escape_markdown(&error_msg);
         let escaped_time = Self::escape_markdown(&timestamp.to_string());
         
@@ -41,6 +45,7 @@ impl TelegramAlerter {
             "🚨 *Strategy Error Alert*\n\n\
             ⏰ *Time:* {}\n\
             🪙 *Token:* `{}`\n\
+            🧭 *Type:* {}\n\
             💱 *Strategy:* {}\n\
             📊 *Status:* Failed\n\n\
             ❌ *Error Details:*\n\
@@ -49,6 +54,7 @@ impl TelegramAlerter {
             *PLEASE CLOSE THE POSITIONS MANUALLY AND REOPEN THE STRATEGY\\.*",
             escaped_time,
             escaped_symbol,
+            Self::escape_markdown(&strategy.strategy_type.to_string()),
             Self::escape_markdown(&format!("{:?}", strategy.status)),
             escaped_error
         );
@@ -56,6 +62,197 @@ impl TelegramAlerter {
         self.send_message(&message).await
     }
 
+    /// Send an alert for a wallet group whose legs could not be repaired and was rolled back
+    pub async fn send_group_rollback_alert(
+        &self,
+        token_symbol: &str,
+        wallet_ids: &[u8],
+        error: &TradingError,
+        rolled_back: bool,
+    ) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+        let action = if rolled_back {
+            "Group rolled back, other groups keep running\\."
+        } else {
+            "*ROLLBACK FAILED, PLEASE CLOSE THE POSITIONS MANUALLY\\.*"
+        };
+
+        let message = format!(
+            "⚠️ *Group Execution Failed*\n\n\
+            ⏰ *Time:* {}\n\
+            🪙 *Token:* `{}`\n\
+            👛 *Wallets:* {}\n\n\
+            ❌ *Error Details:*\n\
+            {}\n\n\
+            {}",
+            Self::escape_markdown(&timestamp.to_string()),
+            Self::escape_markdown(token_symbol),
+            Self::escape_markdown(&format!("{:?}", wallet_ids)),
+            Self::escape_markdown(&format!("{}", error)),
+            action
+        );
+
+        self.send_message(&message).await
+    }
+
+    /// Send an alert for a strategy over its collateral ratio or PnL divergence limit
+    pub async fn send_risk_limit_alert(
+        &self,
+        strategy: &StrategyMetadata,
+        breach: &str,
+        action: &str,
+    ) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+
+        let message = format!(
+            "⚠️ *Risk Limit Crossed*\n\n\
+            ⏰ *Time:* {}\n\
+            🪙 *Token:* `{}`\n\
+            🆔 *Strategy:* `{}`\n\
+            👛 *Wallets:* {}\n\n\
+            📉 *Breach:*\n\
+            {}\n\n\
+            🛠 *Action:* {}",
+            Self::escape_markdown(&timestamp.to_string()),
+            Self::escape_markdown(&strategy.market_label()),
+            Self::escape_markdown(&strategy.id),
+            Self::escape_markdown(&format!("{:?}", strategy.wallet_ids)),
+            Self::escape_markdown(breach),
+            Self::escape_markdown(action)
+        );
+
+        self.send_message(&message).await
+    }
+
+    pub async fn send_collateral_rescue_alert(
+        &self,
+        strategy: &StrategyMetadata,
+        distance_pct: Decimal,
+        transfers: &[PlannedTransfer],
+    ) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+
+        let transfers = transfers
+            .iter()
+            .map(|t| format!("#{} → #{}: {:.2} USDC", t.from_wallet_id, t.to_wallet_id, t.amount_usdc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let message = format!(
+            "🛟 *Collateral Rescue*\n\n\
+            ⏰ *Time:* {}\n\
+            🪙 *Token:* `{}`\n\
+            🆔 *Strategy:* `{}`\n\
+            📏 *Distance to liquidation:* {}%\n\n\
+            💸 *Transfers:*\n\
+            {}",
+            Self::escape_markdown(&timestamp.to_string()),
+            Self::escape_markdown(&strategy.market_label()),
+            Self::escape_markdown(&strategy.id),
+            Self::escape_markdown(&format!("{:.2}", distance_pct)),
+            Self::escape_markdown(&transfers)
+        );
+
+        self.send_message(&message).await
+    }
+
+    pub async fn send_slippage_alert(
+        &self,
+        count: usize,
+        window_minutes: i64,
+        latest: &[SlippageBreach],
+    ) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+
+        let fills = latest
+            .iter()
+            .map(|b| format!("#{} {}: {:.1} bps", b.wallet_id, b.symbol, b.slippage_bps))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let message = format!(
+            "🐌 *Repeated Slippage Breaches*\n\n\
+            ⏰ *Time:* {}\n\
+            🔢 *Breaches:* {} in the last {} min\n\n\
+            📉 *Latest fills:*\n\
+            {}",
+            Self::escape_markdown(&timestamp.to_string()),
+            count,
+            window_minutes,
+            Self::escape_markdown(&fills)
+        );
+
+        self.send_message(&message).await
+    }
+
+    pub async fn send_reconciliation_alert(&self, report: &ReconciliationReport) -> Result<()> {
+        let timestamp = report.checked_at.format("%Y-%m-%d %H:%M:%S UTC");
+
+        let mut lines = report
+            .mismatches
+            .iter()
+            .take(20)
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>();
+        if report.mismatches.len() > lines.len() {
+            lines.push(format!("... and {} more", report.mismatches.len() - lines.len()));
+        }
+
+        let message = format!(
+            "🧾 *Position Reconciliation*\n\n\
+            ⏰ *Time:* {}\n\
+            🔢 *Mismatches:* {} \\({} remediated, {} failed\\)\n\n\
+            {}",
+            Self::escape_markdown(&timestamp.to_string()),
+            report.mismatches.len(),
+            report.remediated,
+            report.failed,
+            Self::escape_markdown(&lines.join("\n"))
+        );
+
+        self.send_message(&message).await
+    }
+
+    pub async fn send_circuit_breaker_alert(&self, reason: &str, flattened: bool) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+
+        let message = format!(
+            "🔌 *Circuit Breaker Tripped*\n\n\
+            ⏰ *Time:* {}\n\n\
+            📉 *Reason:*\n\
+            {}\n\n\
+            🛠 *Action:* {}\n\n\
+            No new strategies will be opened until the breaker is reset\\.",
+            Self::escape_markdown(&timestamp.to_string()),
+            Self::escape_markdown(reason),
+            Self::escape_markdown(if flattened { "all strategies closed" } else { "new strategies halted" })
+        );
+
+        self.send_message(&message).await
+    }
+
+    pub async fn send_kill_switch_alert(&self, reason: &str, source: &str, killed: usize, flattened: bool) -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+
+        let message = format!(
+            "🛑 *Kill Switch Activated*\n\n\
+            ⏰ *Time:* {}\n\
+            📡 *Source:* {}\n\n\
+            📝 *Reason:*\n\
+            {}\n\n\
+            🧹 *Strategies killed:* {}\n\
+            📉 *Positions:* {}\n\n\
+            Trading stays stopped until the kill switch is cleared\\.",
+            Self::escape_markdown(&timestamp.to_string()),
+            Self::escape_markdown(source),
+            Self::escape_markdown(reason),
+            killed,
+            Self::escape_markdown(if flattened { "all wallets flat" } else { "CLOSE FAILED, CHECK MANUALLY!" })
+        );
+
+        self.send_message(&message).await
+    }
+
     /// Escape special characters for Telegram MarkdownV2
     fn escape_markdown(text: &str) -> String {
         text.chars()
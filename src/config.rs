@@ -2,6 +2,7 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// Application configuration
@@ -10,6 +11,36 @@ pub struct AppConfig {
     pub trading: TradingConfig,
     pub monitoring: MonitoringConfig,
     pub exchanges: ExchangesConfig,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    #[serde(default)]
+    pub pair: PairConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub cost: CostConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    #[serde(default)]
+    pub exposure: ExposureConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub price: PriceConfig,
+    #[serde(default)]
+    pub rescue: RescueConfig,
+    #[serde(default)]
+    pub kill_switch: KillSwitchConfig,
+    #[serde(default)]
+    pub margin: MarginConfig,
+    #[serde(default)]
+    pub reconciliation: ReconciliationConfig,
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub leases: LeaseConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,15 +57,23 @@ pub struct TradingConfig {
     /// Maximum position duration in hours
     #[serde(default = "default_max_duration_hours")]
     pub max_duration_hours: u64,
-    /// Minimum collateral ratio before alerting (e.g., 1.5 for 150%)
+    /// Minimum collateral ratio of each wallet (e.g., 1.5 for 150%), enforced with `risk.limit_action`;
+    /// positions are sized to at most balance × leverage / min_collateral_ratio
     #[serde(default = "default_min_collateral_ratio")]
     pub min_collateral_ratio: f64,
-    /// Maximum PnL divergence threshold (percentage, e.g., 0.05 for 5%)
+    /// Maximum |long PnL + short PnL| of a strategy relative to its long notional (e.g., 0.05 for 5%),
+    /// enforced with `risk.limit_action`
     #[serde(default = "default_max_pnl_divergence")]
     pub max_pnl_divergence: f64,
-    /// Cooldown period between position openings (in seconds)
+    /// Cooldown before a wallet joins a new cohort after its strategy closed (in seconds)
     #[serde(default = "default_cooldown_seconds")]
     pub cooldown_seconds: u64,
+    /// Maximum number of tokens a wallet group splits its balance across (each token is hedged on its own)
+    #[serde(default = "default_max_tokens_per_group")]
+    pub max_tokens_per_group: usize,
+    /// Minimum per-wallet balance assigned to one token (fewer tokens are used for small wallets)
+    #[serde(default = "default_min_token_balance_usdc")]
+    pub min_token_balance_usdc: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +90,753 @@ pub struct MonitoringConfig {
     pub telegram_bot_token: String,
 }
 
+/// How each leg of a group is split into child orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// One market order per leg
+    Single,
+    /// Equal child orders per leg
+    Twap,
+    /// Randomly sized child orders per leg
+    Randomized,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionConfig {
+    /// Slicing mode used for opening and closing legs
+    #[serde(default = "default_execution_mode")]
+    pub mode: ExecutionMode,
+    /// Maximum number of child orders per leg
+    #[serde(default = "default_slices")]
+    pub slices: usize,
+    /// Minimum delay between child order rounds (in seconds)
+    #[serde(default = "default_min_slice_delay_seconds")]
+    pub min_slice_delay_seconds: u64,
+    /// Maximum delay between child order rounds (in seconds)
+    #[serde(default = "default_max_slice_delay_seconds")]
+    pub max_slice_delay_seconds: u64,
+    /// Minimum child order size in USDC (fewer slices are used for small legs)
+    #[serde(default = "default_min_slice_usdc")]
+    pub min_slice_usdc: f64,
+    /// Maximum allowed |long - short| filled notional during entry and exit (in USDC)
+    #[serde(default = "default_max_net_exposure_usdc")]
+    pub max_net_exposure_usdc: f64,
+    /// How many times a failed child order is retried with a fresh price and nonce
+    #[serde(default = "default_leg_retry_attempts")]
+    pub leg_retry_attempts: u32,
+    /// Delay between retries of a failed child order (in seconds)
+    #[serde(default = "default_leg_retry_delay_seconds")]
+    pub leg_retry_delay_seconds: u64,
+    /// Move a failed leg's notional to same-side wallets with spare capacity before rolling back
+    #[serde(default = "default_true")]
+    pub redistribute_failed_legs: bool,
+    /// Maximum adverse slippage of a child order against the order book mid (in bps).
+    /// A breach halts the group's remaining rounds and keeps only what was filled.
+    #[serde(default = "default_max_slippage_bps")]
+    pub max_slippage_bps: f64,
+    /// Number of slippage breaches within the alert window that triggers a Telegram alert
+    #[serde(default = "default_slippage_alert_count")]
+    pub slippage_alert_count: usize,
+    /// Window over which slippage breaches are counted (in minutes)
+    #[serde(default = "default_slippage_alert_window_minutes")]
+    pub slippage_alert_window_minutes: u64,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_execution_mode(),
+            slices: default_slices(),
+            min_slice_delay_seconds: default_min_slice_delay_seconds(),
+            max_slice_delay_seconds: default_max_slice_delay_seconds(),
+            min_slice_usdc: default_min_slice_usdc(),
+            max_net_exposure_usdc: default_max_net_exposure_usdc(),
+            leg_retry_attempts: default_leg_retry_attempts(),
+            leg_retry_delay_seconds: default_leg_retry_delay_seconds(),
+            redistribute_failed_legs: true,
+            max_slippage_bps: default_max_slippage_bps(),
+            slippage_alert_count: default_slippage_alert_count(),
+            slippage_alert_window_minutes: default_slippage_alert_window_minutes(),
+        }
+    }
+}
+
+/// Correlated pair strategies: long one token, short a beta-weighted basket of correlated tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairConfig {
+    /// Whether correlated pair strategies may be opened
+    #[serde(default)]
+    pub enabled: bool,
+    /// Probability that a group token is traded as a correlated pair instead of delta-neutral
+    #[serde(default = "default_pair_probability")]
+    pub probability: f64,
+    /// Maximum number of tokens in the short basket
+    #[serde(default = "default_pair_basket_size")]
+    pub basket_size: usize,
+    /// Candle resolution used for correlation and beta (e.g. "1h")
+    #[serde(default = "default_pair_candle_resolution")]
+    pub candle_resolution: String,
+    /// Number of candles used for correlation and beta
+    #[serde(default = "default_pair_lookback_candles")]
+    pub lookback_candles: usize,
+    /// Minimum correlation of returns between the long token and the basket
+    #[serde(default = "default_pair_min_correlation")]
+    pub min_correlation: f64,
+    /// Close the pair when its combined PnL drops below this percentage of the long notional
+    #[serde(default = "default_pair_spread_stop_pct")]
+    pub spread_stop_pct: f64,
+}
+
+impl Default for PairConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probability: default_pair_probability(),
+            basket_size: default_pair_basket_size(),
+            candle_resolution: default_pair_candle_resolution(),
+            lookback_candles: default_pair_lookback_candles(),
+            min_correlation: default_pair_min_correlation(),
+            spread_stop_pct: default_pair_spread_stop_pct(),
+        }
+    }
+}
+
+/// Continuous deployment: cohorts of idle wallets are opened while other cohorts are still running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// Maximum number of strategies open at the same time across all cohorts
+    #[serde(default = "default_max_concurrent_strategies")]
+    pub max_concurrent_strategies: usize,
+    /// Interval between scheduler ticks (monitoring and cohort launches)
+    #[serde(default = "default_scheduler_tick_seconds")]
+    pub tick_seconds: u64,
+    /// Minimum time between two cohort launches, so lifecycles stay staggered
+    #[serde(default = "default_min_cohort_interval_seconds")]
+    pub min_cohort_interval_seconds: u64,
+    /// Minimum number of wallets in a cohort
+    #[serde(default = "default_min_cohort_size")]
+    pub min_cohort_size: usize,
+    /// Maximum number of wallets in a cohort
+    #[serde(default = "default_max_cohort_size")]
+    pub max_cohort_size: usize,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_strategies: default_max_concurrent_strategies(),
+            tick_seconds: default_scheduler_tick_seconds(),
+            min_cohort_interval_seconds: default_min_cohort_interval_seconds(),
+            min_cohort_size: default_min_cohort_size(),
+            max_cohort_size: default_max_cohort_size(),
+        }
+    }
+}
+
+/// Risk limits applied by the monitor, with per-token overrides
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    /// Emergency close when a position is within this distance of liquidation (percent)
+    #[serde(default = "default_emergency_distance_pct")]
+    pub emergency_distance_pct: f64,
+    /// Warn when a position is within this distance of liquidation (percent)
+    #[serde(default = "default_warning_distance_pct")]
+    pub warning_distance_pct: f64,
+    /// Interval between risk checks (in seconds)
+    #[serde(default = "default_risk_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// Close a strategy that has been running for longer than this, whatever its close time (in hours)
+    #[serde(default = "default_max_lifetime_hours")]
+    pub max_lifetime_hours: u64,
+    /// Retry closing a strategy stuck in CLOSING for longer than this (in minutes)
+    #[serde(default = "default_closing_timeout_minutes")]
+    pub closing_timeout_minutes: u64,
+    /// What to do when `min_collateral_ratio` or `max_pnl_divergence` is crossed
+    #[serde(default = "default_limit_action")]
+    pub limit_action: LimitAction,
+    /// Share of every leg closed by one `reduce` action
+    #[serde(default = "default_reduce_fraction")]
+    pub reduce_fraction: f64,
+    /// Number of `reduce` actions after which a strategy still over its limits is closed
+    #[serde(default = "default_max_reductions")]
+    pub max_reductions: u32,
+    /// Minimum time between two actions (alerts or reductions) on the same strategy (in seconds)
+    #[serde(default = "default_limit_action_cooldown_seconds")]
+    pub limit_action_cooldown_seconds: u64,
+    /// Overrides keyed by Lighter symbol (e.g. "PENGU")
+    #[serde(default)]
+    pub tokens: HashMap<String, RiskOverride>,
+}
+
+/// Action taken when a strategy crosses its collateral ratio or PnL divergence limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitAction {
+    /// Send a Telegram alert only
+    Alert,
+    /// Close `reduce_fraction` of every leg (closing after `max_reductions`)
+    Reduce,
+    /// Close the strategy
+    Close,
+}
+
+impl std::fmt::Display for LimitAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitAction::Alert => write!(f, "alert"),
+            LimitAction::Reduce => write!(f, "reduce"),
+            LimitAction::Close => write!(f, "close"),
+        }
+    }
+}
+
+/// Per-token override of any `RiskConfig` limit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskOverride {
+    pub emergency_distance_pct: Option<f64>,
+    pub warning_distance_pct: Option<f64>,
+    pub check_interval_seconds: Option<u64>,
+    pub max_lifetime_hours: Option<u64>,
+    pub closing_timeout_minutes: Option<u64>,
+}
+
+/// Risk limits resolved for one strategy
+#[derive(Debug, Clone, Copy)]
+pub struct RiskLimits {
+    pub emergency_distance_pct: f64,
+    pub warning_distance_pct: f64,
+    pub check_interval_seconds: u64,
+    pub max_lifetime_hours: u64,
+    pub closing_timeout_minutes: u64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            emergency_distance_pct: default_emergency_distance_pct(),
+            warning_distance_pct: default_warning_distance_pct(),
+            check_interval_seconds: default_risk_check_interval_seconds(),
+            max_lifetime_hours: default_max_lifetime_hours(),
+            closing_timeout_minutes: default_closing_timeout_minutes(),
+            limit_action: default_limit_action(),
+            reduce_fraction: default_reduce_fraction(),
+            max_reductions: default_max_reductions(),
+            limit_action_cooldown_seconds: default_limit_action_cooldown_seconds(),
+            tokens: HashMap::new(),
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Limits for a single token, defaults overridden by its `[risk.tokens.<SYMBOL>]` entry
+    pub fn for_symbol(&self, symbol: &str) -> RiskLimits {
+        let token = self.tokens.get(symbol).cloned().unwrap_or_default();
+
+        RiskLimits {
+            emergency_distance_pct: token.emergency_distance_pct.unwrap_or(self.emergency_distance_pct),
+            warning_distance_pct: token.warning_distance_pct.unwrap_or(self.warning_distance_pct),
+            check_interval_seconds: token.check_interval_seconds.unwrap_or(self.check_interval_seconds),
+            max_lifetime_hours: token.max_lifetime_hours.unwrap_or(self.max_lifetime_hours),
+            closing_timeout_minutes: token.closing_timeout_minutes.unwrap_or(self.closing_timeout_minutes),
+        }
+    }
+
+    /// Most conservative limits across all markets of a strategy
+    pub fn for_symbols(&self, symbols: &[String]) -> RiskLimits {
+        symbols
+            .iter()
+            .map(|symbol| self.for_symbol(symbol))
+            .reduce(|a, b| RiskLimits {
+                emergency_distance_pct: a.emergency_distance_pct.max(b.emergency_distance_pct),
+                warning_distance_pct: a.warning_distance_pct.max(b.warning_distance_pct),
+                check_interval_seconds: a.check_interval_seconds.min(b.check_interval_seconds),
+                max_lifetime_hours: a.max_lifetime_hours.min(b.max_lifetime_hours),
+                closing_timeout_minutes: a.closing_timeout_minutes.min(b.closing_timeout_minutes),
+            })
+            .unwrap_or_else(|| self.for_symbol(""))
+    }
+
+    fn validate_limits(name: &str, limits: &RiskLimits) -> Result<()> {
+        if limits.emergency_distance_pct <= 0.0 {
+            anyhow::bail!("{}: emergency_distance_pct must be greater than 0", name);
+        }
+
+        if limits.warning_distance_pct < limits.emergency_distance_pct {
+            anyhow::bail!("{}: warning_distance_pct cannot be lower than emergency_distance_pct", name);
+        }
+
+        if limits.check_interval_seconds == 0 {
+            anyhow::bail!("{}: check_interval_seconds must be greater than 0", name);
+        }
+
+        if limits.max_lifetime_hours == 0 || limits.closing_timeout_minutes == 0 {
+            anyhow::bail!("{}: max_lifetime_hours and closing_timeout_minutes must be greater than 0", name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Triggers of the kill switch, which flattens every wallet and stays active until cleared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillSwitchConfig {
+    /// Environment variable that activates the kill switch when set (to anything but "0" or "false")
+    #[serde(default = "default_kill_switch_name")]
+    pub env_var: String,
+    /// File whose presence activates the kill switch (its content is used as the reason)
+    #[serde(default = "default_kill_switch_name")]
+    pub sentinel_file: String,
+    /// Accept /kill, /killstatus and /unkill from the Telegram alert chat
+    #[serde(default = "default_true")]
+    pub telegram_commands: bool,
+    /// Only the instance with this `leases.instance_id` polls Telegram for commands (every instance if unset)
+    #[serde(default)]
+    pub telegram_listener_instance: Option<String>,
+    /// Port of the admin API (only started when ADMIN_API_TOKEN is set)
+    #[serde(default = "default_admin_api_port")]
+    pub admin_api_port: u16,
+    /// Interval between checks of the environment variable and sentinel file (in seconds)
+    #[serde(default = "default_kill_switch_poll_seconds")]
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for KillSwitchConfig {
+    fn default() -> Self {
+        Self {
+            env_var: default_kill_switch_name(),
+            sentinel_file: default_kill_switch_name(),
+            telegram_commands: true,
+            telegram_listener_instance: None,
+            admin_api_port: default_admin_api_port(),
+            poll_interval_seconds: default_kill_switch_poll_seconds(),
+        }
+    }
+}
+
+impl KillSwitchConfig {
+    /// Whether the instance holding leases as `holder` polls Telegram for commands
+    ///
+    /// Telegram delivers each update to a single `getUpdates` poller, so with several
+    /// instances only one of them may listen.
+    pub fn polls_telegram(&self, holder: &str) -> bool {
+        self.telegram_commands
+            && self
+                .telegram_listener_instance
+                .as_deref()
+                .is_none_or(|instance| instance == holder)
+    }
+}
+
+/// How a market's margin is shared with the rest of the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarginMode {
+    /// Every position draws on the whole account collateral
+    Cross,
+    /// Each position only risks the margin allocated to it
+    Isolated,
+}
+
+impl std::fmt::Display for MarginMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarginMode::Cross => write!(f, "cross"),
+            MarginMode::Isolated => write!(f, "isolated"),
+        }
+    }
+}
+
+/// Margin settings applied to every market before a strategy opens positions on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginConfig {
+    /// Margin mode of every traded market
+    #[serde(default = "default_margin_mode")]
+    pub mode: MarginMode,
+    /// Per-market margin mode, keyed by Lighter symbol (e.g. "PENGU")
+    #[serde(default)]
+    pub tokens: HashMap<String, MarginMode>,
+    /// Times the account is read back to confirm new settings before giving up
+    #[serde(default = "default_margin_verify_attempts")]
+    pub verify_attempts: u32,
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_margin_mode(),
+            tokens: HashMap::new(),
+            verify_attempts: default_margin_verify_attempts(),
+        }
+    }
+}
+
+impl MarginConfig {
+    /// Margin mode of a single market, the default overridden by its `[margin.tokens]` entry
+    pub fn mode_for(&self, symbol: &str) -> MarginMode {
+        self.tokens.get(symbol).copied().unwrap_or(self.mode)
+    }
+}
+
+/// Remediation applied to one kind of reconciliation mismatch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcileAction {
+    /// Report the mismatch only
+    Alert,
+    /// Close the position (orphans) or the strategy that owns it
+    Close,
+}
+
+impl std::fmt::Display for ReconcileAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconcileAction::Alert => write!(f, "alert"),
+            ReconcileAction::Close => write!(f, "close"),
+        }
+    }
+}
+
+/// Comparison of stored strategies and positions with live exchange positions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationConfig {
+    /// Whether positions are reconciled at startup and on a schedule
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Interval between two reconciliations (in minutes)
+    #[serde(default = "default_reconciliation_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Relative difference between stored and live entry notional reported as a size mismatch (percent)
+    #[serde(default = "default_reconciliation_size_tolerance_pct")]
+    pub size_tolerance_pct: f64,
+    /// Live position owned by no active strategy
+    #[serde(default = "default_reconcile_close")]
+    pub orphan_action: ReconcileAction,
+    /// Stored position of an active strategy that is flat on the exchange
+    #[serde(default = "default_reconcile_alert")]
+    pub missing_action: ReconcileAction,
+    /// Live position on the wrong side, or whose size differs beyond the tolerance
+    #[serde(default = "default_reconcile_alert")]
+    pub mismatch_action: ReconcileAction,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: default_reconciliation_interval_minutes(),
+            size_tolerance_pct: default_reconciliation_size_tolerance_pct(),
+            orphan_action: default_reconcile_close(),
+            missing_action: default_reconcile_alert(),
+            mismatch_action: default_reconcile_alert(),
+        }
+    }
+}
+
+/// Database the bot keeps its state in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// PostgreSQL server at `DATABASE_URL`
+    Postgres,
+    /// SQLite database file at `storage.sqlite_path`
+    Sqlite,
+    /// Process memory, lost on exit
+    Memory,
+}
+
+impl std::fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageBackend::Postgres => write!(f, "postgres"),
+            StorageBackend::Sqlite => write!(f, "sqlite"),
+            StorageBackend::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// Where positions, strategies, ledgers and risk state are stored
+    #[serde(default = "default_storage_backend")]
+    pub backend: StorageBackend,
+    /// Database file of the SQLite backend (created if missing)
+    #[serde(default = "default_sqlite_path")]
+    pub sqlite_path: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_storage_backend(),
+            sqlite_path: default_sqlite_path(),
+        }
+    }
+}
+
+/// Periodic snapshots of every wallet's balances and Lighter points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// Whether wallet balances and points are snapshotted on a schedule
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Interval between two snapshots (in minutes)
+    #[serde(default = "default_snapshot_interval_minutes")]
+    pub interval_minutes: u64,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: default_snapshot_interval_minutes(),
+        }
+    }
+}
+
+/// Wallet leases, which let several instances share one database and each trade only the wallets it holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseConfig {
+    /// Whether the scheduler only plans and monitors with wallets it holds a lease on
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// How long a lease lasts without a heartbeat before another instance may claim the wallet (in seconds)
+    #[serde(default = "default_lease_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Interval between two renewals of the leases (in seconds)
+    #[serde(default = "default_lease_heartbeat_seconds")]
+    pub heartbeat_seconds: u64,
+    /// Name of this instance in `wallet_leases` (defaults to FLY_MACHINE_ID, then HOSTNAME)
+    #[serde(default)]
+    pub instance_id: Option<String>,
+    /// Maximum number of wallets this instance leases (every free wallet if unset)
+    #[serde(default)]
+    pub max_wallets: Option<usize>,
+}
+
+impl Default for LeaseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_seconds: default_lease_ttl_seconds(),
+            heartbeat_seconds: default_lease_heartbeat_seconds(),
+            instance_id: None,
+            max_wallets: None,
+        }
+    }
+}
+
+impl LeaseConfig {
+    /// Name this instance holds its leases under
+    ///
+    /// Fly machines keep their ID across restarts, so a restarted instance gets its wallets back
+    /// without waiting for its old leases to expire.
+    pub fn holder(&self) -> String {
+        self.instance_id
+            .clone()
+            .or_else(|| std::env::var("FLY_MACHINE_ID").ok())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| "local".to_string())
+    }
+}
+
+/// Collateral top-ups for legs near liquidation, tried before an emergency close
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescueConfig {
+    /// Whether at-risk wallets are topped up before the strategy is emergency closed
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Liquidation distance the top-up aims to restore (percent)
+    #[serde(default = "default_rescue_target_distance_pct")]
+    pub target_distance_pct: f64,
+    /// Share of a winning wallet's available balance that may be moved (0.0 - 1.0)
+    #[serde(default = "default_rescue_max_donor_share")]
+    pub max_donor_share: f64,
+    /// Wallet holding spare USDC, used after the winning wallets (never used for farming)
+    #[serde(default)]
+    pub reserve_wallet_id: Option<u8>,
+    /// Maximum USDC moved to a single wallet per rescue
+    #[serde(default = "default_rescue_max_transfer_usdc")]
+    pub max_transfer_usdc: f64,
+    /// Rescues per strategy before falling back to an emergency close
+    #[serde(default = "default_rescue_max_rescues")]
+    pub max_rescues: u32,
+    /// Fee charged by Lighter per transfer (in USDC)
+    #[serde(default)]
+    pub transfer_fee_usdc: f64,
+}
+
+impl Default for RescueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_distance_pct: default_rescue_target_distance_pct(),
+            max_donor_share: default_rescue_max_donor_share(),
+            reserve_wallet_id: None,
+            max_transfer_usdc: default_rescue_max_transfer_usdc(),
+            max_rescues: default_rescue_max_rescues(),
+            transfer_fee_usdc: 0.0,
+        }
+    }
+}
+
+/// Sanity checks applied to the order book mid before it is used for an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceConfig {
+    /// Maximum deviation of the mid price from the last trade (percent)
+    #[serde(default = "default_max_price_deviation_pct")]
+    pub max_deviation_pct: f64,
+    /// Reject the price if the market has not traded for this long (in seconds)
+    #[serde(default = "default_max_price_staleness_seconds")]
+    pub max_staleness_seconds: u64,
+}
+
+impl Default for PriceConfig {
+    fn default() -> Self {
+        Self {
+            max_deviation_pct: default_max_price_deviation_pct(),
+            max_staleness_seconds: default_max_price_staleness_seconds(),
+        }
+    }
+}
+
+/// Daily/weekly loss and drawdown limits that halt new strategies until manually reset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Whether equity is tracked and the breaker can trip
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Maximum trading loss over the last 24 hours (in USDC)
+    #[serde(default = "default_max_daily_loss_usdc")]
+    pub max_daily_loss_usdc: f64,
+    /// Maximum trading loss over the last 7 days (in USDC)
+    #[serde(default = "default_max_weekly_loss_usdc")]
+    pub max_weekly_loss_usdc: f64,
+    /// Maximum trading loss since the 7-day peak of total account value, as a percentage of the peak
+    #[serde(default = "default_max_drawdown_pct")]
+    pub max_drawdown_pct: f64,
+    /// Close every active strategy when the breaker trips
+    #[serde(default)]
+    pub flatten_on_trip: bool,
+    /// Interval between equity snapshots (in seconds)
+    #[serde(default = "default_snapshot_interval_seconds")]
+    pub snapshot_interval_seconds: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_daily_loss_usdc: default_max_daily_loss_usdc(),
+            max_weekly_loss_usdc: default_max_weekly_loss_usdc(),
+            max_drawdown_pct: default_max_drawdown_pct(),
+            flatten_on_trip: false,
+            snapshot_interval_seconds: default_snapshot_interval_seconds(),
+        }
+    }
+}
+
+/// What to do with a plan that would breach an exposure limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExposureAction {
+    /// Scale every leg down to fit the tightest limit
+    Scale,
+    /// Do not open the strategy
+    Reject,
+}
+
+/// Portfolio exposure limits checked before any order of a plan is sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureConfig {
+    /// Maximum gross notional (longs + shorts) held on one token across all wallets (in USDC)
+    #[serde(default = "default_max_token_notional_usdc")]
+    pub max_token_notional_usdc: f64,
+    /// Maximum gross notional held on one exchange (in USDC)
+    #[serde(default = "default_max_exchange_notional_usdc")]
+    pub max_exchange_notional_usdc: f64,
+    /// Maximum gross notional held overall (in USDC)
+    #[serde(default = "default_max_total_notional_usdc")]
+    pub max_total_notional_usdc: f64,
+    /// Maximum gross notional of a wallet relative to its account value
+    #[serde(default = "default_max_wallet_leverage")]
+    pub max_wallet_leverage: f64,
+    /// Maximum share of a market's open interest held across all wallets (0.01 = 1%)
+    #[serde(default = "default_max_open_interest_share")]
+    pub max_open_interest_share: f64,
+    /// What to do with a plan that would breach a limit
+    #[serde(default = "default_exposure_action")]
+    pub action: ExposureAction,
+    /// Smallest share of the planned notional a plan may be scaled down to
+    #[serde(default = "default_min_scale_factor")]
+    pub min_scale_factor: f64,
+}
+
+impl Default for ExposureConfig {
+    fn default() -> Self {
+        Self {
+            max_token_notional_usdc: default_max_token_notional_usdc(),
+            max_exchange_notional_usdc: default_max_exchange_notional_usdc(),
+            max_total_notional_usdc: default_max_total_notional_usdc(),
+            max_wallet_leverage: default_max_wallet_leverage(),
+            max_open_interest_share: default_max_open_interest_share(),
+            action: default_exposure_action(),
+            min_scale_factor: default_min_scale_factor(),
+        }
+    }
+}
+
+/// What to do with a plan whose estimated cost is above the ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostAction {
+    /// Do not open the strategy
+    Skip,
+    /// Scale every leg down until the cost fits, skipping if it never does
+    Resize,
+}
+
+/// Pre-trade cost estimation: fees, order book spread/slippage and funding over the duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostConfig {
+    /// Whether plans are estimated before execution
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Taker fee per fill (in basis points of notional)
+    #[serde(default)]
+    pub taker_fee_bps: f64,
+    /// Number of orders per side fetched to estimate spread and slippage
+    #[serde(default = "default_order_book_depth")]
+    pub order_book_depth: usize,
+    /// Hours of funding history averaged to project funding over the duration
+    #[serde(default = "default_funding_lookback_hours")]
+    pub funding_lookback_hours: usize,
+    /// Maximum estimated cost per unit of round-trip volume (in basis points)
+    #[serde(default = "default_max_cost_bps")]
+    pub max_cost_bps: f64,
+    /// What to do when the estimate is above `max_cost_bps`
+    #[serde(default = "default_cost_action")]
+    pub action: CostAction,
+    /// Smallest share of the planned notional a plan may be resized to
+    #[serde(default = "default_min_resize_factor")]
+    pub min_resize_factor: f64,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            taker_fee_bps: 0.0,
+            order_book_depth: default_order_book_depth(),
+            funding_lookback_hours: default_funding_lookback_hours(),
+            max_cost_bps: default_max_cost_bps(),
+            action: default_cost_action(),
+            min_resize_factor: default_min_resize_factor(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangesConfig {
     pub backpack: ExchangeCredentials,
@@ -100,6 +886,14 @@ fn default_cooldown_seconds() -> u64 {
     300 // 5 minutes
 }
 
+fn default_max_tokens_per_group() -> usize {
+    1
+}
+
+fn default_min_token_balance_usdc() -> f64 {
+    10.0
+}
+
 fn default_check_interval_seconds() -> u64 {
     60
 }
@@ -108,6 +902,274 @@ fn default_api_timeout_seconds() -> u64 {
     10
 }
 
+fn default_execution_mode() -> ExecutionMode {
+    ExecutionMode::Twap
+}
+
+fn default_slices() -> usize {
+    3
+}
+
+fn default_min_slice_delay_seconds() -> u64 {
+    5
+}
+
+fn default_max_slice_delay_seconds() -> u64 {
+    20
+}
+
+fn default_min_slice_usdc() -> f64 {
+    20.0
+}
+
+fn default_max_net_exposure_usdc() -> f64 {
+    25.0
+}
+
+fn default_leg_retry_attempts() -> u32 {
+    2
+}
+
+fn default_leg_retry_delay_seconds() -> u64 {
+    3
+}
+
+fn default_max_slippage_bps() -> f64 {
+    30.0
+}
+
+fn default_slippage_alert_count() -> usize {
+    3
+}
+
+fn default_slippage_alert_window_minutes() -> u64 {
+    60
+}
+
+fn default_pair_probability() -> f64 {
+    0.3
+}
+
+fn default_pair_basket_size() -> usize {
+    2
+}
+
+fn default_pair_candle_resolution() -> String {
+    "1h".to_string()
+}
+
+fn default_pair_lookback_candles() -> usize {
+    168 // 1 week of hourly candles
+}
+
+fn default_pair_min_correlation() -> f64 {
+    0.7
+}
+
+fn default_pair_spread_stop_pct() -> f64 {
+    3.0
+}
+
+fn default_max_concurrent_strategies() -> usize {
+    4
+}
+
+fn default_scheduler_tick_seconds() -> u64 {
+    15
+}
+
+fn default_min_cohort_interval_seconds() -> u64 {
+    600
+}
+
+fn default_min_cohort_size() -> usize {
+    3
+}
+
+fn default_max_cohort_size() -> usize {
+    5
+}
+
+fn default_order_book_depth() -> usize {
+    50
+}
+
+fn default_funding_lookback_hours() -> usize {
+    24
+}
+
+fn default_max_cost_bps() -> f64 {
+    10.0
+}
+
+fn default_cost_action() -> CostAction {
+    CostAction::Resize
+}
+
+fn default_min_resize_factor() -> f64 {
+    0.25
+}
+
+fn default_emergency_distance_pct() -> f64 {
+    13.0
+}
+
+fn default_warning_distance_pct() -> f64 {
+    16.0
+}
+
+fn default_risk_check_interval_seconds() -> u64 {
+    15
+}
+
+fn default_max_lifetime_hours() -> u64 {
+    24
+}
+
+fn default_closing_timeout_minutes() -> u64 {
+    10
+}
+
+fn default_limit_action() -> LimitAction {
+    LimitAction::Alert
+}
+
+fn default_reduce_fraction() -> f64 {
+    0.25
+}
+
+fn default_max_reductions() -> u32 {
+    3
+}
+
+fn default_limit_action_cooldown_seconds() -> u64 {
+    300
+}
+
+fn default_max_token_notional_usdc() -> f64 {
+    50_000.0
+}
+
+fn default_max_exchange_notional_usdc() -> f64 {
+    200_000.0
+}
+
+fn default_max_total_notional_usdc() -> f64 {
+    200_000.0
+}
+
+fn default_max_wallet_leverage() -> f64 {
+    5.0
+}
+
+fn default_max_open_interest_share() -> f64 {
+    0.01
+}
+
+fn default_exposure_action() -> ExposureAction {
+    ExposureAction::Scale
+}
+
+fn default_min_scale_factor() -> f64 {
+    0.25
+}
+
+fn default_kill_switch_name() -> String {
+    "KILL_SWITCH".to_string()
+}
+
+fn default_admin_api_port() -> u16 {
+    8080
+}
+
+fn default_kill_switch_poll_seconds() -> u64 {
+    5
+}
+
+fn default_margin_mode() -> MarginMode {
+    MarginMode::Cross
+}
+
+fn default_margin_verify_attempts() -> u32 {
+    5
+}
+
+fn default_reconciliation_interval_minutes() -> u64 {
+    30
+}
+
+fn default_reconciliation_size_tolerance_pct() -> f64 {
+    10.0
+}
+
+fn default_storage_backend() -> StorageBackend {
+    StorageBackend::Postgres
+}
+
+fn default_sqlite_path() -> String {
+    "perp-trader.db".to_string()
+}
+
+fn default_snapshot_interval_minutes() -> u64 {
+    60
+}
+
+fn default_lease_ttl_seconds() -> u64 {
+    120 // 2 minutes
+}
+
+fn default_lease_heartbeat_seconds() -> u64 {
+    30
+}
+
+fn default_reconcile_close() -> ReconcileAction {
+    ReconcileAction::Close
+}
+
+fn default_reconcile_alert() -> ReconcileAction {
+    ReconcileAction::Alert
+}
+
+fn default_rescue_target_distance_pct() -> f64 {
+    25.0
+}
+
+fn default_rescue_max_donor_share() -> f64 {
+    0.5
+}
+
+fn default_rescue_max_transfer_usdc() -> f64 {
+    1000.0
+}
+
+fn default_rescue_max_rescues() -> u32 {
+    2
+}
+
+fn default_max_price_deviation_pct() -> f64 {
+    1.0
+}
+
+fn default_max_price_staleness_seconds() -> u64 {
+    900
+}
+
+fn default_max_daily_loss_usdc() -> f64 {
+    250.0
+}
+
+fn default_max_weekly_loss_usdc() -> f64 {
+    750.0
+}
+
+fn default_max_drawdown_pct() -> f64 {
+    10.0
+}
+
+fn default_snapshot_interval_seconds() -> u64 {
+    300
+}
+
 fn default_true() -> bool {
     true
 }
@@ -176,6 +1238,183 @@ impl AppConfig {
             anyhow::bail!("max_pnl_divergence must be between 0.0 and 1.0");
         }
 
+        if self.trading.max_tokens_per_group == 0 {
+            anyhow::bail!("max_tokens_per_group must be at least 1");
+        }
+
+        if self.trading.min_token_balance_usdc <= 0.0 {
+            anyhow::bail!("min_token_balance_usdc must be greater than 0");
+        }
+
+        if self.execution.slices == 0 {
+            anyhow::bail!("execution.slices must be at least 1");
+        }
+
+        if self.execution.min_slice_delay_seconds > self.execution.max_slice_delay_seconds {
+            anyhow::bail!("min_slice_delay_seconds cannot be greater than max_slice_delay_seconds");
+        }
+
+        if self.execution.min_slice_usdc <= 0.0 {
+            anyhow::bail!("min_slice_usdc must be greater than 0");
+        }
+
+        if self.execution.max_net_exposure_usdc <= 0.0 {
+            anyhow::bail!("max_net_exposure_usdc must be greater than 0");
+        }
+
+        // Orders are limited to ±0.5% from the mid, so a larger slippage limit never triggers
+        if self.execution.max_slippage_bps <= 0.0 || self.execution.max_slippage_bps > 50.0 {
+            anyhow::bail!("execution.max_slippage_bps must be between 0 and 50");
+        }
+
+        if self.execution.slippage_alert_count == 0 {
+            anyhow::bail!("execution.slippage_alert_count must be at least 1");
+        }
+
+        if !(0.0..=1.0).contains(&self.pair.probability) {
+            anyhow::bail!("pair.probability must be between 0.0 and 1.0");
+        }
+
+        if self.pair.basket_size == 0 {
+            anyhow::bail!("pair.basket_size must be at least 1");
+        }
+
+        if self.pair.lookback_candles < 10 {
+            anyhow::bail!("pair.lookback_candles must be at least 10");
+        }
+
+        if !(-1.0..=1.0).contains(&self.pair.min_correlation) {
+            anyhow::bail!("pair.min_correlation must be between -1.0 and 1.0");
+        }
+
+        if self.pair.spread_stop_pct <= 0.0 {
+            anyhow::bail!("pair.spread_stop_pct must be greater than 0");
+        }
+
+        if self.scheduler.max_concurrent_strategies == 0 {
+            anyhow::bail!("scheduler.max_concurrent_strategies must be at least 1");
+        }
+
+        if self.scheduler.tick_seconds == 0 {
+            anyhow::bail!("scheduler.tick_seconds must be greater than 0");
+        }
+
+        if self.scheduler.min_cohort_size < 2 {
+            anyhow::bail!("scheduler.min_cohort_size must be at least 2 (one long and one short)");
+        }
+
+        if self.scheduler.min_cohort_size > self.scheduler.max_cohort_size {
+            anyhow::bail!("min_cohort_size cannot be greater than max_cohort_size");
+        }
+
+        RiskConfig::validate_limits("risk", &self.risk.for_symbol(""))?;
+        for symbol in self.risk.tokens.keys() {
+            RiskConfig::validate_limits(&format!("risk.tokens.{}", symbol), &self.risk.for_symbol(symbol))?;
+        }
+
+        if self.risk.reduce_fraction <= 0.0 || self.risk.reduce_fraction >= 1.0 {
+            anyhow::bail!("risk.reduce_fraction must be between 0.0 and 1.0 (exclusive)");
+        }
+
+        if self.trading.max_duration_hours > self.risk.max_lifetime_hours {
+            anyhow::bail!("max_duration_hours cannot be greater than risk.max_lifetime_hours");
+        }
+
+        if self.exposure.max_token_notional_usdc <= 0.0
+            || self.exposure.max_exchange_notional_usdc <= 0.0
+            || self.exposure.max_total_notional_usdc <= 0.0
+        {
+            anyhow::bail!("exposure notional limits must be greater than 0");
+        }
+
+        if self.exposure.max_wallet_leverage < self.trading.max_leverage {
+            anyhow::bail!("exposure.max_wallet_leverage cannot be lower than trading.max_leverage");
+        }
+
+        if self.exposure.max_open_interest_share <= 0.0 || self.exposure.max_open_interest_share > 1.0 {
+            anyhow::bail!("exposure.max_open_interest_share must be between 0.0 (exclusive) and 1.0");
+        }
+
+        if self.exposure.min_scale_factor <= 0.0 || self.exposure.min_scale_factor > 1.0 {
+            anyhow::bail!("exposure.min_scale_factor must be between 0.0 (exclusive) and 1.0");
+        }
+
+        if self.kill_switch.poll_interval_seconds == 0 {
+            anyhow::bail!("kill_switch.poll_interval_seconds must be greater than 0");
+        }
+
+        if self.reconciliation.interval_minutes == 0 || self.reconciliation.size_tolerance_pct <= 0.0 {
+            anyhow::bail!("reconciliation.interval_minutes and size_tolerance_pct must be greater than 0");
+        }
+
+        if self.storage.backend == StorageBackend::Sqlite && self.storage.sqlite_path.trim().is_empty() {
+            anyhow::bail!("storage.sqlite_path must be set for the sqlite backend");
+        }
+
+        if self.snapshots.interval_minutes == 0 {
+            anyhow::bail!("snapshots.interval_minutes must be greater than 0");
+        }
+
+        if self.leases.heartbeat_seconds == 0 || self.leases.heartbeat_seconds >= self.leases.ttl_seconds {
+            anyhow::bail!("leases.heartbeat_seconds must be greater than 0 and less than leases.ttl_seconds");
+        }
+
+        if self.leases.max_wallets.is_some_and(|max| max < self.scheduler.min_cohort_size) {
+            anyhow::bail!("leases.max_wallets must be at least scheduler.min_cohort_size");
+        }
+
+        if self.margin.verify_attempts == 0 {
+            anyhow::bail!("margin.verify_attempts must be greater than 0");
+        }
+
+        if self.rescue.target_distance_pct <= self.risk.emergency_distance_pct || self.rescue.target_distance_pct >= 100.0 {
+            anyhow::bail!("rescue.target_distance_pct must be between risk.emergency_distance_pct and 100");
+        }
+
+        if self.rescue.max_donor_share <= 0.0 || self.rescue.max_donor_share > 1.0 {
+            anyhow::bail!("rescue.max_donor_share must be between 0.0 and 1.0");
+        }
+
+        if self.rescue.max_transfer_usdc <= 0.0 || self.rescue.transfer_fee_usdc < 0.0 {
+            anyhow::bail!("rescue.max_transfer_usdc must be greater than 0 and transfer_fee_usdc cannot be negative");
+        }
+
+        if self.price.max_deviation_pct <= 0.0 {
+            anyhow::bail!("price.max_deviation_pct must be greater than 0");
+        }
+
+        if self.price.max_staleness_seconds < 60 {
+            anyhow::bail!("price.max_staleness_seconds must be at least 60 (one candle)");
+        }
+
+        if self.circuit_breaker.max_daily_loss_usdc <= 0.0 || self.circuit_breaker.max_weekly_loss_usdc <= 0.0 {
+            anyhow::bail!("circuit_breaker loss limits must be greater than 0");
+        }
+
+        if self.circuit_breaker.max_drawdown_pct <= 0.0 || self.circuit_breaker.max_drawdown_pct >= 100.0 {
+            anyhow::bail!("circuit_breaker.max_drawdown_pct must be between 0 and 100");
+        }
+
+        if self.circuit_breaker.snapshot_interval_seconds == 0 {
+            anyhow::bail!("circuit_breaker.snapshot_interval_seconds must be greater than 0");
+        }
+
+        if self.cost.taker_fee_bps < 0.0 {
+            anyhow::bail!("cost.taker_fee_bps cannot be negative");
+        }
+
+        if self.cost.order_book_depth == 0 {
+            anyhow::bail!("cost.order_book_depth must be at least 1");
+        }
+
+        if self.cost.max_cost_bps <= 0.0 {
+            anyhow::bail!("cost.max_cost_bps must be greater than 0");
+        }
+
+        if self.cost.min_resize_factor <= 0.0 || self.cost.min_resize_factor > 1.0 {
+            anyhow::bail!("cost.min_resize_factor must be between 0.0 (exclusive) and 1.0");
+        }
+
         Ok(())
     }
 
@@ -189,6 +1428,11 @@ impl AppConfig {
         Duration::from_secs(self.monitoring.api_timeout_seconds)
     }
 
+    /// Get minimum interval between cohort launches as Duration
+    pub fn cohort_interval(&self) -> Duration {
+        Duration::from_secs(self.scheduler.min_cohort_interval_seconds)
+    }
+
     /// Get cooldown period as Duration
     pub fn cooldown_period(&self) -> Duration {
         Duration::from_secs(self.trading.cooldown_seconds)
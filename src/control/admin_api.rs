@@ -0,0 +1,180 @@
+use crate::{
+    error::TradingError,
+    risk::kill_switch::{KillSwitch, KillSwitchSource},
+};
+
+use serde_json::json;
+use std::time::Duration;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Take},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+const MAX_BODY_BYTES: usize = 4096;
+/// Cap on the whole request (request line, headers and body)
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+/// Time a client has to send its whole request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Minimal HTTP API to inspect, trigger and clear the kill switch
+///
+/// * `GET /health` - Liveness check (no authentication)
+/// * `GET /kill-switch` - Current state
+/// * `POST /kill-switch` - Activate, with the request body as the reason
+/// * `DELETE /kill-switch` - Clear
+///
+/// Every kill switch route requires `Authorization: Bearer <ADMIN_API_TOKEN>`.
+pub struct AdminApi {
+    kill_switch: KillSwitch,
+    token: String,
+}
+
+/// Status code and JSON body of a response
+type Response = (u16, serde_json::Value);
+
+impl AdminApi {
+    pub fn new(kill_switch: KillSwitch, token: String) -> Self {
+        Self { kill_switch, token }
+    }
+
+    /// Accept connections until the listener fails
+    pub async fn serve(self, port: u16) -> Result<(), TradingError> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| TradingError::ConfigError(format!("Cannot bind admin API to port {}: {}", port, e)))?;
+        info!("🛂 Admin API listening on port {}", port);
+
+        let api = std::sync::Arc::new(self);
+        loop {
+            let (stream, addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!("⚠️ Admin API failed to accept a connection: {}", e);
+                    continue;
+                }
+            };
+
+            let api = api.clone();
+            tokio::spawn(async move {
+                if let Err(e) = api.handle(stream).await {
+                    warn!("⚠️ Admin API request from {} failed: {}", addr, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, stream: TcpStream) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+        let (method, path, authorization, body) = timeout(REQUEST_TIMEOUT, Self::read_request(&mut reader))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "request not received in time"))??;
+
+        let (status, payload) = self.route(&method, &path, authorization.as_deref(), &body).await;
+        let payload = payload.to_string();
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            reason_phrase(status),
+            payload.len(),
+            payload
+        );
+
+        let mut stream = reader.into_inner().into_inner();
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    /// Read the method, path, authorization header and body of a request
+    async fn read_request(
+        reader: &mut BufReader<Take<TcpStream>>,
+    ) -> std::io::Result<(String, String, Option<String>, String)> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut authorization = None;
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "authorization" => authorization = Some(value.trim().to_string()),
+                    "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length.min(MAX_BODY_BYTES)];
+        reader.read_exact(&mut body).await?;
+        let body = String::from_utf8_lossy(&body).trim().to_string();
+
+        Ok((method, path, authorization, body))
+    }
+
+    async fn route(&self, method: &str, path: &str, authorization: Option<&str>, body: &str) -> Response {
+        if method == "GET" && path == "/health" {
+            return (200, json!({ "status": "ok" }));
+        }
+
+        if path != "/kill-switch" {
+            return (404, json!({ "error": "not found" }));
+        }
+
+        if !self.is_authorized(authorization) {
+            return (401, json!({ "error": "unauthorized" }));
+        }
+
+        let result = match method {
+            "GET" => self.kill_switch.state().await.map(|state| json!({ "state": state })),
+            "POST" => {
+                let reason = if body.is_empty() { "Triggered from the admin API" } else { body };
+                self.kill_switch
+                    .trigger(reason, KillSwitchSource::Api)
+                    .await
+                    .map(|activated| json!({ "activated": activated }))
+            }
+            "DELETE" => self.kill_switch.clear().await.map(|cleared| json!({ "cleared": cleared })),
+            _ => return (405, json!({ "error": "method not allowed" })),
+        };
+
+        match result {
+            Ok(payload) => (200, payload),
+            Err(e) => {
+                error!("❌ Admin API {} {} failed: {}", method, path, e);
+                (500, json!({ "error": e.to_string() }))
+            }
+        }
+    }
+
+    /// Compare the bearer token without short-circuiting on the first mismatch
+    fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+            return false;
+        };
+
+        token.len() == self.token.len()
+            && token
+                .bytes()
+                .zip(self.token.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
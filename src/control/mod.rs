@@ -0,0 +1,35 @@
+pub mod admin_api;
+pub mod telegram_commands;
+
+use crate::{config::AppConfig, risk::kill_switch::KillSwitch};
+
+/// Start every kill switch trigger in the background: environment variable and
+/// sentinel file polling, Telegram commands (on the listener instance only) and the admin API
+pub fn spawn_kill_switch_triggers(kill_switch: &KillSwitch, config: &AppConfig) {
+    tokio::spawn(kill_switch.clone().watch_external_triggers());
+
+    let holder = config.leases.holder();
+    if kill_switch.config().polls_telegram(&holder) {
+        let listener = telegram_commands::TelegramCommands::new(
+            kill_switch.clone(),
+            &config.monitoring.telegram_bot_token,
+            config.monitoring.telegram_chat_id,
+        );
+        tokio::spawn(listener.run());
+    } else if kill_switch.config().telegram_commands {
+        info!("📵 Telegram commands are handled by another instance, not by {}", holder);
+    }
+
+    match std::env::var("ADMIN_API_TOKEN") {
+        Ok(token) if !token.is_empty() => {
+            let api = admin_api::AdminApi::new(kill_switch.clone(), token);
+            let port = kill_switch.config().admin_api_port;
+            tokio::spawn(async move {
+                if let Err(e) = api.serve(port).await {
+                    error!("❌ Admin API stopped: {}", e);
+                }
+            });
+        }
+        _ => warn!("⚠️ ADMIN_API_TOKEN is not set, the admin API is disabled"),
+    }
+}
@@ -0,0 +1,109 @@
+use crate::risk::kill_switch::{KillSwitch, KillSwitchSource};
+
+use teloxide::{
+    prelude::*,
+    types::UpdateKind,
+};
+use tokio::time::{sleep, Duration};
+
+/// Long-polling timeout of getUpdates, below the bot client's request timeout
+const POLL_TIMEOUT_SECONDS: u32 = 10;
+
+/// Kill switch commands accepted from the Telegram alert chat
+///
+/// * `/kill <reason>` - Activate the kill switch
+/// * `/killstatus` - Show the kill switch state
+/// * `/unkill` - Clear the kill switch
+///
+/// Messages from any other chat are ignored.
+pub struct TelegramCommands {
+    kill_switch: KillSwitch,
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramCommands {
+    pub fn new(kill_switch: KillSwitch, bot_token: &str, chat_id: i64) -> Self {
+        Self {
+            kill_switch,
+            bot: Bot::new(bot_token),
+            chat_id: ChatId(chat_id),
+        }
+    }
+
+    /// Poll for commands until the process exits
+    pub async fn run(self) {
+        info!("🤖 Listening for kill switch commands on Telegram");
+        let mut offset = 0;
+
+        loop {
+            let updates = match self.bot.get_updates().offset(offset).timeout(POLL_TIMEOUT_SECONDS).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    warn!("⚠️ Failed to poll Telegram commands: {}", e);
+                    sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            for update in updates {
+                offset = update.id.as_offset();
+
+                let UpdateKind::Message(message) = update.kind else {
+                    continue;
+                };
+                if message.chat.id != self.chat_id {
+                    continue;
+                }
+                let Some(text) = message.text() else {
+                    continue;
+                };
+
+                if let Some(reply) = self.handle(text).await {
+                    if let Err(e) = self.bot.send_message(self.chat_id, reply).await {
+                        warn!("⚠️ Failed to reply to Telegram command: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Execute a command and return the reply, or `None` for other messages
+    async fn handle(&self, text: &str) -> Option<String> {
+        let (command, argument) = text.trim().split_once(' ').unwrap_or((text.trim(), ""));
+        // Commands may be addressed to the bot, e.g. /kill@my_bot
+        let command = command.split('@').next().unwrap_or(command);
+
+        let reply = match command {
+            "/kill" => {
+                let reason = match argument.trim() {
+                    "" => "Triggered from Telegram",
+                    reason => reason,
+                };
+                match self.kill_switch.trigger(reason, KillSwitchSource::Telegram).await {
+                    Ok(true) => format!("🛑 Kill switch activated: {}\nAll wallets are being flattened.", reason),
+                    Ok(false) => "🛑 Kill switch is already active.".to_string(),
+                    Err(e) => format!("❌ Failed to activate the kill switch: {}", e),
+                }
+            }
+            "/unkill" => match self.kill_switch.clear().await {
+                Ok(true) => "🟢 Kill switch cleared, trading resumes.".to_string(),
+                Ok(false) => "Kill switch was not active.".to_string(),
+                Err(e) => format!("❌ Failed to clear the kill switch: {}", e),
+            },
+            "/killstatus" => match self.kill_switch.state().await {
+                Ok(state) if state.active => format!(
+                    "🛑 Kill switch active since {} ({}): {}",
+                    state.triggered_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                    state.source.unwrap_or_default(),
+                    state.reason.unwrap_or_default()
+                ),
+                Ok(_) => "🟢 Kill switch is not active.".to_string(),
+                Err(e) => format!("❌ Failed to read the kill switch: {}", e),
+            },
+            _ => return None,
+        };
+
+        Some(reply)
+    }
+}
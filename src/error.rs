@@ -1,5 +1,7 @@
 #![allow(unused)]
+use chrono::{DateTime, Utc};
 use thiserror::Error;
+use crate::trader::strategy::StrategyStatus;
 
 #[derive(Error, Debug)]
 pub enum RequestError {
@@ -42,6 +44,10 @@ pub enum TradingError {
     #[error("Order execution failed: {0}")]
     OrderExecutionFailed(String),
 
+    /// The exchange accepted the order, but it could not be confirmed and may still fill
+    #[error("Order unconfirmed: {0}")]
+    OrderUnconfirmed(String),
+
     #[error("Invalid nonce: {0}")]
     InvalidNonce(String),
 
@@ -57,6 +63,24 @@ pub enum TradingError {
     #[error("Market data unavailable: {0}")]
     MarketDataUnavailable(String),
 
+    #[error("Stale price: {0}")]
+    StalePrice(String),
+
+    #[error("Price deviation: {0}")]
+    PriceDeviation(String),
+
+    #[error("Kill switch active: {0}")]
+    KillSwitchActive(String),
+
+    #[error("Circuit breaker tripped: {0}")]
+    CircuitBreakerTripped(String),
+
+    #[error("Exposure limit exceeded: {0}")]
+    ExposureLimitExceeded(String),
+
+    #[error("Cost ceiling exceeded: {0}")]
+    CostCeilingExceeded(String),
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
@@ -66,6 +90,9 @@ pub enum TradingError {
     #[error("Storage error: {0}")]
     StorageError(#[from] sqlx::Error),
 
+    #[error("Migration error: {0}")]
+    MigrationError(#[from] sqlx::migrate::MigrateError),
+
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
 
@@ -74,5 +101,29 @@ pub enum TradingError {
 
     #[error("Signing error: {0}")]
     SigningError(String),
+
+    #[error("Wallet lease unavailable: {0}")]
+    WalletLeaseUnavailable(String),
+
+    #[error("Strategy not found: {0}")]
+    StrategyNotFound(String),
+
+    #[error("Illegal status transition for strategy {id}: {from} -> {to}")]
+    IllegalStatusTransition { id: String, from: StrategyStatus, to: StrategyStatus },
+
+    #[error("Strategy {id} changed concurrently: expected {expected} updated at {updated_at}, found {found}")]
+    StaleStrategyStatus {
+        id: String,
+        expected: StrategyStatus,
+        updated_at: DateTime<Utc>,
+        found: StrategyStatus,
+    },
+}
+
+impl TradingError {
+    /// Check if the error means another pass or instance already moved the strategy on
+    pub fn is_strategy_conflict(&self) -> bool {
+        matches!(self, TradingError::IllegalStatusTransition { .. } | TradingError::StaleStrategyStatus { .. })
+    }
 }
 
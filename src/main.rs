@@ -10,6 +10,8 @@ mod trader;
 mod storage;
 mod helpers;
 mod alert;
+mod risk;
+mod control;
 mod test;
 
 use std::fs::{File, OpenOptions};
@@ -17,12 +19,10 @@ use std::io::{BufReader, Write};
 use std::time::Duration;
 use anyhow::{Result, Context};
 use inquire::{Select, Confirm};
-use rand::Rng;
 use rust_decimal::Decimal;
-use tokio::time;
 use serde_json::Value;
 
-use crate::{perp::lighter::{client::LighterClient}, trader::client::TraderClient, storage::database::Database, helpers::encode::encrypt_private_key};
+use crate::{config::{AppConfig, StorageBackend}, perp::lighter::{client::LighterClient}, trader::{client::TraderClient, scheduler::CohortScheduler}, storage::{database::Database, Storage}, risk::kill_switch::KillSwitchSource, helpers::encode::encrypt_private_key};
 use colored::*;
 
 /// Load all available wallet IDs from api-keys.json
@@ -183,6 +183,76 @@ async fn fill_empty_private_keys() -> Result<()> {
     Ok(())
 }
 
+/// Handle the `migrate status` and `migrate rollback [version]` commands
+///
+/// Migrations manage the Postgres schema; the SQLite schema is applied when the bot connects.
+async fn run_migrate_command(args: &[String]) -> Result<()> {
+    let backend = AppConfig::load()?.storage.backend;
+    if backend != StorageBackend::Postgres {
+        anyhow::bail!("migrate only applies to the postgres storage backend (configured: {})", backend);
+    }
+
+    let pool = storage::init_pool().await?;
+
+    match args.first().map(String::as_str) {
+        Some("status") | None => {
+            for migration in storage::migrations::status(&pool).await? {
+                let state = match migration.installed_on {
+                    Some(installed_on) if migration.modified => format!("applied {} (MODIFIED since)", installed_on.format("%Y-%m-%d %H:%M:%S UTC")),
+                    Some(installed_on) => format!("applied {}", installed_on.format("%Y-%m-%d %H:%M:%S UTC")),
+                    None => "pending".to_string(),
+                };
+                info!("{:>4} | {:<40} | {}", migration.version, migration.description, state);
+            }
+        }
+        Some("rollback") => {
+            let target = args
+                .get(1)
+                .map(|v| v.parse::<i64>().context("Rollback target must be a migration version"))
+                .transpose()?;
+
+            let reverted = storage::migrations::rollback(&pool, target).await?;
+            if reverted.is_empty() {
+                info!("Nothing to roll back");
+            } else {
+                info!("✅ Reverted migrations {:?}", reverted);
+            }
+        }
+        Some(other) => anyhow::bail!("Unknown migrate command: {} (expected status or rollback [version])", other),
+    }
+
+    Ok(())
+}
+
+/// Handle the `timeline <strategy_id>` command: print a strategy's journal, oldest event first
+async fn run_timeline_command(args: &[String]) -> Result<()> {
+    let strategy_id = args.first().context("Usage: timeline <strategy_id>")?;
+    let storage = Storage::connect(&AppConfig::load()?.storage).await?;
+
+    if let Some(strategy) = storage.strategies.get_strategy_metadata(strategy_id).await? {
+        info!(
+            "Strategy {} | {} | {} | wallets: {:?} | status: {} | realized PnL: {}",
+            strategy.id,
+            strategy.strategy_type,
+            strategy.market_label(),
+            strategy.wallet_ids,
+            strategy.status,
+            strategy.realized_pnl.map_or("-".to_string(), |pnl| format!("{:.2} USDC", pnl))
+        );
+    }
+
+    let events = storage.events.get_strategy_events(strategy_id).await?;
+
+    if events.is_empty() {
+        info!("No events recorded for strategy {}", strategy_id);
+    }
+    for event in events {
+        info!("{}", event.timeline_line());
+    }
+
+    Ok(())
+}
+
 /// Detect if running on Fly.io
 fn is_running_on_flyio() -> bool {
     std::env::var("FLY_APP_NAME").is_ok() || 
@@ -217,6 +287,14 @@ async fn main() -> Result<()> {
 
     info!("🚀 Starting perp-trader application...");
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return run_migrate_command(&args[1..]).await;
+    }
+    if args.first().map(String::as_str) == Some("timeline") {
+        return run_timeline_command(&args[1..]).await;
+    }
+
     // Load all available wallets
     let wallet_ids = load_all_wallet_ids()?;
 
@@ -236,10 +314,11 @@ async fn main() -> Result<()> {
     println!("\n✅ Connected Wallets: {}", wallet_ids.len());
     println!("   Wallet IDs: {:?}\n", wallet_ids);
 
-    // Create database connection pool
-    info!("🔌 Connecting to database...");
-    let pool = storage::init_pool().await?;
-    info!("✅ Database connected successfully");
+    // Connect to the configured storage backend
+    let storage_config = AppConfig::load()?.storage;
+    info!("🔌 Connecting to {} storage...", storage_config.backend);
+    let storage = Storage::connect(&storage_config).await?;
+    info!("✅ Storage connected successfully");
 
     enum Action {
         FarmLighter,
@@ -247,6 +326,9 @@ async fn main() -> Result<()> {
         CloseAllPositions,
         ShowAllWalletsBalances,
         ShowAllWalletsPoints,
+        ResetCircuitBreaker,
+        ActivateKillSwitch,
+        ClearKillSwitch,
         ClearAllLighterApiKeys,
         FillEmptyPrivateKeys,
     }
@@ -264,6 +346,9 @@ async fn main() -> Result<()> {
             "💸 Close all active positions",
             "💰 Show all wallets balances",
             "🏆 Show all wallets points",
+            "🔌 Reset circuit breaker",
+            "🛑 Activate kill switch",
+            "🟢 Clear kill switch",
             "🧹 Clear all lighter_api_keys from JSON",
             "🔑 Fill empty private keys from MongoDB",
         ];
@@ -278,8 +363,11 @@ async fn main() -> Result<()> {
             s if s == options[2] => Action::CloseAllPositions,
             s if s == options[3] => Action::ShowAllWalletsBalances,
             s if s == options[4] => Action::ShowAllWalletsPoints,
-            s if s == options[5] => Action::ClearAllLighterApiKeys,
-            s if s == options[6] => Action::FillEmptyPrivateKeys,
+            s if s == options[5] => Action::ResetCircuitBreaker,
+            s if s == options[6] => Action::ActivateKillSwitch,
+            s if s == options[7] => Action::ClearKillSwitch,
+            s if s == options[8] => Action::ClearAllLighterApiKeys,
+            s if s == options[9] => Action::FillEmptyPrivateKeys,
             _ => {
                 warn!("Invalid selection");
                 return Ok(());
@@ -293,6 +381,9 @@ async fn main() -> Result<()> {
             Action::CloseAllPositions => "Close all open positions?",
             Action::ShowAllWalletsBalances => "Show all wallets balances?",
             Action::ShowAllWalletsPoints => "Show all wallets points?",
+            Action::ResetCircuitBreaker => "Reset the circuit breaker and allow new strategies?",
+            Action::ActivateKillSwitch => "Activate the kill switch? This cancels all orders and closes every position on every wallet.",
+            Action::ClearKillSwitch => "Clear the kill switch and allow trading again?",
             Action::ClearAllLighterApiKeys => "Clear all lighter_api_key fields in api-keys.json?",
             Action::FillEmptyPrivateKeys => "Fill empty private keys from MongoDB?",
         };
@@ -322,7 +413,7 @@ async fn main() -> Result<()> {
 
     // Initialize trader client
     info!("Initializing trader client with {} wallets...", wallet_ids.len());
-    let trader_client = TraderClient::new(wallet_ids.clone(), pool.clone())
+    let trader_client = TraderClient::new(wallet_ids.clone(), storage)
         .await
         .context("Failed to create trader client")?;
 
@@ -337,32 +428,34 @@ async fn main() -> Result<()> {
             trader_client.close_all_positions_on_lighter_for_all_wallets().await?;
             info!("✅ All positions closed");
         }
-        Action::FarmLighter => {            
-            let mut rng = rand::thread_rng();
-            let mut i = 0;
-
-            
-            loop {
-                // Check for and retry any failed strategies from previous runs
-                info!("🔍 Checking for failed strategies from previous runs...");
-                trader_client.retry_failed_strategies().await?;
-                
-                let loop_sleep_minutes = rng.gen_range(30..=80);
-                info!("#{} | Strategy starting...", i);
-
-                trader_client.farm_points_on_lighter_from_multiple_wallets().await?;
-
-                let active_strategies = trader_client.get_active_strategies().await?;
-
-                if !active_strategies.is_empty() {
-                    trader_client.monitor_and_close_strategies(active_strategies).await?;
-                }
-
-                i += 1;
-                info!("#{} | Sleeping for {} minutes", i, loop_sleep_minutes);
-                time::sleep(Duration::from_secs(loop_sleep_minutes * 60)).await;
+        Action::ResetCircuitBreaker => {
+            match trader_client.reset_circuit_breaker().await? {
+                Some(reason) => info!("✅ Circuit breaker reset (was tripped: {})", reason),
+                None => info!("Circuit breaker was not tripped"),
+            }
+        }
+        Action::ActivateKillSwitch => {
+            let kill_switch = trader_client.kill_switch();
+            kill_switch.trigger("Activated from the menu", KillSwitchSource::Menu).await?;
+            if let Some(state) = kill_switch.active_state().await? {
+                trader_client.engage_kill_switch(&state).await?;
+            }
+            info!("🛑 Kill switch active, clear it to resume trading");
+        }
+        Action::ClearKillSwitch => {
+            if trader_client.kill_switch().clear().await? {
+                info!("✅ Kill switch cleared");
+            } else {
+                info!("Kill switch was not active");
             }
         }
+        Action::FarmLighter => {
+            control::spawn_kill_switch_triggers(trader_client.kill_switch(), trader_client.config());
+
+            // Cohorts of idle wallets are opened while earlier cohorts are still running
+            let mut scheduler = CohortScheduler::new(&trader_client);
+            scheduler.run().await?;
+        }
         Action::ShowAllWalletsBalances => {
             let wallet_balances = trader_client.fetch_wallet_balances_on_lighter().await?;
             for i in 0..wallet_balances.len() {
@@ -31,6 +31,8 @@ pub struct Position {
     pub close_at: DateTime<Utc>,
     /// When the position was actually closed
     pub closed_at: Option<DateTime<Utc>>,
+    /// Mark price when the position was closed
+    pub close_price: Option<Decimal>,
     /// Realized PnL when closed
     pub realized_pnl: Option<Decimal>,
     /// Last updated timestamp
@@ -149,6 +149,18 @@ impl Token {
         }
     }
 
+    /// Resolve a token from the symbol string used on the given exchange
+    pub fn from_symbol_string(exchange: Exchange, symbol: &str) -> Result<Token, TradingError> {
+        [
+            Self::eth(), Self::sol(), Self::hype(), Self::bnb(), Self::xrp(),
+            Self::aave(), Self::ena(), Self::zk(), Self::dydx(), Self::pengu(),
+            Self::ton(), Self::eden(), Self::gmx(), Self::grass(), Self::render(),
+        ]
+        .into_iter()
+        .find(|token| token.get_symbol_string(exchange) == symbol)
+        .ok_or_else(|| TradingError::InvalidInput(format!("Unknown token symbol: {}", symbol)))
+    }
+
     pub fn get_address(&self) -> Result<String, TradingError> {
         match self.symbol {
             SupportedToken::RENDER => Ok("rndrizKT3MK1iimdxRdWabcF7Zg7AR5T4nud4EkHBof".to_string()),
@@ -1,9 +1,10 @@
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use reqwest::{header::HeaderMap, Method};
-use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use tokio::time::sleep;
 use urlencoding;
@@ -12,13 +13,33 @@ use alloy::signers::local::PrivateKeySigner;
 use alloy::primitives::eip191_hash_message;
 use crate::error::RequestError;
 use crate::model::PositionStatus;
-use crate::perp::lighter::models::{LighterPoints, LighterPosition, LighterTx};
+use crate::config::{AppConfig, MarginMode};
+use crate::perp::lighter::price::{MarketPrice, PriceGuard};
+use crate::perp::lighter::models::{LighterCandle, LighterFunding, LighterMarketStats, LighterOrderBook, LighterOrderFill, LighterPoints, LighterPosition, LighterSubmittedOrder, LighterTx};
+use crate::storage::{storage_order::{OrderRecord, OrderStatus}, traits::OrderStore};
 use crate::{error::TradingError, model::{balance::Balance, token::Token, Exchange, Position, PositionSide}, perp::{lighter::{models::{LighterAccount, LighterOrder}, signer::SignerClient}, PerpExchange}, request::Request, trader::wallet::Wallet};
-use crate::perp::lighter::signer::{TX_TYPE_CHANGE_PUB_KEY, TX_TYPE_CREATE_ORDER, TX_TYPE_UPDATE_LEVERAGE};
+use crate::perp::lighter::signer::{CANCEL_ALL_TIF_IMMEDIATE, CROSS_MARGIN_MODE, ISOLATED_MARGIN_MODE, TX_TYPE_CANCEL_ALL_ORDERS, TX_TYPE_CHANGE_PUB_KEY, TX_TYPE_CREATE_ORDER, TX_TYPE_TRANSFER, TX_TYPE_UPDATE_LEVERAGE, USDC_TICKER_SCALE};
 
 const DEFAULT_API_KEY_INDEX: i32 = 0;
 const DEFAULT_BASE_URL: &str = "https://mainnet.zklighter.elliot.ai/api/v1";
 
+/// Initial margin fraction in basis points allowing at least `leverage`
+pub(crate) fn initial_margin_fraction(leverage: f64) -> Result<i32, TradingError> {
+    if !leverage.is_finite() || leverage < 1.0 {
+        return Err(TradingError::InvalidInput(format!("Invalid leverage: {}", leverage)));
+    }
+
+    Ok(((10_000.0 / leverage).floor() as i32).max(1))
+}
+
+/// Lighter's code for a margin mode
+fn lighter_margin_mode(mode: MarginMode) -> i32 {
+    match mode {
+        MarginMode::Cross => CROSS_MARGIN_MODE,
+        MarginMode::Isolated => ISOLATED_MARGIN_MODE,
+    }
+}
+
 
 /// A client for interacting with the Lighter perpetual futures exchange.
 /// 
@@ -50,6 +71,11 @@ pub struct LighterClient {
     api_private_key: String,
     api_public_key: String,
     signer_client: SignerClient,
+    price_guard: PriceGuard,
+    /// Taker fee per fill (in basis points of notional), for the order ledger
+    taker_fee_bps: f64,
+    /// Ledger every submitted order is recorded in, if attached
+    order_ledger: Option<Arc<dyn OrderStore>>,
 }
 
 impl LighterClient {
@@ -91,6 +117,9 @@ impl LighterClient {
             None,
         ).map_err(|e| TradingError::SigningError(e.to_string()))?;
 
+        let config = AppConfig::load().map_err(|e| TradingError::ConfigError(e.to_string()))?;
+        let price_guard = PriceGuard::new(config.price);
+
         Ok(Self { 
             wallet: wallet.clone(),
             account_index,
@@ -98,9 +127,22 @@ impl LighterClient {
             api_private_key,
             api_public_key,
             signer_client,
+            price_guard,
+            taker_fee_bps: config.cost.taker_fee_bps,
+            order_ledger: None,
         })
     }
 
+    /// Records every order this client submits, and its resolution, in the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `ledger` - Order store to write to
+    pub fn with_order_ledger(mut self, ledger: Arc<dyn OrderStore>) -> Self {
+        self.order_ledger = Some(ledger);
+        self
+    }
+
     /// Registers a new API key with the Lighter protocol using Ethereum wallet signature.
     ///
     /// This method now also updates the wallet with the new API key for future reuse.
@@ -307,6 +349,9 @@ impl LighterClient {
             api_private_key: api_private_key.to_string(),
             api_public_key: "".to_string(), // We don't need this for verification
             signer_client,
+            price_guard: PriceGuard::new(Default::default()),
+            taker_fee_bps: 0.0,
+            order_ledger: None,
         };
 
         // Try to get account points - if this succeeds, the key is valid
@@ -322,6 +367,20 @@ impl LighterClient {
     }
 
 
+    /// Retrieves the account's collateral ratio (account value / initial margin in use).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Decimal>, TradingError>` - The ratio, or `None` if no margin is in use
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the account cannot be fetched
+    pub async fn get_collateral_ratio(&self) -> Result<Option<Decimal>, TradingError> {
+        let account = self.get_account().await?;
+        Ok(account.collateral_ratio())
+    }
+
     /// Retrieves the complete account information from the Lighter API.
     ///
     /// This method fetches the account details including balances, positions,
@@ -337,7 +396,7 @@ impl LighterClient {
     /// * API request fails
     /// * No accounts are found for the wallet
     /// * Response parsing fails
-    async fn get_account(&self) -> Result<LighterAccount, TradingError> {
+    pub async fn get_account(&self) -> Result<LighterAccount, TradingError> {
         let url = format!("{}/account?by=l1_address&value={}", self.base_url, self.wallet.get_ethereum_address()?);
         let response = Request::process_request(Method::GET, url, None, None, self.wallet.proxy.clone()).await?;
 
@@ -413,8 +472,9 @@ impl LighterClient {
 
     /// Retrieves the current market price for a token with slippage adjustment.
     ///
-    /// This method fetches recent candlestick data and calculates an adjusted
-    /// price with built-in slippage protection for order execution.
+    /// The price is the order book mid, checked against the last trade by the
+    /// price guard (see [`Self::get_checked_price`]), and adjusted for slippage.
+    /// Reduce-only orders are priced with [`Self::get_exit_price`] instead.
     ///
     /// # Arguments
     ///
@@ -428,51 +488,278 @@ impl LighterClient {
     /// # Errors
     ///
     /// Returns `TradingError` if:
-    /// * API request fails
-    /// * No candlestick data available
-    /// * Price calculation fails
+    /// * API request fails or the order book is empty
+    /// * The price is stale or deviates from the last trade (`StalePrice`, `PriceDeviation`)
+    /// * Price scaling fails
     ///
     /// # Slippage Adjustment
     ///
     /// * Long positions: +0.5% slippage protection
     /// * Short positions: -0.5% slippage protection
     pub async fn get_market_price(&self, token: &Token, side: PositionSide) -> Result<u64, TradingError> {
+        let market_price = self.get_checked_price(token).await?;
+        Self::order_price(token, side, market_price.mid)
+    }
+
+
+    /// Converts a mid price into a scaled limit price with ±0.5% slippage protection.
+    fn order_price(token: &Token, side: PositionSide, mid: Decimal) -> Result<u64, TradingError> {
+        let adjusted_price = match side {
+            PositionSide::Short => mid * Decimal::new(995, 3),
+            PositionSide::Long => mid * Decimal::new(1005, 3),
+        };
+
+        // Use token's price denomination to scale the price correctly
+        let price_denomination = Decimal::from_f64(token.get_price_denomination()).ok_or_else(|| {
+            TradingError::InvalidInput(format!("Invalid price denomination for {}", token.symbol))
+        })?;
+
+        // Round to ensure we get a clean integer
+        (adjusted_price * price_denomination)
+            .round()
+            .to_u64()
+            .filter(|price| *price > 0)
+            .ok_or_else(|| TradingError::InvalidInput(format!(
+                "Price {} of {} cannot be scaled to an order price",
+                adjusted_price, token.symbol
+            )))
+    }
+
+    /// Converts a scaled order price back into a price.
+    fn limit_price(token: &Token, price: u64) -> Decimal {
+        Decimal::from(price) / Decimal::from_f64(token.get_price_denomination()).unwrap_or(Decimal::ONE)
+    }
+
+
+    /// Retrieves the order book mid of a token's market after the price sanity checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MarketPrice, TradingError>` - The mid price with the best bid/ask and last trade
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the order book cannot be fetched, the market has not traded
+    /// within `price.max_staleness_seconds` or the mid deviates from the last trade by more
+    /// than `price.max_deviation_pct`
+    pub async fn get_checked_price(&self, token: &Token) -> Result<MarketPrice, TradingError> {
+        let book = self.get_order_book(token, 1).await?;
+
+        // No trades in the lookback window is reported as a stale price by the guard
+        let candles = match self.get_candles(token, "1m", self.price_guard.candle_lookback()).await {
+            Ok(candles) => candles,
+            Err(TradingError::MarketDataUnavailable(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        self.price_guard.check(&token.get_symbol_string(Exchange::Lighter), &book, &candles, Utc::now())
+    }
+
+
+    /// Retrieves a price for a reduce-only order.
+    ///
+    /// Exits must not be blocked in the stale or volatile markets where they matter most, so
+    /// a price rejected by the price guard is logged and replaced by the unchecked order book
+    /// mid, or by the latest candle close if the book is unusable.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Decimal, TradingError>` - The mid price to derive the limit price from
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError::MarketDataUnavailable` if there is neither an order book nor a recent candle
+    pub async fn get_exit_price(&self, token: &Token) -> Result<Decimal, TradingError> {
+        // Candles are only a fallback here, so look further back than the staleness window
+        const FALLBACK_CANDLES: usize = 60;
+
+        let symbol = token.get_symbol_string(Exchange::Lighter);
+        let book = self
+            .get_order_book(token, 1)
+            .await
+            .inspect_err(|e| warn!("#{} | ⚠️ No order book to price a {} exit: {}", self.wallet.id, symbol, e))
+            .ok();
+        let candles = self
+            .get_candles(token, "1m", self.price_guard.candle_lookback().max(FALLBACK_CANDLES))
+            .await
+            .unwrap_or_default();
+
+        if let Some(book) = &book {
+            match self.price_guard.check(&symbol, book, &candles, Utc::now()) {
+                Ok(price) => return Ok(price.mid),
+                Err(e) => warn!("#{} | ⚠️ {} - pricing the reduce-only order without the price guard", self.wallet.id, e),
+            }
+        }
+
+        PriceGuard::fallback_price(book.as_ref(), &candles).ok_or_else(|| {
+            TradingError::MarketDataUnavailable(format!("No order book or recent trade to price a {} exit", symbol))
+        })
+    }
+
+
+    /// Retrieves historical candles for a token's market.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    /// * `resolution` - Candle resolution (`1m`, `5m`, `15m`, `1h`, `4h` or `1d`)
+    /// * `count` - Number of most recent candles to return
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<LighterCandle>, TradingError>` - Candles ordered from oldest to newest
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the resolution is unsupported, the request fails
+    /// or the response contains no candles
+    pub async fn get_candles(&self, token: &Token, resolution: &str, count: usize) -> Result<Vec<LighterCandle>, TradingError> {
+        let resolution_ms: i64 = match resolution {
+            "1m" => 60_000,
+            "5m" => 5 * 60_000,
+            "15m" => 15 * 60_000,
+            "1h" => 60 * 60_000,
+            "4h" => 4 * 60 * 60_000,
+            "1d" => 24 * 60 * 60_000,
+            _ => return Err(TradingError::InvalidInput(format!("Unsupported candle resolution: {}", resolution))),
+        };
+
         let end_timestamp = Utc::now().timestamp_millis();
-        let start_timestamp = end_timestamp - 60000;
-        let url = format!("{}/candlesticks?market_id={}&resolution=1m&start_timestamp={}&end_timestamp={}&count_back=5", self.base_url, token.get_market_index(Exchange::Lighter), start_timestamp, end_timestamp);
+        let start_timestamp = end_timestamp - resolution_ms * count as i64;
+        let url = format!(
+            "{}/candlesticks?market_id={}&resolution={}&start_timestamp={}&end_timestamp={}&count_back={}",
+            self.base_url, token.get_market_index(Exchange::Lighter), resolution, start_timestamp, end_timestamp, count
+        );
         let response = Request::process_request(Method::GET, url, None, None, self.wallet.proxy.clone()).await?;
 
+        let mut candles: Vec<LighterCandle> = match response.get("candlesticks") {
+            Some(candlesticks) => serde_json::from_value(candlesticks.clone())
+                .map_err(|e| TradingError::MarketDataUnavailable(format!("Invalid candlesticks response: {}", e)))?,
+            None => return Err(TradingError::InvalidInput(format!("Candlesticks not found in response: {:?}", response))),
+        };
 
-        match response["candlesticks"].as_array() {
-            Some(candlesticks) => {
+        if candles.is_empty() {
+            return Err(TradingError::MarketDataUnavailable(format!(
+                "No candlesticks found for token {} on Lighter",
+                token.get_symbol_string(Exchange::Lighter)
+            )));
+        }
 
-                if candlesticks.is_empty() {
-                    return Err(TradingError::MarketDataUnavailable(
-                        format!("No candlesticks found for token {} on Lighter", 
-                        token.get_symbol_string(Exchange::Lighter))
-                    ));
-                }
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
 
-                let latest_candlestick = &candlesticks[candlesticks.len() - 1];
-                let price_f64 = latest_candlestick["close"].as_f64().unwrap();
-                let price_decimal = Decimal::from_f64(price_f64).unwrap();
 
-                let adjusted_price = match side {
-                    PositionSide::Short => price_decimal * Decimal::from_f64(0.995).unwrap(),
-                    PositionSide::Long => price_decimal * Decimal::from_f64(1.005).unwrap(),
-                };
+    /// Retrieves the resting orders of a token's market.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    /// * `depth` - Maximum number of orders per side
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LighterOrderBook, TradingError>` - Asks from lowest and bids from highest price
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the request fails or either side of the book is empty
+    pub async fn get_order_book(&self, token: &Token, depth: usize) -> Result<LighterOrderBook, TradingError> {
+        let url = format!(
+            "{}/orderBookOrders?market_id={}&limit={}",
+            self.base_url, token.get_market_index(Exchange::Lighter), depth
+        );
+        let response = Request::process_request(Method::GET, url, None, None, self.wallet.proxy.clone()).await?;
 
-                // Use token's price denomination to scale the price correctly
-                let price_denomination = Decimal::from_f64(token.get_price_denomination()).unwrap();
-                let scaled_price = adjusted_price * price_denomination;
-                
-                // Round to ensure we get a clean integer
-                let price = scaled_price.round().to_string().parse::<u64>().unwrap();
+        let mut book: LighterOrderBook = serde_json::from_value(response)
+            .map_err(|e| TradingError::MarketDataUnavailable(format!("Invalid order book response: {}", e)))?;
 
-                Ok(price)
-            },
-            None => return Err(TradingError::InvalidInput(format!("Candlesticks not found in response: {:?}", response))),
+        if book.asks.is_empty() || book.bids.is_empty() {
+            return Err(TradingError::MarketDataUnavailable(format!(
+                "Order book for {} on Lighter is empty",
+                token.get_symbol_string(Exchange::Lighter)
+            )));
         }
+
+        book.asks.sort_by_key(|level| level.price);
+        book.bids.sort_by_key(|level| std::cmp::Reverse(level.price));
+        Ok(book)
+    }
+
+
+    /// Retrieves price and open interest statistics of a token's market.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LighterMarketStats, TradingError>` - Last trade price and open interest
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the request fails or the market is missing from the response
+    pub async fn get_market_stats(&self, token: &Token) -> Result<LighterMarketStats, TradingError> {
+        let market_id = token.get_market_index(Exchange::Lighter);
+        let url = format!("{}/orderBookDetails?market_id={}", self.base_url, market_id);
+        let response = Request::process_request(Method::GET, url, None, None, self.wallet.proxy.clone()).await?;
+
+        let details: Vec<LighterMarketStats> = match response.get("order_book_details") {
+            Some(details) => serde_json::from_value(details.clone())
+                .map_err(|e| TradingError::MarketDataUnavailable(format!("Invalid order book details response: {}", e)))?,
+            None => return Err(TradingError::InvalidInput(format!("Order book details not found in response: {:?}", response))),
+        };
+
+        details
+            .into_iter()
+            .find(|d| d.market_id == market_id)
+            .ok_or_else(|| TradingError::MarketDataUnavailable(format!(
+                "No market statistics for {} on Lighter",
+                token.get_symbol_string(Exchange::Lighter)
+            )))
+    }
+
+
+    /// Retrieves the hourly funding payments of a token's market.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    /// * `hours` - Number of most recent hourly fundings to return
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<LighterFunding>, TradingError>` - Fundings ordered from oldest to newest
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the request fails or the response cannot be parsed
+    pub async fn get_fundings(&self, token: &Token, hours: usize) -> Result<Vec<LighterFunding>, TradingError> {
+        let end_timestamp = Utc::now().timestamp();
+        let start_timestamp = end_timestamp - 3600 * hours as i64;
+        let url = format!(
+            "{}/fundings?market_id={}&resolution=1h&start_timestamp={}&end_timestamp={}&count_back={}",
+            self.base_url, token.get_market_index(Exchange::Lighter), start_timestamp, end_timestamp, hours
+        );
+        let response = Request::process_request(Method::GET, url, None, None, self.wallet.proxy.clone()).await?;
+
+        let mut fundings: Vec<LighterFunding> = match response.get("fundings") {
+            Some(fundings) => serde_json::from_value(fundings.clone())
+                .map_err(|e| TradingError::MarketDataUnavailable(format!("Invalid fundings response: {}", e)))?,
+            None => return Err(TradingError::InvalidInput(format!("Fundings not found in response: {:?}", response))),
+        };
+
+        fundings.sort_by_key(|f| f.timestamp);
+        Ok(fundings)
     }
 
 
@@ -514,6 +801,8 @@ impl LighterClient {
             match tx {
                 Ok(tx) => {
                     if tx.code == 200 {
+                        self.update_ledger(hash, |ledger| ledger.update_order_status(hash, OrderStatus::Executed, Some(tx.status), Some(tx.executed_at)))
+                            .await;
                         return Ok(tx);
                     } else {
                         last_err = Some(
@@ -534,9 +823,51 @@ impl LighterClient {
             }
         }
 
+        self.update_ledger(hash, |ledger| ledger.update_order_status(hash, OrderStatus::Unconfirmed, None, None))
+            .await;
+
         Err(last_err.unwrap_or_else(|| TradingError::OrderExecutionFailed("get_order_by_hash failed after 3 attempts".to_string())))
     }
 
+    /// Records a submitted order in the ledger, if attached.
+    ///
+    /// The ledger is an audit trail: a failed write is logged and never fails the order.
+    async fn record_order(&self, order: OrderRecord) {
+        if let Some(ledger) = &self.order_ledger {
+            if let Err(e) = ledger.record_order(&order).await {
+                error!("#{} | failed to record order {} in the ledger: {}", self.wallet.id, order.tx_hash, e);
+            }
+        }
+    }
+
+    /// Applies an update to a ledger order, if a ledger is attached.
+    async fn update_ledger<'a, F, Fut>(&'a self, hash: &str, update: F)
+    where
+        F: FnOnce(&'a dyn OrderStore) -> Fut,
+        Fut: std::future::Future<Output = Result<(), TradingError>>,
+    {
+        if let Some(ledger) = &self.order_ledger {
+            if let Err(e) = update(ledger.as_ref()).await {
+                error!("#{} | failed to update order {} in the ledger: {}", self.wallet.id, hash, e);
+            }
+        }
+    }
+
+    /// Records the fill price of an order and its fee at the configured taker fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Transaction hash of the order
+    /// * `fill_price` - Average execution price, if known
+    /// * `notional_usdc` - Notional of the order (in USDC)
+    async fn record_order_fill(&self, hash: &str, fill_price: Option<Decimal>, notional_usdc: Decimal) {
+        let fee_rate = Decimal::from_f64(self.taker_fee_bps).unwrap_or(Decimal::ZERO) / Decimal::from(10_000);
+        let fee_usdc = (notional_usdc.abs() * fee_rate).round_dp(6);
+
+        self.update_ledger(hash, |ledger| ledger.update_order_fill(hash, fill_price, Some(fee_usdc)))
+            .await;
+    }
+
 
     /// Retrieves all active (non-zero) positions for the account.
     ///
@@ -575,8 +906,8 @@ impl LighterClient {
     /// Closes all active positions with market orders.
     ///
     /// This method identifies all open positions and executes market orders
-    /// to close them completely. It includes verification to ensure positions
-    /// are successfully closed.
+    /// to close them completely, one market at a time. It includes verification
+    /// to ensure every position is successfully closed.
     ///
     /// # Returns
     ///
@@ -601,61 +932,92 @@ impl LighterClient {
         }
     
         for position in positions_to_close {
-            let token_id = position.market_id;
-            let token = Token::from_market_index(Exchange::Lighter, token_id);
-            let (position_side_current, position_side_to_close) = self.parse_position_sides(position.sign)?;
-    
-            let price = self.get_market_price(&token, position_side_to_close).await?;
-    
-            info!("#{} | found open {} position to close: {}", self.wallet.id, position_side_current, position.symbol);
-            let position_size: f64 = position.position.to_string().parse::<f64>().unwrap();
-            let base_amount = self.base_amount_from_f64(position_size)?;
-    
-            info!("#{} | <{}> position size: {}", self.wallet.id, position.symbol, position_size);
-            info!("#{} | <{}> base amount: {}", self.wallet.id, position.symbol, base_amount);
-    
-            let order = self
-                .execute_market_order(&token, position_side_to_close, base_amount, price, true).await?;
-    
-            match self.get_order_by_hash(&order).await {
-                Ok(_) => {
-                    info!("#{} | found order by hash: {}", self.wallet.id, order);
-                    let positions = self.get_active_positions().await?;
-                    let market_index = token.get_market_index(Exchange::Lighter);
-    
-                    info!("#{} | looking in positions if still open...", self.wallet.id);
-    
-                    if let Some(pos) = positions.iter().find(|p| p.market_id == market_index) {
+            self.close_lighter_position(&position).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes the position held on a single market, leaving other markets untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token whose market should be flattened
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), TradingError>` - Success if the market is flat (or was already flat)
+    pub async fn close_market_position(&self, token: &Token) -> Result<(), TradingError> {
+        match self.get_market_position(token).await? {
+            Some(position) if self.should_close_position(&position) => {
+                self.close_lighter_position(&position).await
+            }
+            _ => {
+                info!("#{} | no {} position to close!", self.wallet.id, token.get_symbol_string(Exchange::Lighter));
+                Ok(())
+            }
+        }
+    }
+
+    /// Closes one position with a reduce-only market order and verifies that its market is flat.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The exchange position to close
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), TradingError>` - Success if the position is closed
+    async fn close_lighter_position(&self, position: &LighterPosition) -> Result<(), TradingError> {
+        let token_id = position.market_id;
+        let token = Token::from_market_index(Exchange::Lighter, token_id);
+        let (position_side_current, position_side_to_close) = self.parse_position_sides(position.sign)?;
+
+        let price = Self::order_price(&token, position_side_to_close, self.get_exit_price(&token).await?)?;
+
+        info!("#{} | found open {} position to close: {}", self.wallet.id, position_side_current, position.symbol);
+        let position_size: f64 = position.position.to_string().parse::<f64>().unwrap();
+        let base_amount = self.base_amount_from_f64(position_size)?;
+
+        info!("#{} | <{}> position size: {}", self.wallet.id, position.symbol, position_size);
+        info!("#{} | <{}> base amount: {}", self.wallet.id, position.symbol, base_amount);
+
+        let order = self
+            .execute_market_order(&token, position_side_to_close, base_amount, price, true).await?;
+
+        match self.get_order_by_hash(&order.tx_hash).await {
+            Ok(_) => {
+                info!("#{} | found order by hash: {}", self.wallet.id, order.tx_hash);
+                let fill_price = self.reduce_fill_price(&token, position, Self::limit_price(&token, price)).await;
+                let notional = fill_price.map_or(position.position_value, |p| (position.position.abs() * p).round_dp(6));
+                self.record_order_fill(&order.tx_hash, fill_price, notional).await;
+                let market_index = token.get_market_index(Exchange::Lighter);
+
+                info!("#{} | looking in positions if still open...", self.wallet.id);
+
+                match self.get_market_position(&token).await? {
+                    Some(pos) if pos.position_value != Decimal::ZERO => {
                         info!("#{} | position value: {}", self.wallet.id, pos.position_value);
-    
-                        if pos.position_value == Decimal::ZERO {
-                            info!("#{} | position size is 0, which means it closed: {}", self.wallet.id, pos.symbol);
-                            info!("#{} | 🔴🔴 position closed: {}", self.wallet.id, pos.symbol);
 
-                            return Ok(());
-                        } else {
-                            return Err(TradingError::ExchangeError(format!(
-                                "#{} | failed to close position on market index {} with token {}, it's still open...",
-                                self.wallet.id, market_index, token.get_symbol_string(Exchange::Lighter
-                                )
-                            )));
-                        }
-                    } else {
-                        info!("#{} | position not found in positions, which means it closed: {}", self.wallet.id, position.symbol);
+                        Err(TradingError::ExchangeError(format!(
+                            "#{} | failed to close position on market index {} with token {}, it's still open...",
+                            self.wallet.id, market_index, token.get_symbol_string(Exchange::Lighter)
+                        )))
+                    }
+                    _ => {
+                        info!("#{} | position size is 0 or not found, which means it closed: {}", self.wallet.id, position.symbol);
                         info!("#{} | 🔴🔴 position closed: {}", self.wallet.id, position.symbol);
-                        return Ok(());
+                        Ok(())
                     }
                 }
-                Err(e) => {
-                    return Err(TradingError::ExchangeError(format!(
-                        "#{} | failed to confirm close of position {}: {}",
-                        self.wallet.id, position.symbol, e
-                    )));
-                }
+            }
+            Err(e) => {
+                Err(TradingError::ExchangeError(format!(
+                    "#{} | failed to confirm close of position {}: {}",
+                    self.wallet.id, position.symbol, e
+                )))
             }
         }
-    
-        unreachable!()
     }
 
     /// Determines if a position should be closed based on its value.
@@ -793,40 +1155,403 @@ impl LighterClient {
         Ok(base_rounded)
     }
 
-    /// Updates the leverage for a specific token.
+    /// Retrieves the open position on the token's market, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be looked up
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<LighterPosition>, TradingError>` - The position, or `None` if flat
+    pub async fn get_market_position(&self, token: &Token) -> Result<Option<LighterPosition>, TradingError> {
+        let market_index = token.get_market_index(Exchange::Lighter);
+        let positions = self.get_active_positions().await?;
+
+        Ok(positions.into_iter().find(|p| p.market_id == market_index))
+    }
+
+    /// Retrieves the account's position entry for a token's market, even when flat.
+    ///
+    /// Unlike `get_market_position`, a closed position is still returned with its realized PnL.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market should be queried
+    pub async fn get_market_position_entry(&self, token: &Token) -> Result<Option<LighterPosition>, TradingError> {
+        let market_index = token.get_market_index(Exchange::Lighter);
+        let account = self.get_account().await?;
+
+        Ok(account.positions.unwrap_or_default().into_iter().find(|p| p.market_id == market_index))
+    }
+
+    /// Executes a single child order that adds exposure to a position.
+    ///
+    /// Unlike `open_position`, this method does not require the market to be flat,
+    /// so it can be called repeatedly to build a position in slices.
+    ///
+    /// Pricing and submission are retried with a backoff. Once an order was accepted it is
+    /// never resubmitted, since it may already be filled: its tx hash is polled, and if it
+    /// cannot be confirmed the position change decides whether it filled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to trade
+    /// * `side` - The position side (Long/Short)
+    /// * `amount_usdc` - The USDC notional of this slice
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LighterOrderFill, TradingError>` - The confirmed order
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if pricing or submission fails after 3 attempts, or
+    /// `TradingError::OrderUnconfirmed` if the submitted order can neither be confirmed
+    /// nor seen in the position, in which case it may still fill later
+    pub async fn execute_order_slice(&self, token: &Token, side: PositionSide, amount_usdc: Decimal) -> Result<LighterOrderFill, TradingError> {
+        const MAX_SUBMIT_ATTEMPTS: u64 = 3;
+        const CONFIRM_ATTEMPTS: u64 = 3;
+        const BACKOFF_MS: u64 = 500;
+
+        let mut last_error: Option<TradingError> = None;
+        let mut submitted = None;
+
+        for attempt in 1..=MAX_SUBMIT_ATTEMPTS {
+            if attempt > 1 {
+                sleep(Duration::from_millis(BACKOFF_MS * (attempt - 1))).await;
+            }
+
+            let expected_price = match self.get_checked_price(token).await {
+                Ok(p) => p.mid,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            let price = match Self::order_price(token, side, expected_price) {
+                Ok(p) => p,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            let base_amount = match self.calculate_base_amount(amount_usdc, price).await {
+                Ok(a) => a,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            // Position before the order, to derive the fill from the change.
+            // Accounts that never traded report no positions at all
+            let position_before = match self.get_market_position(token).await {
+                Ok(position) => Ok(position),
+                Err(TradingError::PositionNotFound(_)) => Ok(None),
+                Err(e) => Err(e),
+            };
+
+            match self.execute_market_order(token, side, base_amount, price, false).await {
+                Ok(order) => {
+                    info!("#{} | Attempt {}/{}: Slice order sent: {}", self.wallet.id, attempt, MAX_SUBMIT_ATTEMPTS, order.tx_hash);
+                    submitted = Some((order, expected_price, price, base_amount, position_before));
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let Some((order, expected_price, price, base_amount, position_before)) = submitted else {
+            return Err(last_error.unwrap_or_else(|| TradingError::OrderExecutionFailed(
+                format!("Failed to execute order slice after {} attempts", MAX_SUBMIT_ATTEMPTS)
+            )));
+        };
+
+        // From here on the order is only polled, never sent again
+        let mut confirmed = None;
+        for attempt in 1..=CONFIRM_ATTEMPTS {
+            match self.get_order_by_hash(&order.tx_hash).await {
+                Ok(tx) => {
+                    confirmed = Some(tx);
+                    break;
+                }
+                Err(e) => {
+                    warn!("#{} | Slice order {} not confirmed yet ({}/{}): {}", self.wallet.id, order.tx_hash, attempt, CONFIRM_ATTEMPTS, e);
+                    sleep(Duration::from_millis(BACKOFF_MS * attempt)).await;
+                }
+            }
+        }
+
+        let position_after = self.get_market_position(token).await;
+        let (filled_size, fill_price) = match (&position_before, &position_after) {
+            (Ok(before), Ok(Some(after))) => (
+                Self::filled_size(before.as_ref(), after),
+                Self::average_fill_price(before.as_ref(), after),
+            ),
+            _ => (None, None),
+        };
+
+        let executed_at = match confirmed {
+            Some(tx) => tx.executed_at,
+            None if filled_size.is_some() => {
+                warn!("#{} | Slice order {} could not be confirmed, but the position grew", self.wallet.id, order.tx_hash);
+                Utc::now().timestamp_millis()
+            }
+            None => {
+                return Err(TradingError::OrderUnconfirmed(format!(
+                    "Slice order {} could not be confirmed and the position did not change yet",
+                    order.tx_hash
+                )));
+            }
+        };
+
+        // Notional actually filled; the requested one if the position could not be read
+        let filled_usdc = match (filled_size, fill_price) {
+            (Some(size), Some(fill_price)) => (size * fill_price).round_dp(6),
+            _ => amount_usdc,
+        };
+
+        self.record_order_fill(&order.tx_hash, fill_price, filled_usdc).await;
+
+        Ok(LighterOrderFill {
+            tx_hash: order.tx_hash,
+            nonce: order.nonce,
+            price,
+            base_amount,
+            amount_usdc: filled_usdc,
+            executed_at,
+            expected_price,
+            fill_price,
+        })
+    }
+
+    /// Base amount added to a position between two snapshots.
+    ///
+    /// Returns `None` if the position did not grow (e.g. a reduction or a flip).
+    fn filled_size(before: Option<&LighterPosition>, after: &LighterPosition) -> Option<Decimal> {
+        let size_before = match before {
+            Some(p) if p.sign == after.sign => p.position.abs(),
+            Some(p) if !p.position.is_zero() => return None,
+            _ => Decimal::ZERO,
+        };
+
+        let filled = after.position.abs() - size_before;
+        (filled > Decimal::ZERO).then_some(filled)
+    }
+
+    /// Average price of the base amount added to a position between two snapshots.
+    ///
+    /// Returns `None` if the position did not grow (e.g. a reduction or a flip).
+    fn average_fill_price(before: Option<&LighterPosition>, after: &LighterPosition) -> Option<Decimal> {
+        let (size_before, cost_before) = match before {
+            Some(p) if p.sign == after.sign => (p.position.abs(), p.position.abs() * p.avg_entry_price),
+            Some(p) if !p.position.is_zero() => return None,
+            _ => (Decimal::ZERO, Decimal::ZERO),
+        };
+
+        let size_after = after.position.abs();
+        let filled = size_after - size_before;
+        if filled <= Decimal::ZERO {
+            return None;
+        }
+
+        Some((size_after * after.avg_entry_price - cost_before) / filled)
+    }
+
+    /// Reduces an open position by a fraction of its current size with a reduce-only order.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position to reduce
+    /// * `fraction` - Share of the current size to close (0 < fraction <= 1)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LighterOrderFill, TradingError>` - The confirmed reduce-only order
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the fraction is invalid, the slice rounds to zero,
+    /// or order execution/confirmation fails
+    pub async fn reduce_position_slice(&self, position: &LighterPosition, fraction: Decimal) -> Result<LighterOrderFill, TradingError> {
+        if fraction <= Decimal::ZERO || fraction > Decimal::ONE {
+            return Err(TradingError::InvalidInput(format!("Invalid reduce fraction: {}", fraction)));
+        }
+
+        let token = Token::from_market_index(Exchange::Lighter, position.market_id);
+        let (_, side_to_close) = self.parse_position_sides(position.sign)?;
+
+        // Keep the exchange's size precision so the base amount scales like in `close_all_positions`
+        let scale = position.position.scale();
+        let slice_size = (position.position.abs() * fraction).round_dp(scale);
+        let base_amount = (slice_size * Decimal::from(10_u64.pow(scale)))
+            .round()
+            .to_string()
+            .parse::<u64>()
+            .map_err(|e| TradingError::InvalidInput(e.to_string()))?;
+
+        if base_amount == 0 {
+            return Err(TradingError::InvalidInput(format!(
+                "#{} | reduce slice for {} rounds to zero", self.wallet.id, position.symbol
+            )));
+        }
+
+        let expected_price = self.get_exit_price(&token).await?;
+        let price = Self::order_price(&token, side_to_close, expected_price)?;
+        let order = self.execute_market_order(&token, side_to_close, base_amount, price, true).await?;
+        let tx = self.get_order_by_hash(&order.tx_hash).await?;
+
+        info!("#{} | <{}> reduced by {:.2}% | hash: {}", self.wallet.id, position.symbol, fraction * Decimal::from(100), tx.hash);
+
+        let fill_price = self.reduce_fill_price(&token, position, Self::limit_price(&token, price)).await;
+        let amount_usdc = fill_price.map_or(position.position_value * fraction, |p| (slice_size * p).round_dp(6));
+        self.record_order_fill(&tx.hash, fill_price, amount_usdc).await;
+
+        Ok(LighterOrderFill {
+            tx_hash: tx.hash,
+            nonce: order.nonce,
+            price,
+            base_amount,
+            amount_usdc,
+            executed_at: tx.executed_at,
+            expected_price,
+            fill_price,
+        })
+    }
+
+    /// Average price a reduce-only order closed part of `before` at.
+    ///
+    /// Returns `None` if the market's position cannot be read again, or if the derived
+    /// price is beyond the order's limit price, which no fill of the order can be.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token of the reduced position
+    /// * `before` - The position before the order
+    /// * `limit_price` - Limit price of the order
+    async fn reduce_fill_price(&self, token: &Token, before: &LighterPosition, limit_price: Decimal) -> Option<Decimal> {
+        let after = match self.get_market_position_entry(token).await {
+            Ok(Some(after)) => after,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!("#{} | ⚠️ Cannot read the {} position to price its exit: {}", self.wallet.id, before.symbol, e);
+                return None;
+            }
+        };
+
+        let fill_price = Self::exit_fill_price(before, &after)?;
+
+        // Selling out of a long fills at or above the limit, buying back a short at or below it
+        let within_limit = match before.sign {
+            1 => fill_price >= limit_price,
+            -1 => fill_price <= limit_price,
+            _ => false,
+        };
+        if !within_limit || fill_price <= Decimal::ZERO {
+            warn!(
+                "#{} | ⚠️ Derived {} exit price {} is beyond the limit price {}, leaving it unknown",
+                self.wallet.id, before.symbol, fill_price, limit_price
+            );
+            return None;
+        }
+
+        Some(fill_price)
+    }
+
+    /// Average price of the base amount closed between two snapshots of a position.
+    ///
+    /// Closing realizes (exit - entry) × size on a long and (entry - exit) × size on a short,
+    /// so the exit price is backed out of the change in the position's realized PnL.
+    /// `after` is the market's position entry, which stays listed once flat.
+    ///
+    /// Returns `None` if the position did not shrink (e.g. it grew or flipped).
+    fn exit_fill_price(before: &LighterPosition, after: &LighterPosition) -> Option<Decimal> {
+        let size_after = match after {
+            p if p.position.is_zero() => Decimal::ZERO,
+            p if p.sign == before.sign => p.position.abs(),
+            _ => return None,
+        };
+
+        let closed = before.position.abs() - size_after;
+        if closed <= Decimal::ZERO {
+            return None;
+        }
+
+        let realized_per_unit = (after.realized_pnl - before.realized_pnl) / closed;
+        match before.sign {
+            1 => Some(before.avg_entry_price + realized_per_unit),
+            -1 => Some(before.avg_entry_price - realized_per_unit),
+            _ => None,
+        }
+    }
+
+    /// Builds a `Position` record from a live Lighter position.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The traded token
+    /// * `side` - The position side (Long/Short)
+    /// * `close_at` - The scheduled closing time for the position
+    /// * `position` - The live position on the exchange
+    pub fn build_position(&self, token: &Token, side: PositionSide, close_at: DateTime<Utc>, position: &LighterPosition) -> Position {
+        Position {
+            wallet_id: self.wallet.id,
+            id: uuid::Uuid::new_v4().to_string(),
+            strategy_id: None,
+            exchange: Exchange::Lighter,
+            symbol: token.get_symbol_string(Exchange::Lighter),
+            side,
+            size: position.position_value,
+            status: PositionStatus::Open,
+            opened_at: Utc::now(),
+            close_at,
+            closed_at: None,
+            close_price: None,
+            realized_pnl: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Updates the leverage and margin mode of a market.
     ///
-    /// This method sets the leverage and margin mode for a trading pair.
-    /// Currently uses fixed leverage of 33.33x (3333 basis points).
+    /// Lighter stores leverage as the market's initial margin fraction in basis
+    /// points (10000 / leverage), which is rounded down so the market allows at
+    /// least the requested leverage.
     ///
     /// # Arguments
     ///
-    /// * `token` - The token to update leverage for
+    /// * `token` - The token whose market is updated
+    /// * `leverage` - Leverage multiplier (e.g. 3.0 for 3x)
+    /// * `margin_mode` - Cross or isolated margin
     ///
     /// # Returns
     ///
-    /// * `Result<(), TradingError>` - Success if leverage update is submitted
+    /// * `Result<String, TradingError>` - Transaction hash
     ///
     /// # Errors
     ///
     /// Returns `TradingError` if:
+    /// * Leverage is below 1x
     /// * Nonce retrieval fails
     /// * Transaction signing fails
     /// * API submission fails
-    #[allow(unused)]
-    pub async fn update_leverage(&self, token: Token) -> Result<(), TradingError> {
-        let margin_mode = 0;
-        let leverage_fraction = 3333;
+    pub async fn update_leverage(&self, token: &Token, leverage: f64, margin_mode: MarginMode) -> Result<String, TradingError> {
+        let leverage_fraction = initial_margin_fraction(leverage)?;
         let nonce = self.get_nonce().await?;
         let market_index = token.get_market_index(Exchange::Lighter);
 
         let leverage_signed = self.signer_client.sign_update_leverage(
             market_index,
             leverage_fraction,
-            margin_mode,
+            lighter_margin_mode(margin_mode),
             nonce,
         )?;
 
-
         let tx_info_encoded = urlencoding::encode(&leverage_signed);
         let body = format!(
             "tx_type={}&tx_info={}&price_protection={}", 
@@ -836,8 +1561,157 @@ impl LighterClient {
         );
 
         let leverage_hash = self.send_tx(body).await?;
-        info!("#{} | Leverage updated successfully! Hash: {}", self.wallet.id, leverage_hash);
-        Ok(())
+        info!(
+            "#{} | {} leverage set to {:.2}x ({} margin) | hash: {}",
+            self.wallet.id, token.get_symbol_string(Exchange::Lighter), leverage, margin_mode, leverage_hash
+        );
+        Ok(leverage_hash)
+    }
+
+    /// Makes sure a market uses the given leverage and margin mode before trading it.
+    ///
+    /// Nothing is sent if the account already reports these settings. Otherwise the
+    /// leverage is updated and the account is read back until the new settings show up.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token whose market is configured
+    /// * `leverage` - Leverage multiplier (e.g. 3.0 for 3x)
+    /// * `margin_mode` - Cross or isolated margin
+    /// * `verify_attempts` - Times the account is read back before giving up
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if the update fails or is not confirmed in time
+    pub async fn ensure_margin_settings(&self, token: &Token, leverage: f64, margin_mode: MarginMode, verify_attempts: u32) -> Result<(), TradingError> {
+        let market_index = token.get_market_index(Exchange::Lighter);
+        let fraction = initial_margin_fraction(leverage)?;
+
+        if self.has_margin_settings(market_index, fraction, margin_mode).await? {
+            return Ok(());
+        }
+
+        self.update_leverage(token, leverage, margin_mode).await?;
+
+        for attempt in 1..=verify_attempts {
+            sleep(Duration::from_millis(500 * attempt as u64)).await;
+
+            if self.has_margin_settings(market_index, fraction, margin_mode).await? {
+                return Ok(());
+            }
+        }
+
+        Err(TradingError::ExchangeError(format!(
+            "#{} | {} leverage {:.2}x ({} margin) not confirmed after {} checks",
+            self.wallet.id, token.get_symbol_string(Exchange::Lighter), leverage, margin_mode, verify_attempts
+        )))
+    }
+
+    /// Whether the account reports this initial margin fraction and margin mode for a market
+    async fn has_margin_settings(&self, market_index: i32, fraction: i32, margin_mode: MarginMode) -> Result<bool, TradingError> {
+        let account = self.get_account().await?;
+
+        Ok(account.positions.unwrap_or_default().iter().any(|p| {
+            p.market_id == market_index
+                && p.initial_margin_fraction_bps() == Some(fraction)
+                && p.margin_mode == lighter_margin_mode(margin_mode)
+        }))
+    }
+
+    /// Cancels every resting order of this account on all markets.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, TradingError>` - Transaction hash
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if nonce retrieval, signing or submission fails
+    pub async fn cancel_all_orders(&self) -> Result<String, TradingError> {
+        let nonce = self.get_nonce().await?;
+        let signed = self.signer_client.sign_cancel_all_orders(CANCEL_ALL_TIF_IMMEDIATE, 0, nonce)?;
+
+        let tx_info_encoded = urlencoding::encode(&signed);
+        let body = format!("tx_type={}&tx_info={}", TX_TYPE_CANCEL_ALL_ORDERS, tx_info_encoded);
+
+        let hash = self.send_tx(body).await?;
+        info!("#{} | All resting orders cancelled | hash: {}", self.wallet.id, hash);
+        Ok(hash)
+    }
+
+    /// Returns the Lighter account index of this wallet.
+    pub fn account_index(&self) -> u32 {
+        self.account_index
+    }
+
+    /// Retrieves the collateral not used as margin by open positions.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Decimal, TradingError>` - Available balance in USDC
+    pub async fn get_available_balance(&self) -> Result<Decimal, TradingError> {
+        let account = self.get_account().await?;
+        Decimal::from_str(&account.available_balance)
+            .map_err(|e| TradingError::InvalidInput(format!("Invalid available balance '{}': {}", account.available_balance, e)))
+    }
+
+    /// Transfers USDC collateral from this account to another Lighter account.
+    ///
+    /// The transfer is signed with the API key and, when the signer asks for it,
+    /// with the wallet's Ethereum key (EIP-191), like the API key registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `to_account_index` - Lighter account index of the receiver
+    /// * `amount_usdc` - Amount to transfer
+    /// * `fee_usdc` - Transfer fee charged by Lighter
+    /// * `memo` - Free text attached to the transfer (truncated or padded to 32 bytes)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, TradingError>` - Transaction hash
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingError` if:
+    /// * The amount is not positive
+    /// * Nonce retrieval, signing or submission fails
+    pub async fn transfer_usdc(&self, to_account_index: u32, amount_usdc: Decimal, fee_usdc: Decimal, memo: &str) -> Result<String, TradingError> {
+        let scale = Decimal::from(USDC_TICKER_SCALE);
+        let usdc_amount = (amount_usdc * scale).trunc().to_i64()
+            .filter(|amount| *amount > 0)
+            .ok_or_else(|| TradingError::InvalidInput(format!("Invalid transfer amount: {}", amount_usdc)))?;
+        let fee = (fee_usdc * scale).trunc().to_i64()
+            .ok_or_else(|| TradingError::InvalidInput(format!("Invalid transfer fee: {}", fee_usdc)))?;
+
+        // Lighter expects a 32-byte memo
+        let memo: String = memo.chars().filter(|c| c.is_ascii()).chain(std::iter::repeat(' ')).take(32).collect();
+
+        let nonce = self.get_nonce().await?;
+        let signed = self.signer_client.sign_transfer(to_account_index as i64, usdc_amount, fee, &memo, nonce)?;
+
+        let mut tx_info: serde_json::Value = serde_json::from_str(&signed)
+            .map_err(|e| TradingError::SigningError(format!("Invalid signed transfer: {}", e)))?;
+
+        if let Some(message_to_sign) = tx_info.get("MessageToSign").and_then(|m| m.as_str()).map(str::to_string) {
+            let eth_signer = self.wallet.private_key.parse::<PrivateKeySigner>()
+                .map_err(|e| TradingError::SigningError(format!("Invalid private key: {}", e)))?;
+            let message_hash = eip191_hash_message(message_to_sign.as_bytes());
+            let signature: Signature = eth_signer.sign_hash_sync(&message_hash)
+                .map_err(|e| TradingError::SigningError(e.to_string()))?;
+
+            tx_info["L1Sig"] = serde_json::Value::String(signature.to_string());
+            if let Some(fields) = tx_info.as_object_mut() {
+                fields.remove("MessageToSign");
+            }
+        }
+
+        let tx_info_encoded = urlencoding::encode(&tx_info.to_string()).into_owned();
+        let body = format!("tx_type={}&tx_info={}", TX_TYPE_TRANSFER, tx_info_encoded);
+
+        let hash = self.send_tx(body).await?;
+        info!("#{} | Transferred {:.2} USDC to account {} | hash: {}", self.wallet.id, amount_usdc, to_account_index, hash);
+        Ok(hash)
     }
 
 
@@ -858,7 +1732,7 @@ impl LighterClient {
     ///
     /// # Returns
     ///
-    /// * `Result<String, TradingError>` - The transaction hash of the executed order
+    /// * `Result<LighterSubmittedOrder, TradingError>` - The transaction hash and nonce of the executed order
     ///
     /// # Errors
     ///
@@ -873,7 +1747,7 @@ impl LighterClient {
         base_amount: u64,
         price: u64,
         close_position: bool,
-    ) -> Result<String, TradingError> {
+    ) -> Result<LighterSubmittedOrder, TradingError> {
         let market_index = token.get_market_index(Exchange::Lighter);
         let is_ask = matches!(side, PositionSide::Short);
         let reduce_only = matches!(close_position, true);
@@ -924,7 +1798,29 @@ impl LighterClient {
             );
 
             match self.send_tx(body).await {
-                Ok(order_hash) => return Ok(order_hash),
+                Ok(tx_hash) => {
+                    self.record_order(OrderRecord {
+                        exchange: Exchange::Lighter,
+                        wallet_id: self.wallet.id,
+                        symbol: token.get_symbol_string(Exchange::Lighter),
+                        side,
+                        reduce_only,
+                        base_amount: order.tx_info.base_amount,
+                        limit_price: Self::limit_price(token, price),
+                        nonce,
+                        client_order_index: order.tx_info.client_order_index,
+                        tx_hash: tx_hash.clone(),
+                        submitted_at: Utc::now(),
+                        status: OrderStatus::Submitted,
+                        tx_status: None,
+                        executed_at: None,
+                        fill_price: None,
+                        fee_usdc: None,
+                    })
+                    .await;
+
+                    return Ok(LighterSubmittedOrder { tx_hash, nonce });
+                }
                 Err(e) => match e {
                     TradingError::InvalidNonce(e) => {
                         last_nonce_error = Some(e.clone());
@@ -1158,15 +2054,18 @@ impl PerpExchange for LighterClient {
     /// # Errors
     ///
     /// Returns `TradingError` if:
-    /// * Existing positions are found (atomic operation violation)
+    /// * A position is already open on the same market (atomic operation violation)
     /// * Price retrieval fails
     /// * Order execution fails after 3 attempts
     /// * Position verification fails
     async fn open_position(&self, token: Token, side: PositionSide, close_at: DateTime<Utc>, amount_usdc: Decimal) -> Result<Position, TradingError> {
-        // First, check if any position is already open
-        if let Ok(positions) = self.get_active_positions().await {
-            if !positions.is_empty() {
-                return Err(TradingError::AtomicOperationFailed("Position already open".to_string()));
+        // First, check if a position is already open on this market (other markets may hold positions)
+        if let Ok(Some(position)) = self.get_market_position(&token).await {
+            if position.position_value > Decimal::ZERO {
+                return Err(TradingError::AtomicOperationFailed(format!(
+                    "Position already open on {}",
+                    token.get_symbol_string(Exchange::Lighter)
+                )));
             }
         }
 
@@ -1193,9 +2092,9 @@ impl PerpExchange for LighterClient {
 
             // 2. Attempt to execute the market order
             let order_hash = match self.execute_market_order(&token, side, base_amount, price, false).await {
-                Ok(hash) => {
-                    info!("#{} | Attempt {}/3: Order sent: {}", self.wallet.id, attempt, hash);
-                    hash
+                Ok(order) => {
+                    info!("#{} | Attempt {}/3: Order sent: {}", self.wallet.id, attempt, order.tx_hash);
+                    order.tx_hash
                 },
                 Err(e) => {
                     last_error = Some(e);
@@ -1233,6 +2132,7 @@ impl PerpExchange for LighterClient {
                                 opened_at: Utc::now(),
                                 close_at,
                                 closed_at: None,
+                                close_price: None,
                                 realized_pnl: None,
                                 updated_at: Utc::now(),
                             });
@@ -1306,3 +2206,124 @@ impl PerpExchange for LighterClient {
         todo!("Lighter close_position not fully implemented for {}", position.side);
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Client of wallet `wallet_id` that is never used to reach the exchange
+    pub(crate) fn offline_client(wallet_id: u8) -> LighterClient {
+        LighterClient {
+            wallet: Wallet {
+                id: wallet_id,
+                private_key: String::new(),
+                proxy: None,
+                lighter_api_key: String::new(),
+                solana_private_key: None,
+            },
+            account_index: wallet_id as u32,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_private_key: String::new(),
+            api_public_key: String::new(),
+            signer_client: crate::perp::lighter::signer::tests::offline_signer(),
+            price_guard: PriceGuard::new(Default::default()),
+            taker_fee_bps: 0.0,
+            order_ledger: None,
+        }
+    }
+
+    pub(crate) fn position(sign: i32, size: Decimal, avg_entry_price: Decimal) -> LighterPosition {
+        LighterPosition {
+            market_id: 0,
+            symbol: "ETH".to_string(),
+            initial_margin_fraction: "0.2".to_string(),
+            open_order_count: 0,
+            pending_order_count: 0,
+            position_tied_order_count: 0,
+            sign,
+            position: size,
+            avg_entry_price,
+            position_value: size * avg_entry_price,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            liquidation_price: Decimal::ZERO,
+            total_funding_paid_out: None,
+            margin_mode: 0,
+            allocated_margin: None,
+        }
+    }
+
+    #[test]
+    fn exit_price_is_backed_out_of_the_realized_pnl() {
+        // Selling 1 of a 3 @ 3000 long realized +50, so it sold @ 3050
+        let before = position(1, dec!(3), dec!(3000));
+        let after = LighterPosition { realized_pnl: dec!(50), ..position(1, dec!(2), dec!(3000)) };
+        assert_eq!(LighterClient::exit_fill_price(&before, &after), Some(dec!(3050)));
+
+        // Buying back a whole 2 @ 3000 short for -40 means it was bought @ 3020
+        let before = LighterPosition { realized_pnl: dec!(10), ..position(-1, dec!(2), dec!(3000)) };
+        let after = LighterPosition { realized_pnl: dec!(-30), ..position(0, Decimal::ZERO, Decimal::ZERO) };
+        assert_eq!(LighterClient::exit_fill_price(&before, &after), Some(dec!(3020)));
+    }
+
+    #[test]
+    fn no_exit_price_when_the_position_did_not_shrink() {
+        let before = position(1, dec!(2), dec!(3000));
+
+        for after in [position(1, dec!(2), dec!(3000)), position(1, dec!(3), dec!(3000)), position(-1, dec!(1), dec!(3000))] {
+            assert_eq!(LighterClient::exit_fill_price(&before, &after), None);
+        }
+    }
+
+    #[test]
+    fn fill_of_a_new_position_is_its_entry() {
+        let after = position(1, dec!(2), dec!(3000));
+
+        assert_eq!(LighterClient::filled_size(None, &after), Some(dec!(2)));
+        assert_eq!(LighterClient::average_fill_price(None, &after), Some(dec!(3000)));
+
+        // A flat position reported before the fill counts as no position
+        let flat = position(-1, Decimal::ZERO, Decimal::ZERO);
+        assert_eq!(LighterClient::average_fill_price(Some(&flat), &after), Some(dec!(3000)));
+    }
+
+    #[test]
+    fn fill_added_to_a_position_is_backed_out_of_the_average_entry() {
+        // 1 @ 3000 grown to 3 @ 3100 means 2 were filled @ 3150
+        let before = position(-1, dec!(1), dec!(3000));
+        let after = position(-1, dec!(3), dec!(3100));
+
+        assert_eq!(LighterClient::filled_size(Some(&before), &after), Some(dec!(2)));
+        assert_eq!(LighterClient::average_fill_price(Some(&before), &after), Some(dec!(3150)));
+    }
+
+    #[test]
+    fn no_fill_when_the_position_did_not_grow() {
+        let before = position(1, dec!(2), dec!(3000));
+
+        for after in [position(1, dec!(2), dec!(3000)), position(1, dec!(1), dec!(3000)), position(-1, dec!(1), dec!(3000))] {
+            assert_eq!(LighterClient::filled_size(Some(&before), &after), None);
+            assert_eq!(LighterClient::average_fill_price(Some(&before), &after), None);
+        }
+    }
+
+    #[test]
+    fn initial_margin_fraction_allows_at_least_the_leverage() {
+        assert_eq!(initial_margin_fraction(1.0).unwrap(), 10_000);
+        assert_eq!(initial_margin_fraction(2.0).unwrap(), 5_000);
+        assert_eq!(initial_margin_fraction(3.0).unwrap(), 3_333);
+        assert_eq!(initial_margin_fraction(20_000.0).unwrap(), 1);
+
+        for leverage in [0.5, 0.0, -2.0, f64::NAN, f64::INFINITY] {
+            assert!(initial_margin_fraction(leverage).is_err(), "{}", leverage);
+        }
+    }
+
+    #[test]
+    fn initial_margin_fraction_matches_the_reported_percentage() {
+        // The account reads 3x back as "33.33"
+        let reported = LighterPosition { initial_margin_fraction: "33.33".to_string(), ..position(1, dec!(1), dec!(3000)) };
+        assert_eq!(reported.initial_margin_fraction_bps(), Some(initial_margin_fraction(3.0).unwrap()));
+    }
+}
@@ -1,3 +1,3 @@
 pub mod client;
 pub mod models;
-pub mod signer;
\ No newline at end of file
+pub mod signer;pub mod price;
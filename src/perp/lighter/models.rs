@@ -1,8 +1,10 @@
 use chrono::Utc;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use crate::helpers::deserialize_decimal_from_string;
+use crate::model::PositionSide;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LighterAccount {
@@ -24,6 +26,32 @@ pub struct LighterAccount {
     pub positions: Option<Vec<LighterPosition>>,
 }
 
+impl LighterAccount {
+    /// Account value divided by the initial margin of its open positions
+    ///
+    /// Returns `None` when no margin is in use or the values cannot be parsed.
+    pub fn collateral_ratio(&self) -> Option<Decimal> {
+        let account_value = Decimal::from_str_exact(&self.total_asset_value).ok()?;
+
+        let required_margin: Decimal = self
+            .positions
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|p| {
+                let fraction = Decimal::from_str_exact(&p.initial_margin_fraction).ok()?;
+                Some(p.position_value * fraction / Decimal::ONE_HUNDRED)
+            })
+            .sum();
+
+        if required_margin <= Decimal::ZERO {
+            return None;
+        }
+
+        Some(account_value / required_margin)
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LighterPoints {
@@ -53,6 +81,9 @@ pub struct LighterPosition {
     #[serde(deserialize_with = "deserialize_decimal_from_string")]
     pub liquidation_price: Decimal,
     pub total_funding_paid_out: Option<String>,
+    /// `CROSS_MARGIN_MODE` or `ISOLATED_MARGIN_MODE`
+    #[serde(default)]
+    pub margin_mode: i32,
     pub allocated_margin: Option<String>,
 }
 
@@ -80,6 +111,121 @@ pub struct LighterTx {
     pub hash: String,
 }
 
+/// One OHLC candle from the candlesticks endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterCandle {
+    /// Candle open time (ms)
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// One resting order of the order book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterBookLevel {
+    #[serde(deserialize_with = "deserialize_decimal_from_string")]
+    pub price: Decimal,
+    #[serde(deserialize_with = "deserialize_decimal_from_string")]
+    pub remaining_base_amount: Decimal,
+}
+
+/// Resting orders of a market, best price first on each side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterOrderBook {
+    pub asks: Vec<LighterBookLevel>,
+    pub bids: Vec<LighterBookLevel>,
+}
+
+impl LighterOrderBook {
+    /// Midpoint between the best bid and the best ask
+    pub fn mid_price(&self) -> Option<Decimal> {
+        let best_ask = self.asks.first()?.price;
+        let best_bid = self.bids.first()?.price;
+        Some((best_ask + best_bid) / Decimal::TWO)
+    }
+}
+
+/// Market statistics from the orderBookDetails endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterMarketStats {
+    pub symbol: String,
+    pub market_id: i32,
+    pub last_trade_price: f64,
+    /// Open interest in base token units
+    pub open_interest: f64,
+}
+
+impl LighterMarketStats {
+    /// Open interest in USDC at the last trade price
+    pub fn open_interest_usdc(&self) -> Decimal {
+        Decimal::from_f64(self.open_interest * self.last_trade_price).unwrap_or(Decimal::ZERO)
+    }
+}
+
+/// One hourly funding payment from the fundings endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterFunding {
+    pub timestamp: i64,
+    /// Funding rate in percent
+    #[serde(deserialize_with = "deserialize_decimal_from_string")]
+    pub rate: Decimal,
+    /// Side that paid the funding ("long" or "short")
+    pub direction: String,
+}
+
+impl LighterFunding {
+    /// Funding paid by longs as a fraction of notional (negative when shorts paid)
+    pub fn long_rate(&self) -> Decimal {
+        let rate = self.rate / Decimal::ONE_HUNDRED;
+        if self.direction.eq_ignore_ascii_case("short") { -rate } else { rate }
+    }
+}
+
+/// An order accepted by `sendTx`
+#[derive(Debug, Clone)]
+pub struct LighterSubmittedOrder {
+    pub tx_hash: String,
+    /// Nonce the order was signed with
+    pub nonce: i64,
+}
+
+/// A confirmed order submitted as one slice of a larger leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LighterOrderFill {
+    pub tx_hash: String,
+    /// Nonce the order was signed with
+    pub nonce: i64,
+    /// Limit price scaled by the token's price denomination
+    pub price: u64,
+    pub base_amount: u64,
+    /// Notional filled, from the position change at the fill price (the requested notional if unknown)
+    pub amount_usdc: Decimal,
+    pub executed_at: i64,
+    /// Order book mid when the order was priced
+    pub expected_price: Decimal,
+    /// Average execution price, derived from the position change (None if unknown)
+    pub fill_price: Option<Decimal>,
+}
+
+impl LighterOrderFill {
+    /// Adverse slippage of the fill against the expected price (in bps).
+    /// Positive when a long paid more or a short received less than the mid.
+    pub fn slippage_bps(&self, side: PositionSide) -> Option<Decimal> {
+        let fill_price = self.fill_price?;
+        if self.expected_price <= Decimal::ZERO {
+            return None;
+        }
+
+        let diff = match side {
+            PositionSide::Long => fill_price - self.expected_price,
+            PositionSide::Short => self.expected_price - fill_price,
+        };
+        Some(diff / self.expected_price * Decimal::from(10_000))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct LighterOrderInfo {
@@ -151,6 +297,14 @@ impl LighterOrder {
 
 
 impl LighterPosition {
+    /// Initial margin fraction of the market in basis points (10000 / leverage)
+    ///
+    /// The API reports it as a percentage, e.g. "33.33" for 3x.
+    pub fn initial_margin_fraction_bps(&self) -> Option<i32> {
+        let percent = Decimal::from_str_exact(&self.initial_margin_fraction).ok()?;
+        (percent * Decimal::ONE_HUNDRED).round().to_i32()
+    }
+
     /// Returns the percentage distance to liquidation for this position.
     ///
     /// This value indicates how close the position is to its liquidation price,
@@ -207,3 +361,98 @@ impl LighterPosition {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Account worth `total_asset_value` with one position per (position value, initial margin fraction in %)
+    pub(crate) fn account(total_asset_value: Decimal, positions: &[(Decimal, Decimal)]) -> LighterAccount {
+        let positions: Vec<serde_json::Value> = positions
+            .iter()
+            .enumerate()
+            .map(|(market_id, (value, fraction))| {
+                json!({
+                    "market_id": market_id,
+                    "symbol": "ETH",
+                    "initial_margin_fraction": fraction.to_string(),
+                    "open_order_count": 0,
+                    "pending_order_count": 0,
+                    "position_tied_order_count": 0,
+                    "sign": 1,
+                    "position": "1",
+                    "avg_entry_price": value.to_string(),
+                    "position_value": value.to_string(),
+                    "unrealized_pnl": "0",
+                    "realized_pnl": "0",
+                    "liquidation_price": "0",
+                    "total_funding_paid_out": null,
+                    "allocated_margin": null,
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "account_type": 0,
+            "index": 1,
+            "l1_address": "0x0",
+            "total_order_count": 0,
+            "total_isolated_order_count": 0,
+            "pending_order_count": 0,
+            "available_balance": "0",
+            "status": 1,
+            "collateral": total_asset_value.to_string(),
+            "account_index": 1,
+            "name": "",
+            "description": "",
+            "can_invite": false,
+            "total_asset_value": total_asset_value.to_string(),
+            "cross_asset_value": total_asset_value.to_string(),
+            "positions": positions,
+        }))
+        .unwrap()
+    }
+
+    /// Child order of `amount_usdc` priced at a mid of `expected_price`
+    pub(crate) fn fill(amount_usdc: Decimal, expected_price: Decimal, fill_price: Option<Decimal>) -> LighterOrderFill {
+        LighterOrderFill {
+            tx_hash: "0xa".to_string(),
+            nonce: 7,
+            price: 0,
+            base_amount: 0,
+            amount_usdc,
+            executed_at: 0,
+            expected_price,
+            fill_price,
+        }
+    }
+
+    #[test]
+    fn slippage_is_adverse_to_the_side() {
+        let fill = fill(dec!(1000), dec!(3000), Some(dec!(3003)));
+
+        // A long paying more and a short selling higher than the mid
+        assert_eq!(fill.slippage_bps(PositionSide::Long), Some(dec!(10)));
+        assert_eq!(fill.slippage_bps(PositionSide::Short), Some(dec!(-10)));
+    }
+
+    #[test]
+    fn no_slippage_without_a_fill_price_or_a_mid() {
+        assert_eq!(fill(dec!(1000), dec!(3000), None).slippage_bps(PositionSide::Long), None);
+        assert_eq!(fill(dec!(1000), Decimal::ZERO, Some(dec!(3003))).slippage_bps(PositionSide::Long), None);
+    }
+
+    #[test]
+    fn collateral_ratio_is_account_value_over_initial_margin() {
+        // 10% of 3000 plus 20% of 500 = 400 of margin
+        let account = account(dec!(1000), &[(dec!(3000), dec!(10)), (dec!(500), dec!(20))]);
+        assert_eq!(account.collateral_ratio(), Some(dec!(2.5)));
+    }
+
+    #[test]
+    fn no_collateral_ratio_without_margin_in_use() {
+        assert_eq!(account(dec!(1000), &[]).collateral_ratio(), None);
+        assert_eq!(account(dec!(1000), &[(Decimal::ZERO, dec!(10))]).collateral_ratio(), None);
+    }
+}
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+use crate::{
+    config::PriceConfig,
+    error::TradingError,
+    perp::lighter::models::{LighterCandle, LighterOrderBook},
+};
+
+/// A market price that passed the sanity checks
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct MarketPrice {
+    /// Midpoint between the best bid and the best ask
+    pub mid: Decimal,
+    pub best_bid: Decimal,
+    pub best_ask: Decimal,
+    /// Close of the latest 1-minute candle
+    pub last_close: Decimal,
+    /// When the latest candle opened
+    pub last_trade_at: DateTime<Utc>,
+}
+
+/// Derives a market price from the order book and rejects prices that cannot be trusted
+///
+/// The price is the best bid/ask mid. The latest 1-minute candle is only used as a
+/// cross-check: the price is rejected if the mid deviates from its close by more than
+/// `max_deviation_pct`, or if the market has not traded for `max_staleness_seconds`.
+#[derive(Debug, Clone)]
+pub struct PriceGuard {
+    config: PriceConfig,
+}
+
+impl PriceGuard {
+    pub fn new(config: PriceConfig) -> Self {
+        Self { config }
+    }
+
+    /// Number of 1-minute candles that cover the staleness window
+    pub fn candle_lookback(&self) -> usize {
+        (self.config.max_staleness_seconds / 60 + 1) as usize
+    }
+
+    /// Validate an order book against recent candles
+    ///
+    /// # Arguments
+    /// * `symbol` - Market symbol, used in error messages
+    /// * `book` - Order book, best price first on each side
+    /// * `candles` - Recent 1-minute candles, oldest first (may be empty)
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// * `Ok(MarketPrice)` - The mid price passed all checks
+    /// * `Err(TradingError::MarketDataUnavailable)` - If the book is empty or crossed
+    /// * `Err(TradingError::StalePrice)` - If the market has not traded recently
+    /// * `Err(TradingError::PriceDeviation)` - If the mid is too far from the last trade
+    pub fn check(
+        &self,
+        symbol: &str,
+        book: &LighterOrderBook,
+        candles: &[LighterCandle],
+        now: DateTime<Utc>,
+    ) -> Result<MarketPrice, TradingError> {
+        let (best_bid, best_ask) = match (book.bids.first(), book.asks.first()) {
+            (Some(bid), Some(ask)) => (bid.price, ask.price),
+            _ => {
+                return Err(TradingError::MarketDataUnavailable(format!(
+                    "Order book for {} has no bid or no ask",
+                    symbol
+                )))
+            }
+        };
+
+        if best_bid <= Decimal::ZERO || best_bid >= best_ask {
+            return Err(TradingError::MarketDataUnavailable(format!(
+                "Order book for {} is invalid (bid {} / ask {})",
+                symbol, best_bid, best_ask
+            )));
+        }
+
+        let mid = (best_bid + best_ask) / Decimal::TWO;
+
+        let last = candles.last().ok_or_else(|| {
+            TradingError::StalePrice(format!(
+                "{} has not traded in the last {}s",
+                symbol, self.config.max_staleness_seconds
+            ))
+        })?;
+
+        let last_trade_at = DateTime::<Utc>::from_timestamp_millis(last.timestamp).ok_or_else(|| {
+            TradingError::MarketDataUnavailable(format!("Invalid candle timestamp {} for {}", last.timestamp, symbol))
+        })?;
+
+        let age = (now - last_trade_at).num_seconds();
+        if age > self.config.max_staleness_seconds as i64 {
+            return Err(TradingError::StalePrice(format!(
+                "Last trade of {} was {}s ago (max {}s)",
+                symbol, age, self.config.max_staleness_seconds
+            )));
+        }
+
+        let last_close = Decimal::from_f64(last.close)
+            .filter(|close| *close > Decimal::ZERO)
+            .ok_or_else(|| TradingError::MarketDataUnavailable(format!("Invalid candle close {} for {}", last.close, symbol)))?;
+
+        let deviation_pct = ((mid - last_close) / last_close).abs() * Decimal::ONE_HUNDRED;
+        let max_deviation_pct = Decimal::from_f64(self.config.max_deviation_pct).unwrap_or(Decimal::ZERO);
+        if deviation_pct > max_deviation_pct {
+            return Err(TradingError::PriceDeviation(format!(
+                "{} mid {} deviates {:.2}% from last trade {} (max {:.2}%)",
+                symbol, mid, deviation_pct, last_close, max_deviation_pct
+            )));
+        }
+
+        Ok(MarketPrice {
+            mid,
+            best_bid,
+            best_ask,
+            last_close,
+            last_trade_at,
+        })
+    }
+
+    /// Unchecked price for reduce-only orders, which must not be blocked by the checks above
+    ///
+    /// # Returns
+    /// * `Some(Decimal)` - The order book mid if the book is valid, otherwise the close of the latest candle
+    /// * `None` - If there is neither a valid book nor a candle
+    pub fn fallback_price(book: Option<&LighterOrderBook>, candles: &[LighterCandle]) -> Option<Decimal> {
+        let book_mid = book.and_then(|book| match (book.bids.first(), book.asks.first()) {
+            (Some(bid), Some(ask)) if bid.price > Decimal::ZERO && bid.price < ask.price => {
+                Some((bid.price + ask.price) / Decimal::TWO)
+            }
+            _ => None,
+        });
+
+        book_mid.or_else(|| {
+            candles
+                .last()
+                .and_then(|candle| Decimal::from_f64(candle.close))
+                .filter(|close| *close > Decimal::ZERO)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perp::lighter::models::LighterBookLevel;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn guard() -> PriceGuard {
+        PriceGuard::new(PriceConfig { max_deviation_pct: 1.0, max_staleness_seconds: 300 })
+    }
+
+    fn book(bid: Decimal, ask: Decimal) -> LighterOrderBook {
+        let level = |price| LighterBookLevel { price, remaining_base_amount: dec!(1) };
+        LighterOrderBook { asks: vec![level(ask)], bids: vec![level(bid)] }
+    }
+
+    fn candle(age_seconds: i64, close: f64) -> LighterCandle {
+        LighterCandle {
+            timestamp: (now() - Duration::seconds(age_seconds)).timestamp_millis(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+        }
+    }
+
+    #[test]
+    fn accepts_a_fresh_mid_close_to_the_last_trade() {
+        let price = guard().check("ETH", &book(dec!(2999), dec!(3001)), &[candle(600, 2000.0), candle(60, 3010.0)], now()).unwrap();
+
+        assert_eq!(price.mid, dec!(3000));
+        assert_eq!(price.last_close, dec!(3010));
+        assert_eq!(price.last_trade_at, now() - Duration::seconds(60));
+    }
+
+    #[test]
+    fn rejects_empty_or_crossed_books() {
+        let empty = LighterOrderBook { asks: Vec::new(), bids: Vec::new() };
+        let candles = [candle(60, 3000.0)];
+
+        assert!(matches!(guard().check("ETH", &empty, &candles, now()), Err(TradingError::MarketDataUnavailable(_))));
+        assert!(matches!(
+            guard().check("ETH", &book(dec!(3001), dec!(2999)), &candles, now()),
+            Err(TradingError::MarketDataUnavailable(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_stale_markets() {
+        let book = book(dec!(2999), dec!(3001));
+
+        assert!(matches!(guard().check("ETH", &book, &[], now()), Err(TradingError::StalePrice(_))));
+        assert!(matches!(guard().check("ETH", &book, &[candle(301, 3000.0)], now()), Err(TradingError::StalePrice(_))));
+    }
+
+    #[test]
+    fn rejects_mids_far_from_the_last_trade() {
+        let result = guard().check("ETH", &book(dec!(2999), dec!(3001)), &[candle(60, 3040.0)], now());
+
+        assert!(matches!(result, Err(TradingError::PriceDeviation(_))));
+    }
+
+    #[test]
+    fn falls_back_to_the_mid_then_the_last_close() {
+        let candles = [candle(3600, 2900.0)];
+
+        assert_eq!(PriceGuard::fallback_price(Some(&book(dec!(2999), dec!(3001))), &candles), Some(dec!(3000)));
+        assert_eq!(PriceGuard::fallback_price(Some(&book(dec!(3001), dec!(2999))), &candles), Some(dec!(2900)));
+        assert_eq!(PriceGuard::fallback_price(None, &candles), Some(dec!(2900)));
+        assert_eq!(PriceGuard::fallback_price(None, &[]), None);
+    }
+}
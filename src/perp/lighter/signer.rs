@@ -1186,4 +1186,22 @@ impl Clone for SignerClient {
             nonce_manager: Arc::clone(&self.nonce_manager),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Signer that never loads a key into the native library, for clients that send nothing
+    pub(crate) fn offline_signer() -> SignerClient {
+        SignerClient {
+            url: "https://testnet.zklighter.elliot.ai".to_string(),
+            private_keys: HashMap::new(),
+            chain_id: 300,
+            api_key_index: 0,
+            account_index: 0,
+            start_api_key: 0,
+            end_api_key: 0,
+            nonce_manager: Arc::new(Mutex::new(NonceManager::new(0))),
+        }
+    }
+}
@@ -248,6 +248,7 @@ impl RangerClient {
                         opened_at: Utc::now(),
                         close_at,
                         closed_at: None,
+                        close_price: None,
                         realized_pnl: None,
                         updated_at: Utc::now(),
                     });
@@ -0,0 +1,251 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    config::CircuitBreakerConfig,
+    error::TradingError,
+    perp::lighter::client::LighterClient,
+    storage::{storage_risk::{BreakerState, EquitySnapshot}, traits::{OrderStore, RiskStore, StrategyStore}},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+/// Halts new strategies when losses over rolling windows cross the configured limits
+///
+/// Account value and unrealized PnL across all wallets are snapshotted periodically.
+/// Losses are measured from trading alone (realized PnL of closed strategies, the change
+/// in unrealized PnL and entry fees), so deposits, withdrawals and transfers never trip
+/// it. The tripped state is stored in the database and survives restarts until it is
+/// reset manually.
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    storage: Arc<dyn RiskStore>,
+    strategy_storage: Arc<dyn StrategyStore>,
+    order_storage: Arc<dyn OrderStore>,
+    last_snapshot_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+/// Trading PnL between an earlier snapshot and the current one
+struct TradingPnl {
+    /// PnL realized by strategies closed since the earlier snapshot, net of exit fees
+    realized: Decimal,
+    unrealized_change: Decimal,
+    /// Fees paid on orders that opened or grew positions
+    entry_fees: Decimal,
+}
+
+impl TradingPnl {
+    fn loss(&self) -> Decimal {
+        self.entry_fees - self.realized - self.unrealized_change
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "realized {:.2}, unrealized change {:.2}, entry fees {:.2}",
+            self.realized, self.unrealized_change, self.entry_fees
+        )
+    }
+}
+
+impl CircuitBreaker {
+    pub async fn new(
+        config: CircuitBreakerConfig,
+        storage: Arc<dyn RiskStore>,
+        strategy_storage: Arc<dyn StrategyStore>,
+        order_storage: Arc<dyn OrderStore>,
+    ) -> Result<Self, TradingError> {
+        Ok(Self {
+            config,
+            storage,
+            strategy_storage,
+            order_storage,
+            last_snapshot_at: Mutex::new(None),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn flatten_on_trip(&self) -> bool {
+        self.config.flatten_on_trip
+    }
+
+    /// Current breaker state if it is tripped
+    pub async fn tripped_state(&self) -> Result<Option<BreakerState>, TradingError> {
+        let state = self.storage.get_breaker_state().await?;
+        Ok(state.tripped.then_some(state))
+    }
+
+    /// Take an equity snapshot if the snapshot interval has elapsed
+    ///
+    /// # Arguments
+    /// * `clients` - Lighter client of every wallet managed by the bot
+    ///
+    /// # Returns
+    /// * `Ok(Some(EquitySnapshot))` - A new snapshot was taken and stored
+    /// * `Ok(None)` - The last snapshot is still recent
+    /// * `Err(TradingError)` - If an account cannot be fetched or the snapshot cannot be saved
+    pub async fn snapshot_if_due(&self, clients: &[(u8, LighterClient)]) -> Result<Option<EquitySnapshot>, TradingError> {
+        let now = Utc::now();
+        let interval = Duration::seconds(self.config.snapshot_interval_seconds as i64);
+        let last = *self.last_snapshot_at.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|taken_at| now - taken_at < interval) {
+            return Ok(None);
+        }
+
+        let accounts = futures::future::try_join_all(clients.iter().map(|(_, client)| client.get_account())).await?;
+
+        let mut snapshot = EquitySnapshot {
+            taken_at: now,
+            account_value: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+        };
+        for account in accounts {
+            snapshot.account_value += account.total_asset_value.parse().unwrap_or(Decimal::ZERO);
+            snapshot.unrealized_pnl += account
+                .positions
+                .unwrap_or_default()
+                .iter()
+                .map(|p| p.unrealized_pnl)
+                .sum::<Decimal>();
+        }
+
+        // Keep a little more than the weekly window
+        self.storage.save_equity_snapshot(&snapshot, now - Duration::days(8)).await?;
+        *self.last_snapshot_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(now);
+
+        Ok(Some(snapshot))
+    }
+
+    /// Trading PnL from snapshot `from` up to `current`
+    async fn trading_pnl(&self, from: &EquitySnapshot, current: &EquitySnapshot) -> Result<TradingPnl, TradingError> {
+        Ok(TradingPnl {
+            realized: self.strategy_storage.get_realized_pnl_since(from.taken_at).await?,
+            unrealized_change: current.unrealized_pnl - from.unrealized_pnl,
+            entry_fees: self.order_storage.get_entry_fees_since(from.taken_at).await?,
+        })
+    }
+
+    /// Compare a snapshot against the daily, weekly and drawdown limits
+    ///
+    /// The drawdown is the trading loss since the 7-day account value peak, as a
+    /// percentage of that peak.
+    ///
+    /// # Returns
+    /// * `Ok(Some(String))` - Description of the first limit crossed
+    /// * `Ok(None)` - Within limits
+    pub async fn evaluate(&self, current: &EquitySnapshot) -> Result<Option<String>, TradingError> {
+        let week = self
+            .storage
+            .get_equity_snapshots_since(current.taken_at - Duration::days(7))
+            .await?;
+        let day_start = current.taken_at - Duration::days(1);
+        let limit = |value: f64| Decimal::from_f64(value).unwrap_or(Decimal::ZERO);
+
+        if let Some(first) = week.iter().find(|s| s.taken_at >= day_start) {
+            let pnl = self.trading_pnl(first, current).await?;
+            if pnl.loss() > limit(self.config.max_daily_loss_usdc) {
+                return Ok(Some(format!(
+                    "24h loss {:.2} USDC ({}) exceeds {:.2} USDC",
+                    pnl.loss(), pnl.describe(), self.config.max_daily_loss_usdc
+                )));
+            }
+        }
+
+        if let Some(first) = week.first() {
+            let pnl = self.trading_pnl(first, current).await?;
+            if pnl.loss() > limit(self.config.max_weekly_loss_usdc) {
+                return Ok(Some(format!(
+                    "7d loss {:.2} USDC ({}) exceeds {:.2} USDC",
+                    pnl.loss(), pnl.describe(), self.config.max_weekly_loss_usdc
+                )));
+            }
+        }
+
+        if let Some(peak) = week.iter().max_by_key(|s| s.account_value) {
+            if peak.account_value > Decimal::ZERO {
+                let pnl = self.trading_pnl(peak, current).await?;
+                let drawdown_pct = pnl.loss() / peak.account_value * Decimal::ONE_HUNDRED;
+                if drawdown_pct > limit(self.config.max_drawdown_pct) {
+                    return Ok(Some(format!(
+                        "Drawdown {:.2}% from 7d peak {:.2} USDC ({}) exceeds {:.2}%",
+                        drawdown_pct, peak.account_value, pnl.describe(), self.config.max_drawdown_pct
+                    )));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Trip the breaker
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The breaker was tripped by this call (callers alert and flatten once)
+    /// * `Ok(false)` - It was already tripped
+    pub async fn trip(&self, reason: &str) -> Result<bool, TradingError> {
+        self.storage.trip_breaker(reason).await
+    }
+
+    /// Reset the breaker after manual review
+    pub async fn reset(&self) -> Result<(), TradingError> {
+        self.storage.reset_breaker().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{conformance, Storage};
+    use crate::trader::strategy::{StrategyStatus, TradingStrategy};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn snapshot(hour: u32, account_value: Decimal, unrealized_pnl: Decimal) -> EquitySnapshot {
+        EquitySnapshot { taken_at: at(hour), account_value, unrealized_pnl }
+    }
+
+    async fn breaker(storage: &Storage, snapshots: &[EquitySnapshot]) -> CircuitBreaker {
+        for snapshot in snapshots {
+            storage.risk.save_equity_snapshot(snapshot, at(0)).await.unwrap();
+        }
+        CircuitBreaker::new(CircuitBreakerConfig::default(), storage.risk.clone(), storage.strategies.clone(), storage.orders.clone())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn withdrawals_do_not_trip_the_breaker() {
+        let storage = Storage::memory();
+        let current = snapshot(2, dec!(4000), dec!(-5));
+        let breaker = breaker(&storage, &[snapshot(1, dec!(10000), Decimal::ZERO), current.clone()]).await;
+
+        assert_eq!(breaker.evaluate(&current).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn trading_losses_trip_the_breaker() {
+        let storage = Storage::memory();
+        let closed = TradingStrategy {
+            status: StrategyStatus::Closed,
+            closed_at: Some(at(2)),
+            realized_pnl: Some(dec!(-200)),
+            ..conformance::strategy("s1", Vec::new(), Vec::new())
+        };
+        storage.strategies.save_strategy(&closed).await.unwrap();
+        storage.orders.record_order(&conformance::order("0xa", 1, at(1))).await.unwrap();
+        storage.orders.update_order_fill("0xa", Some(dec!(3100)), Some(dec!(20))).await.unwrap();
+
+        // A deposit hides the loss in account value
+        let current = snapshot(3, dec!(15000), dec!(-40));
+        let breaker = breaker(&storage, &[snapshot(1, dec!(10000), Decimal::ZERO), current.clone()]).await;
+
+        let reason = breaker.evaluate(&current).await.unwrap().unwrap();
+        assert!(reason.starts_with("24h loss 260.00 USDC"), "{}", reason);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    config::KillSwitchConfig,
+    error::TradingError,
+    storage::{storage_risk::KillSwitchState, traits::RiskStore},
+};
+
+use tokio::{
+    sync::Notify,
+    time::{sleep, Duration},
+};
+
+/// What activated the kill switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillSwitchSource {
+    Env,
+    File,
+    Telegram,
+    Api,
+    Menu,
+}
+
+impl std::fmt::Display for KillSwitchSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillSwitchSource::Env => write!(f, "env"),
+            KillSwitchSource::File => write!(f, "file"),
+            KillSwitchSource::Telegram => write!(f, "telegram"),
+            KillSwitchSource::Api => write!(f, "api"),
+            KillSwitchSource::Menu => write!(f, "menu"),
+        }
+    }
+}
+
+/// Latched, process-wide stop for all trading
+///
+/// The state lives in the database, so the switch stays active across restarts and
+/// is shared by every instance until it is cleared explicitly. Activations wake up
+/// whoever waits on [`KillSwitch::notified`], so the trader reacts without waiting
+/// for its next tick.
+#[derive(Clone)]
+pub struct KillSwitch {
+    config: KillSwitchConfig,
+    storage: Arc<dyn RiskStore>,
+    notify: Arc<Notify>,
+}
+
+impl KillSwitch {
+    pub async fn new(config: KillSwitchConfig, storage: Arc<dyn RiskStore>) -> Result<Self, TradingError> {
+        Ok(Self {
+            config,
+            storage,
+            notify: Arc::new(Notify::new()),
+        })
+    }
+
+    pub fn config(&self) -> &KillSwitchConfig {
+        &self.config
+    }
+
+    /// Full kill switch state, active or not
+    pub async fn state(&self) -> Result<KillSwitchState, TradingError> {
+        self.storage.get_kill_switch_state().await
+    }
+
+    /// Current state if the kill switch is active
+    pub async fn active_state(&self) -> Result<Option<KillSwitchState>, TradingError> {
+        let state = self.storage.get_kill_switch_state().await?;
+        Ok(state.active.then_some(state))
+    }
+
+    /// Activate the kill switch
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was activated by this call
+    /// * `Ok(false)` - It was already active
+    pub async fn trigger(&self, reason: &str, source: KillSwitchSource) -> Result<bool, TradingError> {
+        let activated = self.storage.trigger_kill_switch(reason, &source.to_string()).await?;
+        if activated {
+            error!("🛑 Kill switch activated ({}): {}", source, reason);
+            self.notify.notify_one();
+        }
+
+        Ok(activated)
+    }
+
+    /// Clear the kill switch so trading can resume
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was active and is now cleared
+    /// * `Ok(false)` - It was not active
+    pub async fn clear(&self) -> Result<bool, TradingError> {
+        let cleared = self.storage.clear_kill_switch().await?;
+        if cleared {
+            info!("🟢 Kill switch cleared");
+            self.notify.notify_one();
+        }
+
+        Ok(cleared)
+    }
+
+    /// Wait until the kill switch is triggered or cleared
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+
+    /// Activate the kill switch if its environment variable is set or its sentinel file exists
+    pub async fn check_external_triggers(&self) -> Result<(), TradingError> {
+        if let Ok(value) = std::env::var(&self.config.env_var) {
+            let value = value.trim();
+            if !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false") {
+                let reason = format!("{} environment variable is set", self.config.env_var);
+                self.trigger(&reason, KillSwitchSource::Env).await?;
+            }
+        }
+
+        let path = Path::new(&self.config.sentinel_file);
+        if path.exists() {
+            let content = tokio::fs::read_to_string(path).await.unwrap_or_default();
+            let reason = match content.trim() {
+                "" => format!("Sentinel file {} exists", self.config.sentinel_file),
+                reason => reason.to_string(),
+            };
+            self.trigger(&reason, KillSwitchSource::File).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Poll the environment variable and sentinel file until the process exits
+    pub async fn watch_external_triggers(self) {
+        loop {
+            if let Err(e) = self.check_external_triggers().await {
+                error!("❌ Failed to check kill switch triggers: {}", e);
+            }
+
+            sleep(Duration::from_secs(self.config.poll_interval_seconds)).await;
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod portfolio;
+pub mod circuit_breaker;
+pub mod rescue;
+pub mod kill_switch;
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::{ExposureAction, ExposureConfig},
+    error::TradingError,
+    model::{token::Token, Exchange},
+    perp::lighter::client::LighterClient,
+    trader::strategy::WalletAllocation,
+};
+
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+/// Account value and gross notional of one wallet
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WalletExposure {
+    pub account_value: Decimal,
+    pub gross_notional: Decimal,
+}
+
+/// Gross notional currently held across all wallets
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioExposure {
+    /// Gross notional per Lighter symbol
+    pub by_token: HashMap<String, Decimal>,
+    /// Gross notional per exchange
+    pub by_exchange: HashMap<Exchange, Decimal>,
+    pub wallets: HashMap<u8, WalletExposure>,
+}
+
+impl PortfolioExposure {
+    /// Fetch the open positions and account value of every wallet on Lighter
+    ///
+    /// # Arguments
+    /// * `clients` - Lighter client of every wallet managed by the bot
+    ///
+    /// # Returns
+    /// * `Ok(PortfolioExposure)` - Current exposure
+    /// * `Err(TradingError)` - If any account cannot be fetched
+    pub async fn fetch(clients: &[(u8, LighterClient)]) -> Result<Self, TradingError> {
+        let accounts = futures::future::try_join_all(
+            clients.iter().map(|(wallet_id, client)| async move {
+                Ok::<_, TradingError>((*wallet_id, client.get_account().await?))
+            })
+        ).await?;
+
+        let mut exposure = Self::default();
+        for (wallet_id, account) in accounts {
+            let mut wallet = WalletExposure {
+                account_value: account.total_asset_value.parse().unwrap_or(Decimal::ZERO),
+                gross_notional: Decimal::ZERO,
+            };
+
+            for position in account.positions.unwrap_or_default() {
+                if position.position_value <= Decimal::ZERO {
+                    continue;
+                }
+
+                *exposure.by_token.entry(position.symbol.clone()).or_default() += position.position_value;
+                *exposure.by_exchange.entry(Exchange::Lighter).or_default() += position.position_value;
+                wallet.gross_notional += position.position_value;
+            }
+
+            exposure.wallets.insert(wallet_id, wallet);
+        }
+
+        Ok(exposure)
+    }
+
+    /// Gross notional held overall
+    pub fn total(&self) -> Decimal {
+        self.by_exchange.values().sum()
+    }
+}
+
+/// Checks plans against the portfolio exposure limits
+pub struct PortfolioRisk {
+    config: ExposureConfig,
+}
+
+impl PortfolioRisk {
+    pub fn new(config: ExposureConfig) -> Self {
+        Self { config }
+    }
+
+    /// Check a plan against every exposure limit, scaling it down or rejecting it
+    ///
+    /// Every leg is scaled by the same factor so the plan stays balanced. The factor is
+    /// the tightest headroom across the token, exchange, overall, wallet leverage and
+    /// open interest limits.
+    ///
+    /// # Arguments
+    /// * `exposure` - Current portfolio exposure
+    /// * `open_interest` - Open interest (in USDC) of every market traded by the plan
+    /// * `tokens` - Token traded by each allocation
+    /// * `allocations` - Planned legs, scaled in place
+    ///
+    /// # Returns
+    /// * `Ok(Decimal)` - Factor applied to the plan (1 when no limit is binding)
+    /// * `Err(TradingError::ExposureLimitExceeded)` - If the plan must be rejected
+    pub fn check_plan(
+        &self,
+        exposure: &PortfolioExposure,
+        open_interest: &HashMap<String, Decimal>,
+        tokens: &[Token],
+        allocations: &mut [WalletAllocation],
+    ) -> Result<Decimal, TradingError> {
+        let mut planned_by_token: HashMap<String, Decimal> = HashMap::new();
+        let mut planned_by_wallet: HashMap<u8, Decimal> = HashMap::new();
+        for (token, allocation) in tokens.iter().zip(allocations.iter()) {
+            *planned_by_token.entry(token.get_symbol_string(Exchange::Lighter)).or_default() += allocation.usdc_amount;
+            *planned_by_wallet.entry(allocation.wallet_id).or_default() += allocation.usdc_amount;
+        }
+        let planned_total: Decimal = planned_by_token.values().sum();
+
+        if planned_total <= Decimal::ZERO {
+            return Ok(Decimal::ONE);
+        }
+
+        // (headroom / planned notional, reason) of every limit, the smallest one binds
+        let mut limits: Vec<(Decimal, String)> = Vec::new();
+        let limit = |value: f64| Decimal::from_f64(value).unwrap_or(Decimal::ZERO);
+
+        for (symbol, planned) in &planned_by_token {
+            let current = exposure.by_token.get(symbol).copied().unwrap_or(Decimal::ZERO);
+            let max_token = limit(self.config.max_token_notional_usdc);
+            limits.push((
+                (max_token - current) / planned,
+                format!("{} gross notional {:.2} + {:.2} USDC > {:.2} USDC", symbol, current, planned, max_token),
+            ));
+
+            match open_interest.get(symbol) {
+                Some(oi) if *oi > Decimal::ZERO => {
+                    let max_share = oi * limit(self.config.max_open_interest_share);
+                    limits.push((
+                        (max_share - current) / planned,
+                        format!(
+                            "{} notional {:.2} + {:.2} USDC > {:.2}% of open interest ({:.2} USDC)",
+                            symbol, current, planned, self.config.max_open_interest_share * 100.0, oi
+                        ),
+                    ));
+                }
+                _ => warn!("⚠️ No open interest for {}, skipping the open interest limit", symbol),
+            }
+        }
+
+        let current_exchange = exposure.by_exchange.get(&Exchange::Lighter).copied().unwrap_or(Decimal::ZERO);
+        let max_exchange = limit(self.config.max_exchange_notional_usdc);
+        limits.push((
+            (max_exchange - current_exchange) / planned_total,
+            format!("{} gross notional {:.2} + {:.2} USDC > {:.2} USDC", Exchange::Lighter, current_exchange, planned_total, max_exchange),
+        ));
+
+        let current_total = exposure.total();
+        let max_total = limit(self.config.max_total_notional_usdc);
+        limits.push((
+            (max_total - current_total) / planned_total,
+            format!("Overall gross notional {:.2} + {:.2} USDC > {:.2} USDC", current_total, planned_total, max_total),
+        ));
+
+        let max_leverage = limit(self.config.max_wallet_leverage);
+        for (wallet_id, planned) in &planned_by_wallet {
+            let wallet = exposure.wallets.get(wallet_id).copied().unwrap_or_default();
+            let max_wallet = wallet.account_value * max_leverage;
+            limits.push((
+                (max_wallet - wallet.gross_notional) / planned,
+                format!(
+                    "Wallet #{} notional {:.2} + {:.2} USDC > {:.1}x its account value ({:.2} USDC)",
+                    wallet_id, wallet.gross_notional, planned, max_leverage, wallet.account_value
+                ),
+            ));
+        }
+
+        let Some((factor, reason)) = limits.into_iter().min_by(|a, b| a.0.cmp(&b.0)) else {
+            return Ok(Decimal::ONE);
+        };
+
+        if factor >= Decimal::ONE {
+            return Ok(Decimal::ONE);
+        }
+
+        let min_factor = limit(self.config.min_scale_factor);
+        if self.config.action == ExposureAction::Reject || factor < min_factor {
+            return Err(TradingError::ExposureLimitExceeded(reason));
+        }
+
+        // Round down so rounding never pushes the plan back over the limit
+        let factor = factor.round_dp_with_strategy(4, rust_decimal::RoundingStrategy::ToZero);
+        for allocation in allocations.iter_mut() {
+            allocation.usdc_amount *= factor;
+            allocation.percentage *= factor;
+        }
+
+        info!("📏 Plan scaled to {:.1}% of its notional | {}", factor * Decimal::ONE_HUNDRED, reason);
+        Ok(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PositionSide;
+    use rust_decimal_macros::dec;
+
+    fn allocation(wallet_id: u8, side: PositionSide, usdc_amount: Decimal) -> WalletAllocation {
+        WalletAllocation {
+            wallet_id,
+            side,
+            usdc_amount,
+            percentage: dec!(50),
+            capacity_usdc: usdc_amount,
+            leverage: 2.0,
+        }
+    }
+
+    fn plan() -> (Vec<Token>, Vec<WalletAllocation>) {
+        let tokens = vec![Token::eth(), Token::eth()];
+        let allocations = vec![allocation(1, PositionSide::Long, dec!(1000)), allocation(2, PositionSide::Short, dec!(1000))];
+        (tokens, allocations)
+    }
+
+    /// Every wallet worth 10k USDC and a deep ETH market
+    fn exposure(eth_notional: Decimal) -> (PortfolioExposure, HashMap<String, Decimal>) {
+        let symbol = Token::eth().get_symbol_string(Exchange::Lighter);
+        let mut exposure = PortfolioExposure::default();
+        exposure.by_token.insert(symbol.clone(), eth_notional);
+        exposure.by_exchange.insert(Exchange::Lighter, eth_notional);
+        for wallet_id in [1, 2] {
+            exposure.wallets.insert(wallet_id, WalletExposure { account_value: dec!(10000), gross_notional: Decimal::ZERO });
+        }
+
+        (exposure, HashMap::from([(symbol, dec!(100_000_000))]))
+    }
+
+    fn config(max_token_notional_usdc: f64, action: ExposureAction) -> ExposureConfig {
+        ExposureConfig { max_token_notional_usdc, action, ..ExposureConfig::default() }
+    }
+
+    #[test]
+    fn leaves_plans_within_limits_untouched() {
+        let (tokens, mut allocations) = plan();
+        let (exposure, open_interest) = exposure(dec!(1000));
+
+        let factor = PortfolioRisk::new(config(10_000.0, ExposureAction::Scale))
+            .check_plan(&exposure, &open_interest, &tokens, &mut allocations)
+            .unwrap();
+
+        assert_eq!(factor, Decimal::ONE);
+        assert_eq!(allocations[0].usdc_amount, dec!(1000));
+    }
+
+    #[test]
+    fn scales_every_leg_by_the_tightest_headroom() {
+        let (tokens, mut allocations) = plan();
+        let (exposure, open_interest) = exposure(dec!(1500));
+
+        // 1000 USDC of headroom left on ETH for a 2000 USDC plan
+        let factor = PortfolioRisk::new(config(2_500.0, ExposureAction::Scale))
+            .check_plan(&exposure, &open_interest, &tokens, &mut allocations)
+            .unwrap();
+
+        assert_eq!(factor, dec!(0.5));
+        assert_eq!(allocations[0].usdc_amount, dec!(500));
+        assert_eq!(allocations[1].usdc_amount, dec!(500));
+        assert_eq!(allocations[0].percentage, dec!(25));
+    }
+
+    #[test]
+    fn scales_by_wallet_leverage() {
+        let (tokens, mut allocations) = plan();
+        let (mut exposure, open_interest) = exposure(Decimal::ZERO);
+        exposure.wallets.insert(1, WalletExposure { account_value: dec!(100), gross_notional: dec!(100) });
+
+        // Wallet 1 may hold 5x 100 USDC and already holds 100
+        let factor = PortfolioRisk::new(config(10_000.0, ExposureAction::Scale))
+            .check_plan(&exposure, &open_interest, &tokens, &mut allocations)
+            .unwrap();
+
+        assert_eq!(factor, dec!(0.4));
+    }
+
+    #[test]
+    fn rejects_when_configured_or_below_the_minimum_factor() {
+        let (tokens, mut allocations) = plan();
+        let (exposure, open_interest) = exposure(dec!(1500));
+
+        let rejected = PortfolioRisk::new(config(2_500.0, ExposureAction::Reject))
+            .check_plan(&exposure, &open_interest, &tokens, &mut allocations);
+        assert!(matches!(rejected, Err(TradingError::ExposureLimitExceeded(_))));
+        assert_eq!(allocations[0].usdc_amount, dec!(1000));
+
+        // 200 USDC of headroom is 10% of the plan, below the 25% minimum
+        let too_small = PortfolioRisk::new(config(1_700.0, ExposureAction::Scale))
+            .check_plan(&exposure, &open_interest, &tokens, &mut allocations);
+        assert!(matches!(too_small, Err(TradingError::ExposureLimitExceeded(_))));
+    }
+}
@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use crate::{
+    config::RescueConfig,
+    error::TradingError,
+    perp::lighter::{client::LighterClient, models::LighterPosition},
+    storage::{storage_risk::CollateralTransfer, traits::RiskStore},
+};
+
+use chrono::Utc;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+/// A wallet that can give collateral, with the amount it may still give
+#[derive(Debug, Clone)]
+pub struct Donor {
+    pub wallet_id: u8,
+    pub budget_usdc: Decimal,
+}
+
+/// One transfer of a rescue plan
+#[derive(Debug, Clone)]
+pub struct PlannedTransfer {
+    pub from_wallet_id: u8,
+    pub to_wallet_id: u8,
+    pub amount_usdc: Decimal,
+}
+
+/// Moves USDC to wallets near liquidation, from winning wallets or a reserve wallet
+///
+/// In a hedged strategy the leg near liquidation is usually matched by a leg in profit
+/// on the other side. Moving collateral from the winner to the loser pushes the loser's
+/// liquidation price away without touching the positions. Every transfer is recorded.
+pub struct CollateralRescue {
+    config: RescueConfig,
+    storage: Arc<dyn RiskStore>,
+}
+
+impl CollateralRescue {
+    pub async fn new(config: RescueConfig, storage: Arc<dyn RiskStore>) -> Result<Self, TradingError> {
+        Ok(Self {
+            config,
+            storage,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn max_rescues(&self) -> u32 {
+        self.config.max_rescues
+    }
+
+    pub fn reserve_wallet_id(&self) -> Option<u8> {
+        self.config.reserve_wallet_id
+    }
+
+    /// USDC needed to move a position's liquidation distance to `target_distance_pct`
+    ///
+    /// Added collateral moves the liquidation price by roughly `amount / size`, so the
+    /// missing distance times the position value is the amount to add.
+    pub fn required_collateral(&self, position: &LighterPosition) -> Decimal {
+        let target = Decimal::from_f64(self.config.target_distance_pct).unwrap_or(Decimal::ZERO);
+        let missing_pct = (target - position.get_percentage_to_liquidation()).max(Decimal::ZERO);
+
+        missing_pct / Decimal::ONE_HUNDRED * position.position_value
+    }
+
+    /// Amount a donor may give out of its available balance
+    ///
+    /// # Arguments
+    /// * `available_usdc` - Collateral not used as margin
+    /// * `is_reserve` - The reserve wallet gives its whole available balance
+    pub fn donor_budget(&self, available_usdc: Decimal, is_reserve: bool) -> Decimal {
+        let fee = Decimal::from_f64(self.config.transfer_fee_usdc).unwrap_or(Decimal::ZERO);
+        let share = if is_reserve {
+            Decimal::ONE
+        } else {
+            Decimal::from_f64(self.config.max_donor_share).unwrap_or(Decimal::ZERO)
+        };
+
+        (available_usdc * share - fee).max(Decimal::ZERO).round_dp(2)
+    }
+
+    /// Cover every need from the donors, in order
+    ///
+    /// # Arguments
+    /// * `needs` - Wallet IDs with the collateral they need
+    /// * `donors` - Donors in order of preference
+    ///
+    /// # Returns
+    /// * `Some(Vec<PlannedTransfer>)` - Transfers covering every need
+    /// * `None` - The donors cannot cover every need
+    pub fn plan(&self, needs: &[(u8, Decimal)], donors: &[Donor]) -> Option<Vec<PlannedTransfer>> {
+        let max_transfer = Decimal::from_f64(self.config.max_transfer_usdc).unwrap_or(Decimal::ZERO);
+        let mut budgets: Vec<Decimal> = donors.iter().map(|d| d.budget_usdc).collect();
+        let mut transfers = Vec::new();
+
+        for &(to_wallet_id, need) in needs {
+            let mut remaining = need.min(max_transfer).round_dp(2);
+
+            for (donor, budget) in donors.iter().zip(budgets.iter_mut()) {
+                if remaining <= Decimal::ZERO {
+                    break;
+                }
+                if donor.wallet_id == to_wallet_id || *budget <= Decimal::ZERO {
+                    continue;
+                }
+
+                let amount = remaining.min(*budget);
+                *budget -= amount;
+                remaining -= amount;
+                transfers.push(PlannedTransfer {
+                    from_wallet_id: donor.wallet_id,
+                    to_wallet_id,
+                    amount_usdc: amount,
+                });
+            }
+
+            if remaining > Decimal::ZERO {
+                return None;
+            }
+        }
+
+        Some(transfers)
+    }
+
+    /// Execute a planned transfer and record it, whether it succeeds or not
+    ///
+    /// # Arguments
+    /// * `strategy_id` - Strategy being rescued
+    /// * `transfer` - Planned transfer
+    /// * `from` - Lighter client of the donor
+    /// * `to_account_index` - Lighter account index of the receiver
+    ///
+    /// # Returns
+    /// * `Ok(String)` - Transaction hash
+    /// * `Err(TradingError)` - If the transfer fails
+    pub async fn execute(
+        &self,
+        strategy_id: &str,
+        transfer: &PlannedTransfer,
+        from: &LighterClient,
+        to_account_index: u32,
+    ) -> Result<String, TradingError> {
+        let fee = Decimal::from_f64(self.config.transfer_fee_usdc).unwrap_or(Decimal::ZERO);
+        let memo = format!("rescue {}", strategy_id);
+        let result = from.transfer_usdc(to_account_index, transfer.amount_usdc, fee, &memo).await;
+
+        let record = CollateralTransfer {
+            strategy_id: strategy_id.to_string(),
+            from_wallet_id: transfer.from_wallet_id,
+            to_wallet_id: transfer.to_wallet_id,
+            amount_usdc: transfer.amount_usdc,
+            tx_hash: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            created_at: Utc::now(),
+        };
+
+        match &result {
+            Ok(hash) => info!(
+                "🛟 Moved {:.2} USDC from wallet #{} to wallet #{} for strategy {} | hash: {}",
+                transfer.amount_usdc, transfer.from_wallet_id, transfer.to_wallet_id, strategy_id, hash
+            ),
+            Err(e) => error!(
+                "❌ Transfer of {:.2} USDC from wallet #{} to wallet #{} failed: {}",
+                transfer.amount_usdc, transfer.from_wallet_id, transfer.to_wallet_id, e
+            ),
+        }
+
+        if let Err(e) = self.storage.save_collateral_transfer(&record).await {
+            error!("❌ Failed to record collateral transfer for strategy {}: {}", strategy_id, e);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::storage_memory::MemoryStorage;
+    use rust_decimal_macros::dec;
+
+    async fn rescue(max_transfer_usdc: f64) -> CollateralRescue {
+        let config = RescueConfig { max_transfer_usdc, transfer_fee_usdc: 0.5, ..RescueConfig::default() };
+        CollateralRescue::new(config, Arc::new(MemoryStorage::new())).await.unwrap()
+    }
+
+    fn donor(wallet_id: u8, budget_usdc: Decimal) -> Donor {
+        Donor { wallet_id, budget_usdc }
+    }
+
+    fn transfers(plan: &[PlannedTransfer]) -> Vec<(u8, u8, Decimal)> {
+        plan.iter().map(|t| (t.from_wallet_id, t.to_wallet_id, t.amount_usdc)).collect()
+    }
+
+    #[tokio::test]
+    async fn plans_transfers_from_donors_in_order() {
+        let rescue = rescue(1000.0).await;
+        let needs = [(1, dec!(150)), (2, dec!(30))];
+        let donors = [donor(3, dec!(100)), donor(4, dec!(100))];
+
+        let plan = rescue.plan(&needs, &donors).unwrap();
+
+        assert_eq!(transfers(&plan), vec![(3, 1, dec!(100)), (4, 1, dec!(50)), (4, 2, dec!(30))]);
+    }
+
+    #[tokio::test]
+    async fn wallets_never_donate_to_themselves() {
+        let rescue = rescue(1000.0).await;
+
+        let plan = rescue.plan(&[(1, dec!(50))], &[donor(1, dec!(500)), donor(2, dec!(80))]).unwrap();
+
+        assert_eq!(transfers(&plan), vec![(2, 1, dec!(50))]);
+    }
+
+    #[tokio::test]
+    async fn caps_transfers_and_fails_when_donors_fall_short() {
+        let rescue = rescue(75.0).await;
+
+        let plan = rescue.plan(&[(1, dec!(500))], &[donor(2, dec!(1000))]).unwrap();
+        assert_eq!(transfers(&plan), vec![(2, 1, dec!(75))]);
+
+        assert!(rescue.plan(&[(1, dec!(50)), (2, dec!(50))], &[donor(3, dec!(60))]).is_none());
+    }
+
+    #[tokio::test]
+    async fn donor_budgets_keep_the_fee_and_a_share_of_the_balance() {
+        let rescue = rescue(1000.0).await;
+
+        assert_eq!(rescue.donor_budget(dec!(100), false), dec!(49.5));
+        assert_eq!(rescue.donor_budget(dec!(100), true), dec!(99.5));
+        assert_eq!(rescue.donor_budget(dec!(0.4), false), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,340 @@
+//! Checks every storage backend must pass, run by the tests of each backend
+
+use crate::error::TradingError;
+use crate::model::exchange::Exchange;
+use crate::model::position::{Position, PositionSide, PositionStatus};
+use crate::storage::storage_event::{StrategyEvent, StrategyEventKind};
+use crate::storage::storage_order::{OrderRecord, OrderStatus};
+use crate::storage::storage_snapshot::{WalletBalanceSnapshot, WalletPointsSnapshot};
+use crate::storage::Storage;
+use crate::trader::strategy::{StrategyStatus, StrategyType, TradingStrategy};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::json;
+
+fn at(minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 12, minute, 0).unwrap()
+}
+
+pub fn position(id: &str, strategy_id: &str, wallet_id: u8, side: PositionSide) -> Position {
+    Position {
+        wallet_id,
+        id: id.to_string(),
+        strategy_id: Some(strategy_id.to_string()),
+        exchange: Exchange::Lighter,
+        symbol: "ETH".to_string(),
+        side,
+        size: dec!(1.5),
+        status: PositionStatus::Open,
+        opened_at: at(0),
+        close_at: at(30),
+        closed_at: None,
+        close_price: None,
+        realized_pnl: None,
+        updated_at: at(0),
+    }
+}
+
+pub fn strategy(id: &str, longs: Vec<Position>, shorts: Vec<Position>) -> TradingStrategy {
+    TradingStrategy {
+        id: id.to_string(),
+        token_symbol: "ETH".to_string(),
+        wallet_ids: longs.iter().chain(shorts.iter()).map(|p| p.wallet_id).collect(),
+        longs,
+        shorts,
+        shorts_size: dec!(1.5),
+        longs_size: dec!(1.5),
+        opened_at: at(0),
+        updated_at: at(0),
+        close_at: at(30),
+        closed_at: None,
+        realized_pnl: None,
+        status: StrategyStatus::Running,
+        strategy_type: StrategyType::DeltaNeutral,
+        hedge_symbols: Vec::new(),
+        hedge_ratio: None,
+        estimated_cost_usdc: Some(dec!(0.42)),
+        estimated_cost_bps: None,
+        entry_slippage_bps: None,
+        max_leg_slippage_bps: None,
+    }
+}
+
+/// Save a strategy with one long and one short position
+async fn save_strategy(storage: &Storage, id: &str) -> Result<(), TradingError> {
+    let long = position(&format!("{}-long", id), id, 1, PositionSide::Long);
+    let short = position(&format!("{}-short", id), id, 2, PositionSide::Short);
+    storage.positions.save_position(&long).await?;
+    storage.positions.save_position(&short).await?;
+    storage.strategies.save_strategy(&strategy(id, vec![long], vec![short])).await
+}
+
+pub fn order(tx_hash: &str, wallet_id: u8, submitted_at: DateTime<Utc>) -> OrderRecord {
+    OrderRecord {
+        exchange: Exchange::Lighter,
+        wallet_id,
+        symbol: "ETH".to_string(),
+        side: PositionSide::Long,
+        reduce_only: false,
+        base_amount: 15_000,
+        limit_price: dec!(3100.25),
+        nonce: 7,
+        client_order_index: 1,
+        tx_hash: tx_hash.to_string(),
+        submitted_at,
+        status: OrderStatus::Submitted,
+        tx_status: None,
+        executed_at: None,
+        fill_price: None,
+        fee_usdc: None,
+    }
+}
+
+fn balance(wallet_id: u8, minute: u32, total_asset_value: Decimal) -> WalletBalanceSnapshot {
+    WalletBalanceSnapshot {
+        wallet_id,
+        taken_at: at(minute),
+        available_balance: total_asset_value,
+        collateral: total_asset_value,
+        total_asset_value,
+    }
+}
+
+/// Strategies round-trip with their wallets and positions, and only follow legal, fresh transitions
+pub async fn strategy_status_transitions(storage: &Storage) {
+    save_strategy(storage, "s1").await.unwrap();
+
+    let running = storage.strategies.get_strategy_metadata("s1").await.unwrap().unwrap();
+    assert_eq!(running.status, StrategyStatus::Running);
+    assert_eq!(running.wallet_ids, vec![1, 2]);
+    assert_eq!(running.long_position_ids, vec!["s1-long".to_string()]);
+    assert_eq!(running.short_position_ids, vec!["s1-short".to_string()]);
+    assert_eq!(running.estimated_cost_usdc, Some(dec!(0.42)));
+    assert_eq!(storage.strategies.get_active_strategies().await.unwrap().len(), 1);
+
+    let closing_at = storage
+        .strategies
+        .update_strategy_status("s1", StrategyStatus::Running, running.updated_at, StrategyStatus::Closing, None, None)
+        .await
+        .unwrap();
+
+    // A second pass still holding the RUNNING read loses the race
+    let stale = storage
+        .strategies
+        .update_strategy_status("s1", StrategyStatus::Running, running.updated_at, StrategyStatus::Killed, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(stale, TradingError::StaleStrategyStatus { found: StrategyStatus::Closing, .. }), "{}", stale);
+
+    let illegal = storage
+        .strategies
+        .update_strategy_status("s1", StrategyStatus::Closing, closing_at, StrategyStatus::Running, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(illegal, TradingError::IllegalStatusTransition { .. }), "{}", illegal);
+
+    let closing = storage.strategies.get_strategy_metadata("s1").await.unwrap().unwrap();
+    assert_eq!(closing.status, StrategyStatus::Closing);
+    assert_eq!(closing.updated_at, closing_at);
+
+    storage
+        .strategies
+        .update_strategy_status("s1", StrategyStatus::Closing, closing_at, StrategyStatus::Closed, Some(at(31)), Some(dec!(-1.25)))
+        .await
+        .unwrap();
+
+    let closed = storage.strategies.get_strategy_metadata("s1").await.unwrap().unwrap();
+    assert_eq!(closed.status, StrategyStatus::Closed);
+    assert_eq!(closed.closed_at, Some(at(31)));
+    assert_eq!(closed.realized_pnl, Some(dec!(-1.25)));
+    assert!(storage.strategies.get_active_strategies().await.unwrap().is_empty());
+    assert_eq!(storage.strategies.get_realized_pnl_since(at(30)).await.unwrap(), dec!(-1.25));
+    assert_eq!(storage.strategies.get_realized_pnl_since(at(32)).await.unwrap(), Decimal::ZERO);
+
+    let missing = storage
+        .strategies
+        .update_strategy_status("missing", StrategyStatus::Running, at(0), StrategyStatus::Closing, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, TradingError::StrategyNotFound(_)), "{}", missing);
+}
+
+/// Closing a strategy's positions leaves closed ones alone
+pub async fn strategy_positions(storage: &Storage) {
+    save_strategy(storage, "s1").await.unwrap();
+    storage
+        .positions
+        .update_position_status("s1-long", PositionStatus::Closed, Some(at(10)), Some(dec!(0.5)))
+        .await
+        .unwrap();
+    storage.positions.update_strategy_positions_status("s1", PositionStatus::Closing).await.unwrap();
+
+    let positions = storage.positions.get_positions_by_strategy("s1").await.unwrap();
+    assert_eq!(positions.len(), 2);
+
+    let long = positions.iter().find(|p| p.id == "s1-long").unwrap();
+    assert_eq!(long.status, PositionStatus::Closed);
+    assert_eq!(long.realized_pnl, Some(dec!(0.5)));
+    assert_eq!(long.size, dec!(1.5));
+
+    let short = positions.iter().find(|p| p.id == "s1-short").unwrap();
+    assert_eq!(short.status, PositionStatus::Closing);
+
+    let active = storage.positions.get_active_positions().await.unwrap();
+    assert_eq!(active.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["s1-short"]);
+}
+
+/// Leases go to the first holder until they expire, and renewals keep the acquisition time
+pub async fn wallet_leases(storage: &Storage) {
+    let leases = &storage.leases;
+
+    assert_eq!(leases.claim_wallets("a", &[1, 2], at(0), at(2)).await.unwrap(), vec![1, 2]);
+    assert_eq!(leases.claim_wallets("b", &[2, 3], at(1), at(3)).await.unwrap(), vec![3]);
+    assert_eq!(leases.claim_wallets("a", &[1, 2], at(1), at(3)).await.unwrap(), vec![1, 2]);
+    assert!(leases.claim_wallets("a", &[], at(1), at(3)).await.unwrap().is_empty());
+
+    let held = leases.get_wallet_leases().await.unwrap();
+    assert_eq!(held.iter().map(|l| (l.wallet_id, l.holder.as_str())).collect::<Vec<_>>(), vec![(1, "a"), (2, "a"), (3, "b")]);
+    assert_eq!(held[0].acquired_at, at(0));
+    assert_eq!(held[0].expires_at, at(3));
+    assert!(held[0].is_live(at(2)));
+    assert!(!held[0].is_live(at(3)));
+
+    // Once a's leases lapse, b takes wallet 2 over
+    assert_eq!(leases.claim_wallets("b", &[2], at(3), at(5)).await.unwrap(), vec![2]);
+
+    let held = leases.get_wallet_leases().await.unwrap();
+    assert_eq!(held[1].holder, "b");
+    assert_eq!(held[1].acquired_at, at(3));
+}
+
+/// Orders are recorded once per tx hash and updated in place
+pub async fn orders(storage: &Storage) {
+    let orders = &storage.orders;
+
+    orders.record_order(&order("0xa", 1, at(0))).await.unwrap();
+    orders.record_order(&order("0xa", 1, at(5))).await.unwrap();
+    orders.record_order(&order("0xb", 1, at(1))).await.unwrap();
+    orders.record_order(&order("0xc", 2, at(2))).await.unwrap();
+    orders.record_order(&OrderRecord { reduce_only: true, ..order("0xe", 2, at(3)) }).await.unwrap();
+
+    orders.update_order_status("0xa", OrderStatus::Executed, Some(2), Some(1_735_732_800_000)).await.unwrap();
+    orders.update_order_fill("0xa", Some(dec!(3101.5)), Some(dec!(0.12))).await.unwrap();
+    orders.update_order_fill("0xc", None, Some(dec!(0.03))).await.unwrap();
+    orders.update_order_fill("0xe", Some(dec!(3120)), Some(dec!(0.05))).await.unwrap();
+
+    // Unknown fields are kept when a later update does not know them
+    orders.update_order_status("0xa", OrderStatus::Executed, None, None).await.unwrap();
+
+    let recorded = orders.get_order("0xa").await.unwrap().unwrap();
+    assert_eq!(recorded.submitted_at, at(0));
+    assert_eq!(recorded.status, OrderStatus::Executed);
+    assert_eq!(recorded.tx_status, Some(2));
+    assert_eq!(recorded.executed_at, Some(1_735_732_800_000));
+    assert_eq!(recorded.fill_price, Some(dec!(3101.5)));
+    assert_eq!(recorded.limit_price, dec!(3100.25));
+    assert!(orders.get_order("0xd").await.unwrap().is_none());
+
+    let wallet_orders = orders.get_wallet_orders(1, at(0)).await.unwrap();
+    assert_eq!(wallet_orders.iter().map(|o| o.tx_hash.as_str()).collect::<Vec<_>>(), vec!["0xa", "0xb"]);
+    assert_eq!(orders.get_wallet_orders(1, at(1)).await.unwrap().len(), 1);
+
+    // Exit fees are left out
+    assert_eq!(orders.get_entry_fees_since(at(0)).await.unwrap(), dec!(0.15));
+    assert_eq!(orders.get_entry_fees_since(at(1)).await.unwrap(), dec!(0.03));
+}
+
+/// Events are journaled in order and can be read back per strategy or per kind
+pub async fn events(storage: &Storage) {
+    let events = &storage.events;
+
+    let event = |strategy_id: &str, kind: StrategyEventKind, minute: u32| StrategyEvent {
+        created_at: at(minute),
+        ..StrategyEvent::new(strategy_id, kind, json!({ "slippage_bps": "31.5" })).with_leg(1, "ETH")
+    };
+    events.record_event(&event("s1", StrategyEventKind::Planned, 0)).await.unwrap();
+    events.record_event(&event("s1", StrategyEventKind::SlippageBreach, 1)).await.unwrap();
+    events.record_event(&event("s2", StrategyEventKind::SlippageBreach, 2)).await.unwrap();
+    events.record_event(&event("s1", StrategyEventKind::SlippageBreach, 3)).await.unwrap();
+
+    let journal = events.get_strategy_events("s1").await.unwrap();
+    assert_eq!(journal.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![
+        StrategyEventKind::Planned,
+        StrategyEventKind::SlippageBreach,
+        StrategyEventKind::SlippageBreach,
+    ]);
+    assert_eq!(journal[0].wallet_id, Some(1));
+    assert_eq!(journal[0].symbol.as_deref(), Some("ETH"));
+    assert_eq!(journal[0].details, json!({ "slippage_bps": "31.5" }));
+    assert_eq!(journal[0].created_at, at(0));
+
+    let breaches = events.get_events_by_kind(StrategyEventKind::SlippageBreach, at(2)).await.unwrap();
+    assert_eq!(breaches.iter().map(|e| (e.strategy_id.as_str(), e.created_at)).collect::<Vec<_>>(), vec![("s1", at(3)), ("s2", at(2))]);
+    assert_eq!(events.get_events_by_kind(StrategyEventKind::SlippageBreach, at(0)).await.unwrap().len(), 3);
+    assert!(events.get_events_by_kind(StrategyEventKind::RolledBack, at(0)).await.unwrap().is_empty());
+}
+
+/// Snapshot histories are windowed, and deltas span each wallet's first and last snapshot
+pub async fn snapshots(storage: &Storage) {
+    let snapshots = &storage.snapshots;
+
+    snapshots.save_balance_snapshot(&balance(1, 10, dec!(120))).await.unwrap();
+    snapshots.save_balance_snapshot(&balance(1, 0, dec!(100))).await.unwrap();
+    snapshots.save_balance_snapshot(&balance(1, 5, dec!(90))).await.unwrap();
+    snapshots.save_balance_snapshot(&balance(2, 5, dec!(50))).await.unwrap();
+    snapshots.save_balance_snapshot(&balance(1, 20, dec!(999))).await.unwrap();
+
+    let history = snapshots.get_balance_history(1, at(0), at(10)).await.unwrap();
+    assert_eq!(history.iter().map(|s| s.total_asset_value).collect::<Vec<_>>(), vec![dec!(100), dec!(90), dec!(120)]);
+
+    let deltas = snapshots.get_balance_deltas(at(0), at(10)).await.unwrap();
+    assert_eq!(deltas.len(), 2);
+    let wallet_1 = deltas.iter().find(|d| d.wallet_id == 1).unwrap();
+    assert_eq!(wallet_1.start.taken_at, at(0));
+    assert_eq!(wallet_1.end.taken_at, at(10));
+    assert_eq!(wallet_1.total_asset_value_change(), dec!(20));
+    let wallet_2 = deltas.iter().find(|d| d.wallet_id == 2).unwrap();
+    assert_eq!(wallet_2.total_asset_value_change(), Decimal::ZERO);
+
+    for (minute, points) in [(0, dec!(10)), (7, dec!(14.5))] {
+        let snapshot = WalletPointsSnapshot {
+            wallet_id: 1,
+            taken_at: at(minute) + Duration::seconds(1),
+            total_points: points,
+            last_week_points: dec!(4.5),
+        };
+        snapshots.save_points_snapshot(&snapshot).await.unwrap();
+    }
+
+    assert_eq!(snapshots.get_points_history(1, at(0), at(10)).await.unwrap().len(), 2);
+    assert!(snapshots.get_points_history(2, at(0), at(10)).await.unwrap().is_empty());
+
+    let deltas = snapshots.get_points_deltas(at(0), at(10)).await.unwrap();
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].end.total_points - deltas[0].start.total_points, dec!(4.5));
+}
+
+/// The kill switch and circuit breaker latch until they are cleared
+pub async fn risk_switches(storage: &Storage) {
+    let risk = &storage.risk;
+
+    assert!(!risk.get_kill_switch_state().await.unwrap().active);
+    assert!(risk.trigger_kill_switch("drawdown", "api").await.unwrap());
+    assert!(!risk.trigger_kill_switch("again", "telegram").await.unwrap());
+
+    let state = risk.get_kill_switch_state().await.unwrap();
+    assert!(state.active);
+    assert_eq!(state.reason.as_deref(), Some("drawdown"));
+    assert_eq!(state.source.as_deref(), Some("api"));
+
+    assert!(risk.clear_kill_switch().await.unwrap());
+    assert!(!risk.clear_kill_switch().await.unwrap());
+    assert!(!risk.get_kill_switch_state().await.unwrap().active);
+
+    assert!(risk.trip_breaker("losses").await.unwrap());
+    assert!(!risk.trip_breaker("losses").await.unwrap());
+    assert!(risk.get_breaker_state().await.unwrap().tripped);
+    risk.reset_breaker().await.unwrap();
+    assert!(!risk.get_breaker_state().await.unwrap().tripped);
+}
@@ -0,0 +1,103 @@
+use crate::error::TradingError;
+
+use chrono::{DateTime, Utc};
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    PgPool, Row,
+};
+
+/// Schema migrations embedded from `migrations/` at build time
+///
+/// Every migration has an `.up.sql` and a `.down.sql` file, so it can be rolled back.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// State of one embedded migration in the database
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    /// When it was applied, `None` if pending
+    pub installed_on: Option<DateTime<Utc>>,
+    /// Whether the applied SQL differs from the embedded file
+    pub modified: bool,
+}
+
+/// Apply every pending migration
+pub async fn run(pool: &PgPool) -> Result<(), TradingError> {
+    let pending = status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| m.installed_on.is_none())
+        .collect::<Vec<_>>();
+
+    MIGRATOR.run(pool).await?;
+
+    for migration in pending {
+        info!("🗄️ Applied migration {} ({})", migration.version, migration.description);
+    }
+
+    Ok(())
+}
+
+/// State of every embedded migration, oldest first
+pub async fn status(pool: &PgPool) -> Result<Vec<MigrationStatus>, TradingError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    let rows = sqlx::query("SELECT version, installed_on, checksum FROM _sqlx_migrations WHERE success")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let applied = rows
+        .iter()
+        .map(|row| {
+            Ok((
+                row.try_get::<i64, _>("version")?,
+                (row.try_get::<DateTime<Utc>, _>("installed_on")?, row.try_get::<Vec<u8>, _>("checksum")?),
+            ))
+        })
+        .collect::<Result<std::collections::HashMap<_, _>, TradingError>>()?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|m| {
+            let applied = applied.get(&m.version);
+            MigrationStatus {
+                version: m.version,
+                description: m.description.to_string(),
+                installed_on: applied.map(|(installed_on, _)| *installed_on),
+                modified: applied.is_some_and(|(_, checksum)| checksum.as_slice() != &*m.checksum),
+            }
+        })
+        .collect())
+}
+
+/// Revert applied migrations newer than `target`, or only the latest one when `target` is `None`
+///
+/// # Returns
+/// * `Ok(Vec<i64>)` - Versions that were reverted, newest first
+/// * `Err(TradingError)` - If a down migration fails
+pub async fn rollback(pool: &PgPool, target: Option<i64>) -> Result<Vec<i64>, TradingError> {
+    let mut applied = status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| m.installed_on.is_some())
+        .map(|m| m.version)
+        .collect::<Vec<_>>();
+    applied.sort_unstable_by(|a, b| b.cmp(a));
+
+    let target = match target {
+        Some(target) => target,
+        None => applied.get(1).copied().unwrap_or(0),
+    };
+
+    MIGRATOR.undo(pool, target).await?;
+
+    let reverted = applied.into_iter().filter(|&v| v > target).collect::<Vec<_>>();
+    for version in &reverted {
+        warn!("🗄️ Reverted migration {}", version);
+    }
+
+    Ok(reverted)
+}
@@ -1,11 +1,35 @@
 pub mod storage_position;
 pub mod storage_strategy;
 pub mod database;
+pub mod storage_risk;
+pub mod storage_event;
+pub mod storage_order;
+pub mod storage_snapshot;
+pub mod storage_lease;
+pub mod storage_sqlite;
+pub mod storage_memory;
+pub mod migrations;
+pub mod traits;
 
+#[cfg(test)]
+pub(crate) mod conformance;
+
+use crate::config::{StorageBackend, StorageConfig};
 use crate::error::TradingError;
 use anyhow::Context;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
+use std::sync::Arc;
+use storage_event::EventStorage;
+use storage_lease::LeaseStorage;
+use storage_memory::MemoryStorage;
+use storage_order::OrderStorage;
+use storage_position::PositionStorage;
+use storage_risk::RiskStorage;
+use storage_snapshot::SnapshotStorage;
+use storage_sqlite::SqliteStorage;
+use storage_strategy::StrategyStorage;
+use traits::{EventStore, LeaseStore, OrderStore, PositionStore, RiskStore, SnapshotStore, StrategyStore};
 
 /// Initialize a PostgreSQL connection pool from config
 pub async fn init_pool() -> Result<PgPool, TradingError> {
@@ -23,3 +47,64 @@ pub async fn init_pool() -> Result<PgPool, TradingError> {
     
     Ok(pool)
 }
+
+/// Every store the bot keeps its state in, on one backend
+#[derive(Clone)]
+pub struct Storage {
+    pub positions: Arc<dyn PositionStore>,
+    pub strategies: Arc<dyn StrategyStore>,
+    pub events: Arc<dyn EventStore>,
+    pub orders: Arc<dyn OrderStore>,
+    pub snapshots: Arc<dyn SnapshotStore>,
+    pub risk: Arc<dyn RiskStore>,
+    pub leases: Arc<dyn LeaseStore>,
+}
+
+impl Storage {
+    /// Connect to the configured backend and bring its schema up to date
+    pub async fn connect(config: &StorageConfig) -> Result<Self, TradingError> {
+        match config.backend {
+            StorageBackend::Postgres => {
+                let pool = init_pool().await?;
+                migrations::run(&pool).await?;
+                Self::postgres(pool).await
+            }
+            StorageBackend::Sqlite => Ok(Self::from_backend(Arc::new(SqliteStorage::connect(&config.sqlite_path).await?))),
+            StorageBackend::Memory => Ok(Self::memory()),
+        }
+    }
+
+    /// Stores on a PostgreSQL pool
+    pub async fn postgres(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self {
+            positions: Arc::new(PositionStorage::new(pool.clone()).await?),
+            strategies: Arc::new(StrategyStorage::new(pool.clone()).await?),
+            events: Arc::new(EventStorage::new(pool.clone()).await?),
+            orders: Arc::new(OrderStorage::new(pool.clone()).await?),
+            snapshots: Arc::new(SnapshotStorage::new(pool.clone()).await?),
+            risk: Arc::new(RiskStorage::new(pool.clone()).await?),
+            leases: Arc::new(LeaseStorage::new(pool).await?),
+        })
+    }
+
+    /// Empty in-memory stores
+    pub fn memory() -> Self {
+        Self::from_backend(Arc::new(MemoryStorage::new()))
+    }
+
+    /// Every store served by a single backend
+    fn from_backend<B>(backend: Arc<B>) -> Self
+    where
+        B: PositionStore + StrategyStore + EventStore + OrderStore + SnapshotStore + RiskStore + LeaseStore + 'static,
+    {
+        Self {
+            positions: backend.clone(),
+            strategies: backend.clone(),
+            events: backend.clone(),
+            orders: backend.clone(),
+            snapshots: backend.clone(),
+            risk: backend.clone(),
+            leases: backend,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::storage::traits::EventStore;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{postgres::PgRow, types::Json, PgPool, Row};
+use std::str::FromStr;
+
+/// Kind of entry in the strategy journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyEventKind {
+    /// Allocations were computed and are about to be executed
+    Planned,
+    /// A child order of a leg was accepted by the exchange
+    LegOrderSent,
+    /// A leg's position was confirmed on the exchange
+    LegConfirmed,
+    /// The plan could not be opened neutrally and its markets were rolled back
+    RolledBack,
+    /// A leg came within the warning distance of liquidation
+    LiquidationWarning,
+    /// The strategy is being closed before its scheduled time
+    EmergencyClose,
+    /// One attempt to flatten the strategy's markets failed
+    CloseAttemptFailed,
+    /// A child order filled further from the mid than the slippage limit
+    SlippageBreach,
+    /// The strategy's status was updated
+    StatusChange,
+}
+
+impl std::fmt::Display for StrategyEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrategyEventKind::Planned => write!(f, "PLANNED"),
+            StrategyEventKind::LegOrderSent => write!(f, "LEG_ORDER_SENT"),
+            StrategyEventKind::LegConfirmed => write!(f, "LEG_CONFIRMED"),
+            StrategyEventKind::RolledBack => write!(f, "ROLLED_BACK"),
+            StrategyEventKind::LiquidationWarning => write!(f, "LIQUIDATION_WARNING"),
+            StrategyEventKind::EmergencyClose => write!(f, "EMERGENCY_CLOSE"),
+            StrategyEventKind::CloseAttemptFailed => write!(f, "CLOSE_ATTEMPT_FAILED"),
+            StrategyEventKind::SlippageBreach => write!(f, "SLIPPAGE_BREACH"),
+            StrategyEventKind::StatusChange => write!(f, "STATUS_CHANGE"),
+        }
+    }
+}
+
+impl FromStr for StrategyEventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "PLANNED" => Ok(StrategyEventKind::Planned),
+            "LEG_ORDER_SENT" => Ok(StrategyEventKind::LegOrderSent),
+            "LEG_CONFIRMED" => Ok(StrategyEventKind::LegConfirmed),
+            "ROLLED_BACK" => Ok(StrategyEventKind::RolledBack),
+            "LIQUIDATION_WARNING" => Ok(StrategyEventKind::LiquidationWarning),
+            "EMERGENCY_CLOSE" => Ok(StrategyEventKind::EmergencyClose),
+            "CLOSE_ATTEMPT_FAILED" => Ok(StrategyEventKind::CloseAttemptFailed),
+            "SLIPPAGE_BREACH" => Ok(StrategyEventKind::SlippageBreach),
+            "STATUS_CHANGE" => Ok(StrategyEventKind::StatusChange),
+            _ => Err(format!("Invalid StrategyEventKind: {}", s)),
+        }
+    }
+}
+
+/// One entry of the strategy journal
+#[derive(Debug, Clone)]
+pub struct StrategyEvent {
+    /// Journal sequence number (0 until recorded)
+    pub id: i64,
+    pub strategy_id: String,
+    pub kind: StrategyEventKind,
+    /// Wallet of the leg the event is about, if any
+    pub wallet_id: Option<u8>,
+    /// Market of the leg the event is about, if any
+    pub symbol: Option<String>,
+    /// Event-specific fields (tx hash, nonce, prices, error...)
+    pub details: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StrategyEvent {
+    pub fn new(strategy_id: &str, kind: StrategyEventKind, details: Value) -> Self {
+        Self {
+            id: 0,
+            strategy_id: strategy_id.to_string(),
+            kind,
+            wallet_id: None,
+            symbol: None,
+            details,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Attach the event to one leg of the strategy
+    pub fn with_leg(mut self, wallet_id: u8, symbol: &str) -> Self {
+        self.wallet_id = Some(wallet_id);
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    /// One line of a strategy timeline
+    pub fn timeline_line(&self) -> String {
+        let mut line = format!("{} | {:<20}", self.created_at.format("%Y-%m-%d %H:%M:%S%.3f UTC"), self.kind.to_string());
+
+        if let Some(wallet_id) = self.wallet_id {
+            line.push_str(&format!(" | #{}", wallet_id));
+        }
+        if let Some(symbol) = &self.symbol {
+            line.push_str(&format!(" {}", symbol));
+        }
+
+        if let Value::Object(fields) = &self.details {
+            for (key, value) in fields {
+                match value {
+                    Value::Null => {}
+                    Value::String(value) => line.push_str(&format!(" | {}: {}", key, value)),
+                    value => line.push_str(&format!(" | {}: {}", key, value)),
+                }
+            }
+        }
+
+        line
+    }
+}
+
+/// PostgreSQL-based storage for the append-only strategy journal
+#[derive(Clone)]
+pub struct EventStorage {
+    pool: PgPool,
+}
+
+impl EventStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl EventStore for EventStorage {
+    /// Append an event to the journal
+    async fn record_event(&self, event: &StrategyEvent) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_events (strategy_id, kind, wallet_id, symbol, details, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(&event.strategy_id)
+        .bind(event.kind.to_string())
+        .bind(event.wallet_id.map(|id| id as i16))
+        .bind(&event.symbol)
+        .bind(Json(&event.details))
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get every event of a strategy, oldest first
+    async fn get_strategy_events(&self, strategy_id: &str) -> Result<Vec<StrategyEvent>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, strategy_id, kind, wallet_id, symbol, details, created_at
+            FROM strategy_events WHERE strategy_id = $1 ORDER BY id ASC
+            "#,
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+
+    /// Get events of one kind recorded since `since`, newest first
+    async fn get_events_by_kind(
+        &self,
+        kind: StrategyEventKind,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<StrategyEvent>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, strategy_id, kind, wallet_id, symbol, details, created_at
+            FROM strategy_events WHERE kind = $1 AND created_at >= $2 ORDER BY id DESC
+            "#,
+        )
+        .bind(kind.to_string())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+}
+
+fn event_from_row(row: &PgRow) -> Result<StrategyEvent, TradingError> {
+    Ok(StrategyEvent {
+        id: row.try_get("id")?,
+        strategy_id: row.try_get("strategy_id")?,
+        kind: StrategyEventKind::from_str(row.try_get("kind")?).map_err(TradingError::InvalidInput)?,
+        wallet_id: row.try_get::<Option<i16>, _>("wallet_id")?.map(|id| id as u8),
+        symbol: row.try_get("symbol")?,
+        details: row.try_get::<Json<Value>, _>("details")?.0,
+        created_at: row.try_get("created_at")?,
+    })
+}
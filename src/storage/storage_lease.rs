@@ -0,0 +1,102 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::storage::traits::LeaseStore;
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+/// Right of one bot instance to trade a wallet until the lease expires
+#[derive(Debug, Clone)]
+pub struct WalletLease {
+    pub wallet_id: u8,
+    /// Instance holding the lease
+    pub holder: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl WalletLease {
+    /// Check if the lease still holds the wallet at `now`
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// PostgreSQL-based storage for wallet leases
+#[derive(Clone)]
+pub struct LeaseStorage {
+    pool: PgPool,
+}
+
+impl LeaseStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl LeaseStore for LeaseStorage {
+    /// Claim or renew leases in one statement; rows held by another live holder are left untouched
+    async fn claim_wallets(
+        &self,
+        holder: &str,
+        wallet_ids: &[u8],
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, TradingError> {
+        if wallet_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i16> = wallet_ids.iter().map(|id| *id as i16).collect();
+        let rows = sqlx::query(
+            r#"
+            INSERT INTO wallet_leases (wallet_id, holder, acquired_at, expires_at)
+            SELECT UNNEST($1::SMALLINT[]), $2, $3, $4
+            ON CONFLICT (wallet_id) DO UPDATE SET
+                holder = EXCLUDED.holder,
+                acquired_at = CASE
+                    WHEN wallet_leases.holder = EXCLUDED.holder THEN wallet_leases.acquired_at
+                    ELSE EXCLUDED.acquired_at
+                END,
+                expires_at = EXCLUDED.expires_at
+            WHERE wallet_leases.holder = EXCLUDED.holder OR wallet_leases.expires_at <= EXCLUDED.acquired_at
+            RETURNING wallet_id
+            "#,
+        )
+        .bind(ids)
+        .bind(holder)
+        .bind(now)
+        .bind(expires_at)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut claimed = rows
+            .iter()
+            .map(|row| row.try_get::<i16, _>("wallet_id").map(|id| id as u8))
+            .collect::<Result<Vec<u8>, _>>()?;
+        claimed.sort_unstable();
+
+        Ok(claimed)
+    }
+
+    /// Get every wallet lease, by wallet
+    async fn get_wallet_leases(&self) -> Result<Vec<WalletLease>, TradingError> {
+        let rows = sqlx::query("SELECT wallet_id, holder, acquired_at, expires_at FROM wallet_leases ORDER BY wallet_id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(lease_from_row).collect()
+    }
+}
+
+fn lease_from_row(row: &PgRow) -> Result<WalletLease, TradingError> {
+    Ok(WalletLease {
+        wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
+        holder: row.try_get("holder")?,
+        acquired_at: row.try_get("acquired_at")?,
+        expires_at: row.try_get("expires_at")?,
+    })
+}
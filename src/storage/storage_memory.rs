@@ -0,0 +1,536 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::model::exchange::Exchange;
+use crate::model::position::{Position, PositionStatus};
+use crate::storage::storage_event::{StrategyEvent, StrategyEventKind};
+use crate::storage::storage_lease::WalletLease;
+use crate::storage::storage_order::{OrderRecord, OrderStatus};
+use crate::storage::storage_risk::{BreakerState, CollateralTransfer, EquitySnapshot, KillSwitchState};
+use crate::storage::storage_snapshot::{window_deltas, SnapshotDelta, WalletBalanceSnapshot, WalletPointsSnapshot};
+use crate::storage::storage_strategy::{check_transition, stale_status_error, status_timestamp, StrategyMetadata};
+use crate::storage::traits::{EventStore, LeaseStore, OrderStore, PositionStore, RiskStore, SnapshotStore, StrategyStore};
+use crate::trader::strategy::{StrategyStatus, TradingStrategy};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug)]
+struct MemoryState {
+    positions: HashMap<String, Position>,
+    strategies: HashMap<String, StrategyMetadata>,
+    events: Vec<StrategyEvent>,
+    orders: Vec<OrderRecord>,
+    balance_snapshots: Vec<WalletBalanceSnapshot>,
+    points_snapshots: Vec<WalletPointsSnapshot>,
+    equity_snapshots: Vec<EquitySnapshot>,
+    collateral_transfers: Vec<CollateralTransfer>,
+    kill_switch: KillSwitchState,
+    breaker: BreakerState,
+    leases: HashMap<u8, WalletLease>,
+}
+
+impl Default for MemoryState {
+    fn default() -> Self {
+        Self {
+            positions: HashMap::new(),
+            strategies: HashMap::new(),
+            events: Vec::new(),
+            orders: Vec::new(),
+            balance_snapshots: Vec::new(),
+            points_snapshots: Vec::new(),
+            equity_snapshots: Vec::new(),
+            collateral_transfers: Vec::new(),
+            kill_switch: KillSwitchState { active: false, reason: None, source: None, triggered_at: None, cleared_at: None },
+            breaker: BreakerState { tripped: false, reason: None, tripped_at: None, reset_at: None },
+            leases: HashMap::new(),
+        }
+    }
+}
+
+/// In-memory storage for every store, lost when the process exits
+///
+/// Meant for local runs and tests that should not need a database server.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Strategies matching `filter`, sorted by `key`
+    fn strategies_where<K: Ord>(
+        &self,
+        filter: impl Fn(&StrategyMetadata) -> bool,
+        key: impl Fn(&StrategyMetadata) -> K,
+    ) -> Vec<StrategyMetadata> {
+        let mut strategies: Vec<StrategyMetadata> = self.state().strategies.values().filter(|s| filter(s)).cloned().collect();
+        strategies.sort_by_key(key);
+        strategies
+    }
+
+    /// Positions matching `filter`, newest first
+    fn positions_where(&self, filter: impl Fn(&Position) -> bool) -> Vec<Position> {
+        let mut positions: Vec<Position> = self.state().positions.values().filter(|p| filter(p)).cloned().collect();
+        positions.sort_by_key(|p| std::cmp::Reverse(p.opened_at));
+        positions
+    }
+}
+
+#[async_trait]
+impl PositionStore for MemoryStorage {
+    async fn save_position(&self, position: &Position) -> Result<(), TradingError> {
+        self.state().positions.insert(position.id.clone(), position.clone());
+        Ok(())
+    }
+
+    async fn get_position(&self, id: &str) -> Result<Option<Position>, TradingError> {
+        Ok(self.state().positions.get(id).cloned())
+    }
+
+    async fn get_all_positions(&self) -> Result<Vec<Position>, TradingError> {
+        Ok(self.positions_where(|_| true))
+    }
+
+    async fn get_positions_by_exchange(&self, exchange: Exchange) -> Result<Vec<Position>, TradingError> {
+        Ok(self.positions_where(|p| p.exchange == exchange))
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<Position>, TradingError> {
+        Ok(self.positions_where(|p| matches!(p.status, PositionStatus::Open | PositionStatus::Closing)))
+    }
+
+    async fn get_positions_by_strategy(&self, strategy_id: &str) -> Result<Vec<Position>, TradingError> {
+        let mut positions = self.positions_where(|p| p.strategy_id.as_deref() == Some(strategy_id));
+        positions.reverse();
+        Ok(positions)
+    }
+
+    async fn update_strategy_positions_status(&self, strategy_id: &str, status: PositionStatus) -> Result<(), TradingError> {
+        let now = Utc::now();
+        for position in self.state().positions.values_mut() {
+            if position.strategy_id.as_deref() == Some(strategy_id) && position.status != PositionStatus::Closed {
+                position.status = status;
+                position.updated_at = now;
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_position_status(
+        &self,
+        id: &str,
+        status: PositionStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<(), TradingError> {
+        if let Some(position) = self.state().positions.get_mut(id) {
+            position.status = status;
+            position.closed_at = closed_at;
+            position.realized_pnl = realized_pnl;
+            position.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn delete_position(&self, id: &str) -> Result<(), TradingError> {
+        self.state().positions.remove(id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StrategyStore for MemoryStorage {
+    async fn save_strategy(&self, strategy: &TradingStrategy) -> Result<(), TradingError> {
+        self.state().strategies.insert(strategy.id.clone(), StrategyMetadata::from(strategy));
+        Ok(())
+    }
+
+    async fn get_strategy_metadata(&self, id: &str) -> Result<Option<StrategyMetadata>, TradingError> {
+        Ok(self.state().strategies.get(id).cloned())
+    }
+
+    async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        Ok(self.strategies_where(
+            |s| matches!(s.status, StrategyStatus::Running | StrategyStatus::Closing),
+            |s| s.close_at,
+        ))
+    }
+
+    async fn get_strategies_to_close(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let now = Utc::now();
+        Ok(self.strategies_where(|s| s.status == StrategyStatus::Running && s.close_at <= now, |s| s.close_at))
+    }
+
+    async fn update_strategy_status(
+        &self,
+        id: &str,
+        from: StrategyStatus,
+        updated_at: DateTime<Utc>,
+        to: StrategyStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<DateTime<Utc>, TradingError> {
+        check_transition(id, from, to)?;
+
+        let mut state = self.state();
+        let strategy = state.strategies.get_mut(id).ok_or_else(|| TradingError::StrategyNotFound(id.to_string()))?;
+        if strategy.status != from || strategy.updated_at != updated_at {
+            return Err(stale_status_error(id, from, updated_at, Some(strategy.status)));
+        }
+
+        strategy.status = to;
+        strategy.closed_at = closed_at;
+        strategy.realized_pnl = realized_pnl;
+        strategy.updated_at = status_timestamp();
+        Ok(strategy.updated_at)
+    }
+
+    async fn get_all_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        Ok(self.strategies_where(|_| true, |s| std::cmp::Reverse(s.opened_at)))
+    }
+
+    async fn get_failed_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        Ok(self.strategies_where(|s| s.status == StrategyStatus::Failed, |s| std::cmp::Reverse(s.updated_at)))
+    }
+
+    async fn get_strategies_for_wallet(&self, wallet_id: u8) -> Result<Vec<StrategyMetadata>, TradingError> {
+        Ok(self.strategies_where(|s| s.wallet_ids.contains(&wallet_id), |s| std::cmp::Reverse(s.opened_at)))
+    }
+
+    async fn get_realized_pnl_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        Ok(self
+            .state()
+            .strategies
+            .values()
+            .filter(|s| s.closed_at.is_some_and(|closed_at| closed_at >= since))
+            .filter_map(|s| s.realized_pnl)
+            .sum())
+    }
+}
+
+#[async_trait]
+impl EventStore for MemoryStorage {
+    async fn record_event(&self, event: &StrategyEvent) -> Result<(), TradingError> {
+        let mut state = self.state();
+        let mut event = event.clone();
+        event.id = state.events.len() as i64 + 1;
+        state.events.push(event);
+        Ok(())
+    }
+
+    async fn get_strategy_events(&self, strategy_id: &str) -> Result<Vec<StrategyEvent>, TradingError> {
+        Ok(self.state().events.iter().filter(|e| e.strategy_id == strategy_id).cloned().collect())
+    }
+
+    async fn get_events_by_kind(&self, kind: StrategyEventKind, since: DateTime<Utc>) -> Result<Vec<StrategyEvent>, TradingError> {
+        Ok(self
+            .state()
+            .events
+            .iter()
+            .rev()
+            .filter(|e| e.kind == kind && e.created_at >= since)
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl OrderStore for MemoryStorage {
+    async fn record_order(&self, order: &OrderRecord) -> Result<(), TradingError> {
+        let mut state = self.state();
+        if !state.orders.iter().any(|o| o.tx_hash == order.tx_hash) {
+            state.orders.push(order.clone());
+        }
+        Ok(())
+    }
+
+    async fn update_order_status(
+        &self,
+        tx_hash: &str,
+        status: OrderStatus,
+        tx_status: Option<i64>,
+        executed_at: Option<i64>,
+    ) -> Result<(), TradingError> {
+        if let Some(order) = self.state().orders.iter_mut().find(|o| o.tx_hash == tx_hash) {
+            order.status = status;
+            order.tx_status = tx_status.or(order.tx_status);
+            order.executed_at = executed_at.or(order.executed_at);
+        }
+        Ok(())
+    }
+
+    async fn update_order_fill(&self, tx_hash: &str, fill_price: Option<Decimal>, fee_usdc: Option<Decimal>) -> Result<(), TradingError> {
+        if let Some(order) = self.state().orders.iter_mut().find(|o| o.tx_hash == tx_hash) {
+            order.fill_price = fill_price.or(order.fill_price);
+            order.fee_usdc = fee_usdc.or(order.fee_usdc);
+        }
+        Ok(())
+    }
+
+    async fn get_order(&self, tx_hash: &str) -> Result<Option<OrderRecord>, TradingError> {
+        Ok(self.state().orders.iter().find(|o| o.tx_hash == tx_hash).cloned())
+    }
+
+    async fn get_wallet_orders(&self, wallet_id: u8, since: DateTime<Utc>) -> Result<Vec<OrderRecord>, TradingError> {
+        let mut orders: Vec<OrderRecord> = self
+            .state()
+            .orders
+            .iter()
+            .filter(|o| o.wallet_id == wallet_id && o.submitted_at >= since)
+            .cloned()
+            .collect();
+        orders.sort_by_key(|o| o.submitted_at);
+        Ok(orders)
+    }
+
+    async fn get_entry_fees_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        Ok(self.state().orders.iter().filter(|o| o.submitted_at >= since && !o.reduce_only).filter_map(|o| o.fee_usdc).sum())
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for MemoryStorage {
+    async fn save_balance_snapshot(&self, snapshot: &WalletBalanceSnapshot) -> Result<(), TradingError> {
+        self.state().balance_snapshots.push(snapshot.clone());
+        Ok(())
+    }
+
+    async fn save_points_snapshot(&self, snapshot: &WalletPointsSnapshot) -> Result<(), TradingError> {
+        self.state().points_snapshots.push(snapshot.clone());
+        Ok(())
+    }
+
+    async fn get_balance_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletBalanceSnapshot>, TradingError> {
+        let mut history: Vec<WalletBalanceSnapshot> = self
+            .state()
+            .balance_snapshots
+            .iter()
+            .filter(|s| s.wallet_id == wallet_id && s.taken_at >= since && s.taken_at <= until)
+            .cloned()
+            .collect();
+        history.sort_by_key(|s| s.taken_at);
+        Ok(history)
+    }
+
+    async fn get_points_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletPointsSnapshot>, TradingError> {
+        let mut history: Vec<WalletPointsSnapshot> = self
+            .state()
+            .points_snapshots
+            .iter()
+            .filter(|s| s.wallet_id == wallet_id && s.taken_at >= since && s.taken_at <= until)
+            .cloned()
+            .collect();
+        history.sort_by_key(|s| s.taken_at);
+        Ok(history)
+    }
+
+    async fn get_balance_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletBalanceSnapshot>>, TradingError> {
+        let mut history: Vec<WalletBalanceSnapshot> = self
+            .state()
+            .balance_snapshots
+            .iter()
+            .filter(|s| s.taken_at >= since && s.taken_at <= until)
+            .cloned()
+            .collect();
+        history.sort_by_key(|s| s.taken_at);
+        Ok(window_deltas(&history, |s| s.wallet_id))
+    }
+
+    async fn get_points_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletPointsSnapshot>>, TradingError> {
+        let mut history: Vec<WalletPointsSnapshot> = self
+            .state()
+            .points_snapshots
+            .iter()
+            .filter(|s| s.taken_at >= since && s.taken_at <= until)
+            .cloned()
+            .collect();
+        history.sort_by_key(|s| s.taken_at);
+        Ok(window_deltas(&history, |s| s.wallet_id))
+    }
+}
+
+#[async_trait]
+impl RiskStore for MemoryStorage {
+    async fn save_equity_snapshot(&self, snapshot: &EquitySnapshot, retain_since: DateTime<Utc>) -> Result<(), TradingError> {
+        let mut state = self.state();
+        state.equity_snapshots.push(snapshot.clone());
+        state.equity_snapshots.retain(|s| s.taken_at >= retain_since);
+        Ok(())
+    }
+
+    async fn get_equity_snapshots_since(&self, since: DateTime<Utc>) -> Result<Vec<EquitySnapshot>, TradingError> {
+        let mut snapshots: Vec<EquitySnapshot> = self.state().equity_snapshots.iter().filter(|s| s.taken_at >= since).cloned().collect();
+        snapshots.sort_by_key(|s| s.taken_at);
+        Ok(snapshots)
+    }
+
+    async fn get_kill_switch_state(&self) -> Result<KillSwitchState, TradingError> {
+        Ok(self.state().kill_switch.clone())
+    }
+
+    async fn trigger_kill_switch(&self, reason: &str, source: &str) -> Result<bool, TradingError> {
+        let mut state = self.state();
+        let kill_switch = &mut state.kill_switch;
+        if kill_switch.active {
+            return Ok(false);
+        }
+
+        kill_switch.active = true;
+        kill_switch.reason = Some(reason.to_string());
+        kill_switch.source = Some(source.to_string());
+        kill_switch.triggered_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    async fn clear_kill_switch(&self) -> Result<bool, TradingError> {
+        let mut state = self.state();
+        let kill_switch = &mut state.kill_switch;
+        if !kill_switch.active {
+            return Ok(false);
+        }
+
+        kill_switch.active = false;
+        kill_switch.cleared_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    async fn save_collateral_transfer(&self, transfer: &CollateralTransfer) -> Result<(), TradingError> {
+        self.state().collateral_transfers.push(transfer.clone());
+        Ok(())
+    }
+
+    async fn get_breaker_state(&self) -> Result<BreakerState, TradingError> {
+        Ok(self.state().breaker.clone())
+    }
+
+    async fn trip_breaker(&self, reason: &str) -> Result<bool, TradingError> {
+        let mut state = self.state();
+        let breaker = &mut state.breaker;
+        if breaker.tripped {
+            return Ok(false);
+        }
+
+        breaker.tripped = true;
+        breaker.reason = Some(reason.to_string());
+        breaker.tripped_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    async fn reset_breaker(&self) -> Result<(), TradingError> {
+        let mut state = self.state();
+        let breaker = &mut state.breaker;
+        breaker.tripped = false;
+        breaker.reset_at = Some(Utc::now());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LeaseStore for MemoryStorage {
+    async fn claim_wallets(
+        &self,
+        holder: &str,
+        wallet_ids: &[u8],
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, TradingError> {
+        let mut state = self.state();
+        let mut claimed = Vec::new();
+
+        for &wallet_id in wallet_ids {
+            let lease = state.leases.entry(wallet_id).or_insert_with(|| WalletLease {
+                wallet_id,
+                holder: holder.to_string(),
+                acquired_at: now,
+                expires_at,
+            });
+
+            if lease.holder != holder {
+                if lease.is_live(now) {
+                    continue;
+                }
+                lease.holder = holder.to_string();
+                lease.acquired_at = now;
+            }
+            lease.expires_at = expires_at;
+            claimed.push(wallet_id);
+        }
+
+        claimed.sort_unstable();
+        Ok(claimed)
+    }
+
+    async fn get_wallet_leases(&self) -> Result<Vec<WalletLease>, TradingError> {
+        let mut leases: Vec<WalletLease> = self.state().leases.values().cloned().collect();
+        leases.sort_by_key(|l| l.wallet_id);
+        Ok(leases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{conformance, Storage};
+
+    #[tokio::test]
+    async fn strategy_status_transitions() {
+        conformance::strategy_status_transitions(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn strategy_positions() {
+        conformance::strategy_positions(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn wallet_leases() {
+        conformance::wallet_leases(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn orders() {
+        conformance::orders(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn events() {
+        conformance::events(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn snapshots() {
+        conformance::snapshots(&Storage::memory()).await;
+    }
+
+    #[tokio::test]
+    async fn risk_switches() {
+        conformance::risk_switches(&Storage::memory()).await;
+    }
+}
@@ -0,0 +1,234 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::storage::traits::OrderStore;
+use crate::model::exchange::Exchange;
+use crate::model::position::PositionSide;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use std::str::FromStr;
+
+/// Execution status of a ledger order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// Accepted by the exchange, not looked up yet
+    Submitted,
+    /// Found by its tx hash
+    Executed,
+    /// Could not be found by its tx hash
+    Unconfirmed,
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderStatus::Submitted => write!(f, "SUBMITTED"),
+            OrderStatus::Executed => write!(f, "EXECUTED"),
+            OrderStatus::Unconfirmed => write!(f, "UNCONFIRMED"),
+        }
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SUBMITTED" => Ok(OrderStatus::Submitted),
+            "EXECUTED" => Ok(OrderStatus::Executed),
+            "UNCONFIRMED" => Ok(OrderStatus::Unconfirmed),
+            _ => Err(format!("Invalid OrderStatus: {}", s)),
+        }
+    }
+}
+
+/// One order sent to an exchange
+#[derive(Debug, Clone)]
+pub struct OrderRecord {
+    pub exchange: Exchange,
+    pub wallet_id: u8,
+    pub symbol: String,
+    pub side: PositionSide,
+    pub reduce_only: bool,
+    /// Base amount in the exchange's integer units
+    pub base_amount: i64,
+    pub limit_price: Decimal,
+    pub nonce: i64,
+    pub client_order_index: i64,
+    pub tx_hash: String,
+    pub submitted_at: DateTime<Utc>,
+    pub status: OrderStatus,
+    /// Transaction status reported by the exchange
+    pub tx_status: Option<i64>,
+    /// Execution time reported by the exchange
+    pub executed_at: Option<i64>,
+    /// Average execution price (None if unknown)
+    pub fill_price: Option<Decimal>,
+    pub fee_usdc: Option<Decimal>,
+}
+
+/// PostgreSQL-based ledger of exchange orders
+#[derive(Debug, Clone)]
+pub struct OrderStorage {
+    pool: PgPool,
+}
+
+impl OrderStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl OrderStore for OrderStorage {
+    /// Record an order accepted by the exchange
+    async fn record_order(&self, order: &OrderRecord) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO orders
+            (exchange, wallet_id, symbol, side, reduce_only, base_amount, limit_price, nonce, client_order_index,
+             tx_hash, submitted_at, status, tx_status, executed_at, fill_price, fee_usdc, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+        )
+        .bind(order.exchange.to_string())
+        .bind(order.wallet_id as i16)
+        .bind(&order.symbol)
+        .bind(order.side.to_string())
+        .bind(order.reduce_only)
+        .bind(order.base_amount)
+        .bind(order.limit_price)
+        .bind(order.nonce)
+        .bind(order.client_order_index)
+        .bind(&order.tx_hash)
+        .bind(order.submitted_at)
+        .bind(order.status.to_string())
+        .bind(order.tx_status)
+        .bind(order.executed_at)
+        .bind(order.fill_price)
+        .bind(order.fee_usdc)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update an order once its tx hash was looked up
+    async fn update_order_status(
+        &self,
+        tx_hash: &str,
+        status: OrderStatus,
+        tx_status: Option<i64>,
+        executed_at: Option<i64>,
+    ) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET status = $1, tx_status = COALESCE($2, tx_status), executed_at = COALESCE($3, executed_at), updated_at = $4
+            WHERE tx_hash = $5
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(tx_status)
+        .bind(executed_at)
+        .bind(Utc::now())
+        .bind(tx_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Set the fill price and fee of an order
+    async fn update_order_fill(
+        &self,
+        tx_hash: &str,
+        fill_price: Option<Decimal>,
+        fee_usdc: Option<Decimal>,
+    ) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET fill_price = COALESCE($1, fill_price), fee_usdc = COALESCE($2, fee_usdc), updated_at = $3
+            WHERE tx_hash = $4
+            "#,
+        )
+        .bind(fill_price)
+        .bind(fee_usdc)
+        .bind(Utc::now())
+        .bind(tx_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get an order by its tx hash
+    async fn get_order(&self, tx_hash: &str) -> Result<Option<OrderRecord>, TradingError> {
+        let row = sqlx::query(
+            r#"
+            SELECT exchange, wallet_id, symbol, side, reduce_only, base_amount, limit_price, nonce, client_order_index,
+                   tx_hash, submitted_at, status, tx_status, executed_at, fill_price, fee_usdc
+            FROM orders WHERE tx_hash = $1
+            "#,
+        )
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(order_from_row).transpose()
+    }
+
+    /// Get a wallet's orders submitted since `since`, oldest first
+    async fn get_wallet_orders(&self, wallet_id: u8, since: DateTime<Utc>) -> Result<Vec<OrderRecord>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT exchange, wallet_id, symbol, side, reduce_only, base_amount, limit_price, nonce, client_order_index,
+                   tx_hash, submitted_at, status, tx_status, executed_at, fill_price, fee_usdc
+            FROM orders WHERE wallet_id = $1 AND submitted_at >= $2 ORDER BY submitted_at ASC
+            "#,
+        )
+        .bind(wallet_id as i16)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(order_from_row).collect()
+    }
+
+    /// Total fees paid on orders submitted since `since` that were not reduce-only
+    async fn get_entry_fees_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        let fees: Option<Decimal> = sqlx::query_scalar("SELECT SUM(fee_usdc) FROM orders WHERE submitted_at >= $1 AND NOT reduce_only")
+            .bind(since)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(fees.unwrap_or_default())
+    }
+}
+
+fn order_from_row(row: &PgRow) -> Result<OrderRecord, TradingError> {
+    Ok(OrderRecord {
+        exchange: Exchange::from_str(row.try_get("exchange")?).map_err(|e| TradingError::InvalidInput(e.to_string()))?,
+        wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
+        symbol: row.try_get("symbol")?,
+        side: PositionSide::from_str(row.try_get("side")?).map_err(TradingError::InvalidInput)?,
+        reduce_only: row.try_get("reduce_only")?,
+        base_amount: row.try_get("base_amount")?,
+        limit_price: row.try_get("limit_price")?,
+        nonce: row.try_get("nonce")?,
+        client_order_index: row.try_get("client_order_index")?,
+        tx_hash: row.try_get("tx_hash")?,
+        submitted_at: row.try_get("submitted_at")?,
+        status: OrderStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        tx_status: row.try_get("tx_status")?,
+        executed_at: row.try_get("executed_at")?,
+        fill_price: row.try_get("fill_price")?,
+        fee_usdc: row.try_get("fee_usdc")?,
+    })
+}
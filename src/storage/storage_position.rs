@@ -1,11 +1,13 @@
 #![allow(unused)]
 
+use async_trait::async_trait;
 use crate::error::TradingError;
+use crate::storage::traits::PositionStore;
 use crate::model::exchange::Exchange;
 use crate::model::position::{Position, PositionSide, PositionStatus};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::{PgPool, Row};
+use sqlx::{postgres::PgRow, PgPool, Row};
 use std::str::FromStr;
 
 /// PostgreSQL-based storage for positions
@@ -16,64 +18,19 @@ pub struct PositionStorage {
 impl PositionStorage {
     /// Create a new storage instance with a database pool
     pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
-        let storage = Self { pool };
-        storage.init_schema().await?;
-        Ok(storage)
-    }
-
-    /// Initialize the database schema
-    async fn init_schema(&self) -> Result<(), TradingError> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS positions (
-                id TEXT PRIMARY KEY,
-                wallet_id SMALLINT NOT NULL,
-                strategy_id TEXT,
-                exchange TEXT NOT NULL,
-                symbol TEXT NOT NULL,
-                side TEXT NOT NULL,
-                size TEXT NOT NULL,
-                status TEXT NOT NULL,
-                opened_at TIMESTAMPTZ NOT NULL,
-                close_at TIMESTAMPTZ NOT NULL,
-                closed_at TIMESTAMPTZ,
-                realized_pnl TEXT,
-                updated_at TIMESTAMPTZ NOT NULL
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create an index for faster lookups
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_exchange_status 
-            ON positions(exchange, status)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_strategy_id 
-            ON positions(strategy_id)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        Ok(Self { pool })
     }
+}
 
+#[async_trait]
+impl PositionStore for PositionStorage {
     /// Save or update a position
-    pub async fn save_position(&self, position: &Position) -> Result<(), TradingError> {
+    async fn save_position(&self, position: &Position) -> Result<(), TradingError> {
         sqlx::query(
             r#"
             INSERT INTO positions 
-            (id, wallet_id, strategy_id, exchange, symbol, side, size, status, opened_at, close_at, closed_at, realized_pnl, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            (id, wallet_id, strategy_id, exchange, symbol, side, size, status, opened_at, close_at, closed_at, close_price, realized_pnl, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             ON CONFLICT (id) DO UPDATE SET
                 wallet_id = EXCLUDED.wallet_id,
                 strategy_id = EXCLUDED.strategy_id,
@@ -85,6 +42,7 @@ impl PositionStorage {
                 opened_at = EXCLUDED.opened_at,
                 close_at = EXCLUDED.close_at,
                 closed_at = EXCLUDED.closed_at,
+                close_price = EXCLUDED.close_price,
                 realized_pnl = EXCLUDED.realized_pnl,
                 updated_at = EXCLUDED.updated_at
             "#,
@@ -95,12 +53,13 @@ impl PositionStorage {
         .bind(position.exchange.to_string())
         .bind(&position.symbol)
         .bind(position.side.to_string())
-        .bind(position.size.to_string())
+        .bind(position.size)
         .bind(position.status.to_string())
         .bind(position.opened_at)
         .bind(position.close_at)
         .bind(position.closed_at)
-        .bind(position.realized_pnl.map(|pnl| pnl.to_string()))
+        .bind(position.close_price)
+        .bind(position.realized_pnl)
         .bind(position.updated_at)
         .execute(&self.pool)
         .await?;
@@ -109,11 +68,11 @@ impl PositionStorage {
     }
 
     /// Get a position by ID
-    pub async fn get_position(&self, id: &str) -> Result<Option<Position>, TradingError> {
+    async fn get_position(&self, id: &str) -> Result<Option<Position>, TradingError> {
         let row = sqlx::query(
             r#"
             SELECT id, wallet_id, strategy_id, exchange, symbol, side, size, status, 
-                   opened_at, close_at, closed_at, realized_pnl, updated_at
+                   opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
             FROM positions WHERE id = $1
             "#,
         )
@@ -122,74 +81,35 @@ impl PositionStorage {
         .await?;
 
         match row {
-            Some(row) => Ok(Some(Position {
-                wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
-                id: row.try_get("id")?,
-                strategy_id: row.try_get("strategy_id")?,
-                exchange: Exchange::from_str(row.try_get("exchange")?).unwrap(),
-                symbol: row.try_get("symbol")?,
-                side: PositionSide::from_str(row.try_get("side")?).unwrap(),
-                size: Decimal::from_str(row.try_get("size")?).unwrap(),
-                status: PositionStatus::from_str(row.try_get("status")?).unwrap(),
-                opened_at: row.try_get("opened_at")?,
-                close_at: row.try_get("close_at")?,
-                closed_at: row.try_get("closed_at")?,
-                realized_pnl: row
-                    .try_get::<Option<String>, _>("realized_pnl")?
-                    .and_then(|s| Decimal::from_str(&s).ok()),
-                updated_at: row.try_get("updated_at")?,
-            })),
+            Some(row) => Ok(Some(position_from_row(&row)?)),
             None => Ok(None),
         }
     }
 
     /// Get all positions
-    pub async fn get_all_positions(&self) -> Result<Vec<Position>, TradingError> {
+    async fn get_all_positions(&self) -> Result<Vec<Position>, TradingError> {
         let rows = sqlx::query(
             r#"
             SELECT id, wallet_id, strategy_id, exchange, symbol, side, size, status, 
-                   opened_at, close_at, closed_at, realized_pnl, updated_at
+                   opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
             FROM positions ORDER BY opened_at DESC
             "#,
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let positions = rows
-            .iter()
-            .map(|row| {
-                Ok(Position {
-                    wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
-                    id: row.try_get("id")?,
-                    strategy_id: row.try_get("strategy_id")?,
-                    exchange: Exchange::from_str(row.try_get("exchange")?).unwrap(),
-                    symbol: row.try_get("symbol")?,
-                    side: PositionSide::from_str(row.try_get("side")?).unwrap(),
-                    size: Decimal::from_str(row.try_get("size")?).unwrap(),
-                    status: PositionStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    updated_at: row.try_get("updated_at")?,
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(positions)
+        rows.iter().map(position_from_row).collect()
     }
 
     /// Get positions by exchange
-    pub async fn get_positions_by_exchange(
+    async fn get_positions_by_exchange(
         &self,
         exchange: Exchange,
     ) -> Result<Vec<Position>, TradingError> {
         let rows = sqlx::query(
             r#"
             SELECT id, wallet_id, strategy_id, exchange, symbol, side, size, status, 
-                   opened_at, close_at, closed_at, realized_pnl, updated_at
+                   opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
             FROM positions WHERE exchange = $1 ORDER BY opened_at DESC
             "#,
         )
@@ -197,72 +117,64 @@ impl PositionStorage {
         .fetch_all(&self.pool)
         .await?;
 
-        let positions = rows
-            .iter()
-            .map(|row| {
-                Ok(Position {
-                    wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
-                    id: row.try_get("id")?,
-                    strategy_id: row.try_get("strategy_id")?,
-                    exchange: Exchange::from_str(row.try_get("exchange")?).unwrap(),
-                    symbol: row.try_get("symbol")?,
-                    side: PositionSide::from_str(row.try_get("side")?).unwrap(),
-                    size: Decimal::from_str(row.try_get("size")?).unwrap(),
-                    status: PositionStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    updated_at: row.try_get("updated_at")?,
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(positions)
+        rows.iter().map(position_from_row).collect()
     }
 
     /// Get active positions (Open or Closing status)
-    pub async fn get_active_positions(&self) -> Result<Vec<Position>, TradingError> {
+    async fn get_active_positions(&self) -> Result<Vec<Position>, TradingError> {
         let rows = sqlx::query(
             r#"
             SELECT id, wallet_id, strategy_id, exchange, symbol, side, size, status, 
-                   opened_at, close_at, closed_at, realized_pnl, updated_at
+                   opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
             FROM positions WHERE status IN ('OPEN', 'CLOSING') ORDER BY opened_at DESC
             "#,
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let positions = rows
-            .iter()
-            .map(|row| {
-                Ok(Position {
-                    wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
-                    id: row.try_get("id")?,
-                    strategy_id: row.try_get("strategy_id")?,
-                    exchange: Exchange::from_str(row.try_get("exchange")?).unwrap(),
-                    symbol: row.try_get("symbol")?,
-                    side: PositionSide::from_str(row.try_get("side")?).unwrap(),
-                    size: Decimal::from_str(row.try_get("size")?).unwrap(),
-                    status: PositionStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    updated_at: row.try_get("updated_at")?,
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
+        rows.iter().map(position_from_row).collect()
+    }
+
+    /// Get every position of a strategy
+    async fn get_positions_by_strategy(&self, strategy_id: &str) -> Result<Vec<Position>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, wallet_id, strategy_id, exchange, symbol, side, size, status, 
+                   opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
+            FROM positions WHERE strategy_id = $1 ORDER BY opened_at ASC
+            "#,
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(positions)
+        rows.iter().map(position_from_row).collect()
+    }
+
+    /// Set the status of every position of a strategy that is not closed yet
+    async fn update_strategy_positions_status(
+        &self,
+        strategy_id: &str,
+        status: PositionStatus,
+    ) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE positions 
+            SET status = $1, updated_at = $2
+            WHERE strategy_id = $3 AND status <> 'CLOSED'
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(Utc::now())
+        .bind(strategy_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 
     /// Update position status and related fields
-    pub async fn update_position_status(
+    async fn update_position_status(
         &self,
         id: &str,
         status: PositionStatus,
@@ -280,7 +192,7 @@ impl PositionStorage {
         )
         .bind(status.to_string())
         .bind(closed_at)
-        .bind(realized_pnl.map(|pnl| pnl.to_string()))
+        .bind(realized_pnl)
         .bind(updated_at)
         .bind(id)
         .execute(&self.pool)
@@ -290,7 +202,7 @@ impl PositionStorage {
     }
 
     /// Delete a position by ID (use sparingly - prefer status updates)
-    pub async fn delete_position(&self, id: &str) -> Result<(), TradingError> {
+    async fn delete_position(&self, id: &str) -> Result<(), TradingError> {
         sqlx::query("DELETE FROM positions WHERE id = $1")
             .bind(id)
             .execute(&self.pool)
@@ -298,4 +210,23 @@ impl PositionStorage {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn position_from_row(row: &PgRow) -> Result<Position, TradingError> {
+    Ok(Position {
+        wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
+        id: row.try_get("id")?,
+        strategy_id: row.try_get("strategy_id")?,
+        exchange: Exchange::from_str(row.try_get("exchange")?).map_err(|e| TradingError::InvalidInput(e.to_string()))?,
+        symbol: row.try_get("symbol")?,
+        side: PositionSide::from_str(row.try_get("side")?).map_err(TradingError::InvalidInput)?,
+        size: row.try_get("size")?,
+        status: PositionStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        opened_at: row.try_get("opened_at")?,
+        close_at: row.try_get("close_at")?,
+        closed_at: row.try_get("closed_at")?,
+        close_price: row.try_get("close_price")?,
+        realized_pnl: row.try_get("realized_pnl")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
@@ -0,0 +1,259 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::storage::traits::RiskStore;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+
+/// Total account value and unrealized PnL across all wallets at one point in time
+#[derive(Debug, Clone)]
+pub struct EquitySnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub account_value: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+/// Latched state of the circuit breaker
+#[derive(Debug, Clone)]
+pub struct BreakerState {
+    pub tripped: bool,
+    pub reason: Option<String>,
+    pub tripped_at: Option<DateTime<Utc>>,
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+/// Latched state of the kill switch
+#[derive(Debug, Clone, Serialize)]
+pub struct KillSwitchState {
+    pub active: bool,
+    pub reason: Option<String>,
+    /// What triggered it (env, file, telegram, api or menu)
+    pub source: Option<String>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub cleared_at: Option<DateTime<Utc>>,
+}
+
+/// USDC moved between wallets to keep a position away from liquidation
+#[derive(Debug, Clone)]
+pub struct CollateralTransfer {
+    pub strategy_id: String,
+    pub from_wallet_id: u8,
+    pub to_wallet_id: u8,
+    pub amount_usdc: Decimal,
+    /// Transaction hash (None if the transfer failed)
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// PostgreSQL-based storage for equity snapshots, circuit breaker and kill switch state
+/// and collateral transfers
+#[derive(Clone)]
+pub struct RiskStorage {
+    pool: PgPool,
+}
+
+impl RiskStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl RiskStore for RiskStorage {
+    /// Save an equity snapshot and drop snapshots older than `retain_since`
+    async fn save_equity_snapshot(
+        &self,
+        snapshot: &EquitySnapshot,
+        retain_since: DateTime<Utc>,
+    ) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO equity_snapshots (taken_at, account_value, unrealized_pnl)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(snapshot.taken_at)
+        .bind(snapshot.account_value)
+        .bind(snapshot.unrealized_pnl)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("DELETE FROM equity_snapshots WHERE taken_at < $1")
+            .bind(retain_since)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get equity snapshots taken since the given time, oldest first
+    async fn get_equity_snapshots_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<EquitySnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT taken_at, account_value, unrealized_pnl
+            FROM equity_snapshots WHERE taken_at >= $1 ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(EquitySnapshot {
+                    taken_at: row.try_get("taken_at")?,
+                    account_value: row.try_get("account_value")?,
+                    unrealized_pnl: row.try_get("unrealized_pnl")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the kill switch state
+    async fn get_kill_switch_state(&self) -> Result<KillSwitchState, TradingError> {
+        let row = sqlx::query(
+            r#"
+            SELECT active, reason, source, triggered_at, cleared_at
+            FROM kill_switch WHERE id = 1
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(KillSwitchState {
+            active: row.try_get("active")?,
+            reason: row.try_get("reason")?,
+            source: row.try_get("source")?,
+            triggered_at: row.try_get("triggered_at")?,
+            cleared_at: row.try_get("cleared_at")?,
+        })
+    }
+
+    /// Activate the kill switch (no-op if it is already active)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was activated by this call
+    /// * `Ok(false)` - The kill switch was already active
+    async fn trigger_kill_switch(&self, reason: &str, source: &str) -> Result<bool, TradingError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE kill_switch
+            SET active = TRUE, reason = $1, source = $2, triggered_at = $3
+            WHERE id = 1 AND active = FALSE
+            "#,
+        )
+        .bind(reason)
+        .bind(source)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Clear the kill switch
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was active and is now cleared
+    /// * `Ok(false)` - The kill switch was not active
+    async fn clear_kill_switch(&self) -> Result<bool, TradingError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE kill_switch
+            SET active = FALSE, cleared_at = $1
+            WHERE id = 1 AND active = TRUE
+            "#,
+        )
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a collateral transfer, successful or not
+    async fn save_collateral_transfer(&self, transfer: &CollateralTransfer) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO collateral_transfers 
+            (strategy_id, from_wallet_id, to_wallet_id, amount_usdc, tx_hash, error, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&transfer.strategy_id)
+        .bind(transfer.from_wallet_id as i16)
+        .bind(transfer.to_wallet_id as i16)
+        .bind(transfer.amount_usdc)
+        .bind(&transfer.tx_hash)
+        .bind(&transfer.error)
+        .bind(transfer.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the circuit breaker state
+    async fn get_breaker_state(&self) -> Result<BreakerState, TradingError> {
+        let row = sqlx::query(
+            r#"
+            SELECT tripped, reason, tripped_at, reset_at
+            FROM circuit_breaker WHERE id = 1
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(BreakerState {
+            tripped: row.try_get("tripped")?,
+            reason: row.try_get("reason")?,
+            tripped_at: row.try_get("tripped_at")?,
+            reset_at: row.try_get("reset_at")?,
+        })
+    }
+
+    /// Trip the circuit breaker (no-op if it is already tripped)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The breaker was tripped by this call
+    /// * `Ok(false)` - The breaker was already tripped
+    async fn trip_breaker(&self, reason: &str) -> Result<bool, TradingError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE circuit_breaker
+            SET tripped = TRUE, reason = $1, tripped_at = $2
+            WHERE id = 1 AND tripped = FALSE
+            "#,
+        )
+        .bind(reason)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reset the circuit breaker after manual review
+    async fn reset_breaker(&self) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE circuit_breaker
+            SET tripped = FALSE, reset_at = $1
+            WHERE id = 1
+            "#,
+        )
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::storage::traits::SnapshotStore;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use std::collections::HashMap;
+
+/// Balances of one wallet at one point in time
+#[derive(Debug, Clone)]
+pub struct WalletBalanceSnapshot {
+    pub wallet_id: u8,
+    pub taken_at: DateTime<Utc>,
+    pub available_balance: Decimal,
+    pub collateral: Decimal,
+    pub total_asset_value: Decimal,
+}
+
+/// Lighter points of one wallet at one point in time
+#[derive(Debug, Clone)]
+pub struct WalletPointsSnapshot {
+    pub wallet_id: u8,
+    pub taken_at: DateTime<Utc>,
+    pub total_points: Decimal,
+    pub last_week_points: Decimal,
+}
+
+/// First and last snapshot of a wallet within a time window
+#[derive(Debug, Clone)]
+pub struct SnapshotDelta<T> {
+    pub wallet_id: u8,
+    pub start: T,
+    pub end: T,
+}
+
+impl SnapshotDelta<WalletBalanceSnapshot> {
+    /// Change in total asset value over the window (USDC)
+    pub fn total_asset_value_change(&self) -> Decimal {
+        self.end.total_asset_value - self.start.total_asset_value
+    }
+
+    /// Change in collateral over the window (USDC)
+    pub fn collateral_change(&self) -> Decimal {
+        self.end.collateral - self.start.collateral
+    }
+}
+
+impl SnapshotDelta<WalletPointsSnapshot> {
+    /// Points earned over the window
+    pub fn points_earned(&self) -> Decimal {
+        self.end.total_points - self.start.total_points
+    }
+}
+
+/// PostgreSQL-based storage for wallet balance and points time series
+#[derive(Clone)]
+pub struct SnapshotStorage {
+    pool: PgPool,
+}
+
+impl SnapshotStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+
+    /// First (`ASC`) or last (`DESC`) snapshot of every wallet within the window
+    async fn window_edge(
+        &self,
+        table: &str,
+        columns: &str,
+        order: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<PgRow>, TradingError> {
+        let query = format!(
+            "SELECT DISTINCT ON (wallet_id) {} FROM {} WHERE taken_at >= $1 AND taken_at <= $2 ORDER BY wallet_id, taken_at {}",
+            columns, table, order
+        );
+
+        Ok(sqlx::query(&query).bind(since).bind(until).fetch_all(&self.pool).await?)
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for SnapshotStorage {
+    /// Record a wallet's balances
+    async fn save_balance_snapshot(&self, snapshot: &WalletBalanceSnapshot) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_balance_snapshots (wallet_id, taken_at, available_balance, collateral, total_asset_value)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(snapshot.wallet_id as i16)
+        .bind(snapshot.taken_at)
+        .bind(snapshot.available_balance)
+        .bind(snapshot.collateral)
+        .bind(snapshot.total_asset_value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a wallet's Lighter points
+    async fn save_points_snapshot(&self, snapshot: &WalletPointsSnapshot) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_points_snapshots (wallet_id, taken_at, total_points, last_week_points)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(snapshot.wallet_id as i16)
+        .bind(snapshot.taken_at)
+        .bind(snapshot.total_points)
+        .bind(snapshot.last_week_points)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a wallet's balance snapshots taken between `since` and `until`, oldest first
+    async fn get_balance_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletBalanceSnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, available_balance, collateral, total_asset_value
+            FROM wallet_balance_snapshots
+            WHERE wallet_id = $1 AND taken_at >= $2 AND taken_at <= $3
+            ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(wallet_id as i16)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(balance_from_row).collect()
+    }
+
+    /// Get a wallet's points snapshots taken between `since` and `until`, oldest first
+    async fn get_points_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletPointsSnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, total_points, last_week_points
+            FROM wallet_points_snapshots
+            WHERE wallet_id = $1 AND taken_at >= $2 AND taken_at <= $3
+            ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(wallet_id as i16)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(points_from_row).collect()
+    }
+
+    /// Balance change of every wallet between its first and last snapshot in `[since, until]`
+    async fn get_balance_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletBalanceSnapshot>>, TradingError> {
+        let columns = "wallet_id, taken_at, available_balance, collateral, total_asset_value";
+        let start = self.window_edge("wallet_balance_snapshots", columns, "ASC", since, until).await?;
+        let end = self.window_edge("wallet_balance_snapshots", columns, "DESC", since, until).await?;
+
+        pair_edges(&start, &end, balance_from_row)
+    }
+
+    /// Points earned by every wallet between its first and last snapshot in `[since, until]`
+    async fn get_points_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletPointsSnapshot>>, TradingError> {
+        let columns = "wallet_id, taken_at, total_points, last_week_points";
+        let start = self.window_edge("wallet_points_snapshots", columns, "ASC", since, until).await?;
+        let end = self.window_edge("wallet_points_snapshots", columns, "DESC", since, until).await?;
+
+        pair_edges(&start, &end, points_from_row)
+    }
+}
+
+/// First and last snapshot of every wallet, from snapshots sorted oldest first
+pub(crate) fn window_deltas<T: Clone>(history: &[T], wallet_id: fn(&T) -> u8) -> Vec<SnapshotDelta<T>> {
+    let mut deltas: Vec<SnapshotDelta<T>> = Vec::new();
+    for snapshot in history {
+        let id = wallet_id(snapshot);
+        match deltas.iter_mut().find(|d| d.wallet_id == id) {
+            Some(delta) => delta.end = snapshot.clone(),
+            None => deltas.push(SnapshotDelta { wallet_id: id, start: snapshot.clone(), end: snapshot.clone() }),
+        }
+    }
+
+    deltas.sort_by_key(|d| d.wallet_id);
+    deltas
+}
+
+fn pair_edges<T>(
+    start: &[PgRow],
+    end: &[PgRow],
+    from_row: fn(&PgRow) -> Result<T, TradingError>,
+) -> Result<Vec<SnapshotDelta<T>>, TradingError> {
+    let mut ends: HashMap<i16, &PgRow> = HashMap::new();
+    for row in end {
+        ends.insert(row.try_get("wallet_id")?, row);
+    }
+
+    let mut deltas = Vec::new();
+    for row in start {
+        let wallet_id: i16 = row.try_get("wallet_id")?;
+        let Some(end) = ends.get(&wallet_id) else { continue };
+
+        deltas.push(SnapshotDelta {
+            wallet_id: wallet_id as u8,
+            start: from_row(row)?,
+            end: from_row(end)?,
+        });
+    }
+
+    Ok(deltas)
+}
+
+fn balance_from_row(row: &PgRow) -> Result<WalletBalanceSnapshot, TradingError> {
+    Ok(WalletBalanceSnapshot {
+        wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
+        taken_at: row.try_get("taken_at")?,
+        available_balance: row.try_get("available_balance")?,
+        collateral: row.try_get("collateral")?,
+        total_asset_value: row.try_get("total_asset_value")?,
+    })
+}
+
+fn points_from_row(row: &PgRow) -> Result<WalletPointsSnapshot, TradingError> {
+    Ok(WalletPointsSnapshot {
+        wallet_id: row.try_get::<i16, _>("wallet_id")? as u8,
+        taken_at: row.try_get("taken_at")?,
+        total_points: row.try_get("total_points")?,
+        last_week_points: row.try_get("last_week_points")?,
+    })
+}
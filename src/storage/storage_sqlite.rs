@@ -0,0 +1,1044 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use crate::error::TradingError;
+use crate::model::exchange::Exchange;
+use crate::model::position::{Position, PositionSide, PositionStatus};
+use crate::storage::storage_event::{StrategyEvent, StrategyEventKind};
+use crate::storage::storage_lease::WalletLease;
+use crate::storage::storage_order::{OrderRecord, OrderStatus};
+use crate::storage::storage_risk::{BreakerState, CollateralTransfer, EquitySnapshot, KillSwitchState};
+use crate::storage::storage_snapshot::{window_deltas, SnapshotDelta, WalletBalanceSnapshot, WalletPointsSnapshot};
+use crate::storage::storage_strategy::{check_transition, parse_symbols, stale_status_error, status_timestamp, StrategyMetadata};
+use crate::storage::traits::{EventStore, LeaseStore, OrderStore, PositionStore, RiskStore, SnapshotStore, StrategyStore};
+use crate::trader::strategy::{StrategyStatus, StrategyType, TradingStrategy};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqliteRow};
+use sqlx::{types::Json, Row};
+use std::str::FromStr;
+
+/// SQLite schema embedded from `migrations_sqlite/` at build time
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations_sqlite");
+
+const POSITION_COLUMNS: &str = r#"
+    id, wallet_id, strategy_id, exchange, symbol, side, size, status,
+    opened_at, close_at, closed_at, close_price, realized_pnl, updated_at
+"#;
+
+/// Columns of `strategies s`, with its wallets and position IDs from the link tables
+const STRATEGY_COLUMNS: &str = r#"
+    s.id, s.token_symbol, s.longs_size, s.shorts_size, s.status, s.opened_at, s.updated_at,
+    s.close_at, s.closed_at, s.realized_pnl, s.strategy_type, s.hedge_symbols, s.hedge_ratio,
+    s.estimated_cost, s.estimated_cost_bps, s.entry_slippage_bps, s.max_leg_slippage_bps,
+    (SELECT GROUP_CONCAT(w.wallet_id) FROM strategy_wallets w WHERE w.strategy_id = s.id) AS wallet_ids,
+    (SELECT GROUP_CONCAT(p.position_id) FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'LONG') AS long_position_ids,
+    (SELECT GROUP_CONCAT(p.position_id) FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'SHORT') AS short_position_ids
+"#;
+
+const ORDER_COLUMNS: &str = r#"
+    exchange, wallet_id, symbol, side, reduce_only, base_amount, limit_price, nonce, client_order_index,
+    tx_hash, submitted_at, status, tx_status, executed_at, fill_price, fee_usdc
+"#;
+
+/// SQLite-based storage for every store, in a single database file
+///
+/// Amounts are stored as TEXT so they round-trip exactly; sums are computed in Rust.
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Create a new storage instance with a database pool
+    pub async fn new(pool: SqlitePool) -> Result<Self, TradingError> {
+        Ok(Self { pool })
+    }
+
+    /// Open (or create) the database file at `path` and apply the SQLite schema
+    pub async fn connect(path: &str) -> Result<Self, TradingError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .foreign_keys(true);
+
+        let pool = SqlitePool::connect_with(options).await?;
+        SQLITE_MIGRATOR.run(&pool).await?;
+
+        Self::new(pool).await
+    }
+
+    /// Fetch strategies with a `WHERE` / `ORDER BY` clause on `strategies s`
+    async fn fetch_strategies(&self, clause: &str) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!("SELECT {} FROM strategies s {}", STRATEGY_COLUMNS, clause);
+        let rows = sqlx::query(&query).fetch_all(&self.pool).await?;
+
+        rows.iter().map(strategy_from_row).collect()
+    }
+
+    /// Fetch positions with a `WHERE` / `ORDER BY` clause, binding `param` to `?1` if given
+    async fn fetch_positions(&self, clause: &str, param: Option<&str>) -> Result<Vec<Position>, TradingError> {
+        let query = format!("SELECT {} FROM positions {}", POSITION_COLUMNS, clause);
+        let mut statement = sqlx::query(&query);
+        if let Some(param) = param {
+            statement = statement.bind(param);
+        }
+        let rows = statement.fetch_all(&self.pool).await?;
+
+        rows.iter().map(position_from_row).collect()
+    }
+}
+
+#[async_trait]
+impl PositionStore for SqliteStorage {
+    async fn save_position(&self, position: &Position) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO positions
+            (id, wallet_id, strategy_id, exchange, symbol, side, size, status, opened_at, close_at, closed_at, close_price, realized_pnl, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT (id) DO UPDATE SET
+                wallet_id = excluded.wallet_id,
+                strategy_id = excluded.strategy_id,
+                exchange = excluded.exchange,
+                symbol = excluded.symbol,
+                side = excluded.side,
+                size = excluded.size,
+                status = excluded.status,
+                opened_at = excluded.opened_at,
+                close_at = excluded.close_at,
+                closed_at = excluded.closed_at,
+                close_price = excluded.close_price,
+                realized_pnl = excluded.realized_pnl,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&position.id)
+        .bind(position.wallet_id as i64)
+        .bind(&position.strategy_id)
+        .bind(position.exchange.to_string())
+        .bind(&position.symbol)
+        .bind(position.side.to_string())
+        .bind(position.size.to_string())
+        .bind(position.status.to_string())
+        .bind(position.opened_at)
+        .bind(position.close_at)
+        .bind(position.closed_at)
+        .bind(position.close_price.map(|d| d.to_string()))
+        .bind(position.realized_pnl.map(|d| d.to_string()))
+        .bind(position.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_position(&self, id: &str) -> Result<Option<Position>, TradingError> {
+        Ok(self.fetch_positions("WHERE id = ?1", Some(id)).await?.into_iter().next())
+    }
+
+    async fn get_all_positions(&self) -> Result<Vec<Position>, TradingError> {
+        self.fetch_positions("ORDER BY opened_at DESC", None).await
+    }
+
+    async fn get_positions_by_exchange(&self, exchange: Exchange) -> Result<Vec<Position>, TradingError> {
+        self.fetch_positions("WHERE exchange = ?1 ORDER BY opened_at DESC", Some(&exchange.to_string())).await
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<Position>, TradingError> {
+        self.fetch_positions("WHERE status IN ('OPEN', 'CLOSING') ORDER BY opened_at DESC", None).await
+    }
+
+    async fn get_positions_by_strategy(&self, strategy_id: &str) -> Result<Vec<Position>, TradingError> {
+        self.fetch_positions("WHERE strategy_id = ?1 ORDER BY opened_at ASC", Some(strategy_id)).await
+    }
+
+    async fn update_strategy_positions_status(&self, strategy_id: &str, status: PositionStatus) -> Result<(), TradingError> {
+        sqlx::query("UPDATE positions SET status = ?1, updated_at = ?2 WHERE strategy_id = ?3 AND status <> 'CLOSED'")
+            .bind(status.to_string())
+            .bind(Utc::now())
+            .bind(strategy_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_position_status(
+        &self,
+        id: &str,
+        status: PositionStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<(), TradingError> {
+        sqlx::query("UPDATE positions SET status = ?1, closed_at = ?2, realized_pnl = ?3, updated_at = ?4 WHERE id = ?5")
+            .bind(status.to_string())
+            .bind(closed_at)
+            .bind(realized_pnl.map(|d| d.to_string()))
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_position(&self, id: &str) -> Result<(), TradingError> {
+        sqlx::query("DELETE FROM positions WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StrategyStore for SqliteStorage {
+    async fn save_strategy(&self, strategy: &TradingStrategy) -> Result<(), TradingError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO strategies
+            (id, token_symbol, longs_size, shorts_size, status, opened_at, updated_at, close_at, closed_at, realized_pnl,
+             strategy_type, hedge_symbols, hedge_ratio, estimated_cost, estimated_cost_bps, entry_slippage_bps, max_leg_slippage_bps)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+            ON CONFLICT (id) DO UPDATE SET
+                token_symbol = excluded.token_symbol,
+                longs_size = excluded.longs_size,
+                shorts_size = excluded.shorts_size,
+                status = excluded.status,
+                opened_at = excluded.opened_at,
+                updated_at = excluded.updated_at,
+                close_at = excluded.close_at,
+                closed_at = excluded.closed_at,
+                realized_pnl = excluded.realized_pnl,
+                strategy_type = excluded.strategy_type,
+                hedge_symbols = excluded.hedge_symbols,
+                hedge_ratio = excluded.hedge_ratio,
+                estimated_cost = excluded.estimated_cost,
+                estimated_cost_bps = excluded.estimated_cost_bps,
+                entry_slippage_bps = excluded.entry_slippage_bps,
+                max_leg_slippage_bps = excluded.max_leg_slippage_bps
+            "#,
+        )
+        .bind(&strategy.id)
+        .bind(&strategy.token_symbol)
+        .bind(strategy.longs_size.to_string())
+        .bind(strategy.shorts_size.to_string())
+        .bind(strategy.status.to_string())
+        .bind(strategy.opened_at)
+        .bind(strategy.updated_at)
+        .bind(strategy.close_at)
+        .bind(strategy.closed_at)
+        .bind(strategy.realized_pnl.map(|d| d.to_string()))
+        .bind(strategy.strategy_type.to_string())
+        .bind(strategy.hedge_symbols.join(","))
+        .bind(strategy.hedge_ratio.map(|d| d.to_string()))
+        .bind(strategy.estimated_cost_usdc.map(|d| d.to_string()))
+        .bind(strategy.estimated_cost_bps.map(|d| d.to_string()))
+        .bind(strategy.entry_slippage_bps.map(|d| d.to_string()))
+        .bind(strategy.max_leg_slippage_bps.map(|d| d.to_string()))
+        .execute(&mut *tx)
+        .await?;
+
+        // Replace the strategy's wallets and positions
+        sqlx::query("DELETE FROM strategy_wallets WHERE strategy_id = ?1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM strategy_positions WHERE strategy_id = ?1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for wallet_id in &strategy.wallet_ids {
+            sqlx::query("INSERT INTO strategy_wallets (strategy_id, wallet_id) VALUES (?1, ?2) ON CONFLICT DO NOTHING")
+                .bind(&strategy.id)
+                .bind(*wallet_id as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for position in strategy.longs.iter().chain(strategy.shorts.iter()) {
+            sqlx::query("INSERT INTO strategy_positions (strategy_id, position_id, side) VALUES (?1, ?2, ?3) ON CONFLICT DO NOTHING")
+                .bind(&strategy.id)
+                .bind(&position.id)
+                .bind(position.side.to_string())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_strategy_metadata(&self, id: &str) -> Result<Option<StrategyMetadata>, TradingError> {
+        let query = format!("SELECT {} FROM strategies s WHERE s.id = ?1", STRATEGY_COLUMNS);
+        let row = sqlx::query(&query).bind(id).fetch_optional(&self.pool).await?;
+
+        row.as_ref().map(strategy_from_row).transpose()
+    }
+
+    async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("WHERE s.status IN ('RUNNING', 'CLOSING') ORDER BY s.close_at ASC").await
+    }
+
+    async fn get_strategies_to_close(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!(
+            "SELECT {} FROM strategies s WHERE s.status = 'RUNNING' AND s.close_at <= ?1 ORDER BY s.close_at ASC",
+            STRATEGY_COLUMNS
+        );
+        let rows = sqlx::query(&query).bind(Utc::now()).fetch_all(&self.pool).await?;
+
+        rows.iter().map(strategy_from_row).collect()
+    }
+
+    async fn update_strategy_status(
+        &self,
+        id: &str,
+        from: StrategyStatus,
+        updated_at: DateTime<Utc>,
+        to: StrategyStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<DateTime<Utc>, TradingError> {
+        check_transition(id, from, to)?;
+        let now = status_timestamp();
+
+        let result = sqlx::query(
+            "UPDATE strategies SET status = ?1, closed_at = ?2, realized_pnl = ?3, updated_at = ?4 WHERE id = ?5 AND status = ?6 AND updated_at = ?7",
+        )
+        .bind(to.to_string())
+        .bind(closed_at)
+        .bind(realized_pnl.map(|d| d.to_string()))
+        .bind(now)
+        .bind(id)
+        .bind(from.to_string())
+        .bind(updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let found = self.get_strategy_metadata(id).await?.map(|s| s.status);
+            return Err(stale_status_error(id, from, updated_at, found));
+        }
+
+        Ok(now)
+    }
+
+    async fn get_all_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("ORDER BY s.opened_at DESC").await
+    }
+
+    async fn get_failed_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("WHERE s.status = 'FAILED' ORDER BY s.updated_at DESC").await
+    }
+
+    async fn get_strategies_for_wallet(&self, wallet_id: u8) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!(
+            r#"
+            SELECT {} FROM strategies s
+            WHERE EXISTS (SELECT 1 FROM strategy_wallets w WHERE w.strategy_id = s.id AND w.wallet_id = ?1)
+            ORDER BY s.opened_at DESC
+            "#,
+            STRATEGY_COLUMNS
+        );
+        let rows = sqlx::query(&query).bind(wallet_id as i64).fetch_all(&self.pool).await?;
+
+        rows.iter().map(strategy_from_row).collect()
+    }
+
+    async fn get_realized_pnl_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        let values: Vec<String> = sqlx::query_scalar("SELECT realized_pnl FROM strategies WHERE closed_at >= ?1 AND realized_pnl IS NOT NULL")
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        sum_decimals(&values)
+    }
+}
+
+#[async_trait]
+impl EventStore for SqliteStorage {
+    async fn record_event(&self, event: &StrategyEvent) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_events (strategy_id, kind, wallet_id, symbol, details, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&event.strategy_id)
+        .bind(event.kind.to_string())
+        .bind(event.wallet_id.map(|id| id as i64))
+        .bind(&event.symbol)
+        .bind(Json(&event.details))
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_strategy_events(&self, strategy_id: &str) -> Result<Vec<StrategyEvent>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, strategy_id, kind, wallet_id, symbol, details, created_at
+            FROM strategy_events WHERE strategy_id = ?1 ORDER BY id ASC
+            "#,
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+
+    async fn get_events_by_kind(&self, kind: StrategyEventKind, since: DateTime<Utc>) -> Result<Vec<StrategyEvent>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, strategy_id, kind, wallet_id, symbol, details, created_at
+            FROM strategy_events WHERE kind = ?1 AND created_at >= ?2 ORDER BY id DESC
+            "#,
+        )
+        .bind(kind.to_string())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(event_from_row).collect()
+    }
+}
+
+#[async_trait]
+impl OrderStore for SqliteStorage {
+    async fn record_order(&self, order: &OrderRecord) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO orders
+            (exchange, wallet_id, symbol, side, reduce_only, base_amount, limit_price, nonce, client_order_index,
+             tx_hash, submitted_at, status, tx_status, executed_at, fill_price, fee_usdc, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+        )
+        .bind(order.exchange.to_string())
+        .bind(order.wallet_id as i64)
+        .bind(&order.symbol)
+        .bind(order.side.to_string())
+        .bind(order.reduce_only)
+        .bind(order.base_amount)
+        .bind(order.limit_price.to_string())
+        .bind(order.nonce)
+        .bind(order.client_order_index)
+        .bind(&order.tx_hash)
+        .bind(order.submitted_at)
+        .bind(order.status.to_string())
+        .bind(order.tx_status)
+        .bind(order.executed_at)
+        .bind(order.fill_price.map(|d| d.to_string()))
+        .bind(order.fee_usdc.map(|d| d.to_string()))
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_order_status(
+        &self,
+        tx_hash: &str,
+        status: OrderStatus,
+        tx_status: Option<i64>,
+        executed_at: Option<i64>,
+    ) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET status = ?1, tx_status = COALESCE(?2, tx_status), executed_at = COALESCE(?3, executed_at), updated_at = ?4
+            WHERE tx_hash = ?5
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(tx_status)
+        .bind(executed_at)
+        .bind(Utc::now())
+        .bind(tx_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_order_fill(&self, tx_hash: &str, fill_price: Option<Decimal>, fee_usdc: Option<Decimal>) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET fill_price = COALESCE(?1, fill_price), fee_usdc = COALESCE(?2, fee_usdc), updated_at = ?3
+            WHERE tx_hash = ?4
+            "#,
+        )
+        .bind(fill_price.map(|d| d.to_string()))
+        .bind(fee_usdc.map(|d| d.to_string()))
+        .bind(Utc::now())
+        .bind(tx_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_order(&self, tx_hash: &str) -> Result<Option<OrderRecord>, TradingError> {
+        let query = format!("SELECT {} FROM orders WHERE tx_hash = ?1", ORDER_COLUMNS);
+        let row = sqlx::query(&query).bind(tx_hash).fetch_optional(&self.pool).await?;
+
+        row.as_ref().map(order_from_row).transpose()
+    }
+
+    async fn get_wallet_orders(&self, wallet_id: u8, since: DateTime<Utc>) -> Result<Vec<OrderRecord>, TradingError> {
+        let query = format!(
+            "SELECT {} FROM orders WHERE wallet_id = ?1 AND submitted_at >= ?2 ORDER BY submitted_at ASC",
+            ORDER_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(wallet_id as i64)
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(order_from_row).collect()
+    }
+
+    async fn get_entry_fees_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        let values: Vec<String> = sqlx::query_scalar("SELECT fee_usdc FROM orders WHERE submitted_at >= ?1 AND NOT reduce_only AND fee_usdc IS NOT NULL")
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        sum_decimals(&values)
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for SqliteStorage {
+    async fn save_balance_snapshot(&self, snapshot: &WalletBalanceSnapshot) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_balance_snapshots (wallet_id, taken_at, available_balance, collateral, total_asset_value)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(snapshot.wallet_id as i64)
+        .bind(snapshot.taken_at)
+        .bind(snapshot.available_balance.to_string())
+        .bind(snapshot.collateral.to_string())
+        .bind(snapshot.total_asset_value.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_points_snapshot(&self, snapshot: &WalletPointsSnapshot) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO wallet_points_snapshots (wallet_id, taken_at, total_points, last_week_points)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(snapshot.wallet_id as i64)
+        .bind(snapshot.taken_at)
+        .bind(snapshot.total_points.to_string())
+        .bind(snapshot.last_week_points.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_balance_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletBalanceSnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, available_balance, collateral, total_asset_value
+            FROM wallet_balance_snapshots
+            WHERE wallet_id = ?1 AND taken_at >= ?2 AND taken_at <= ?3
+            ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(wallet_id as i64)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(balance_from_row).collect()
+    }
+
+    async fn get_points_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletPointsSnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, total_points, last_week_points
+            FROM wallet_points_snapshots
+            WHERE wallet_id = ?1 AND taken_at >= ?2 AND taken_at <= ?3
+            ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(wallet_id as i64)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(points_from_row).collect()
+    }
+
+    async fn get_balance_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletBalanceSnapshot>>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, available_balance, collateral, total_asset_value
+            FROM wallet_balance_snapshots WHERE taken_at >= ?1 AND taken_at <= ?2
+            ORDER BY taken_at ASC, id ASC
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let history = rows.iter().map(balance_from_row).collect::<Result<Vec<_>, _>>()?;
+        Ok(window_deltas(&history, |s| s.wallet_id))
+    }
+
+    async fn get_points_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletPointsSnapshot>>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wallet_id, taken_at, total_points, last_week_points
+            FROM wallet_points_snapshots WHERE taken_at >= ?1 AND taken_at <= ?2
+            ORDER BY taken_at ASC, id ASC
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let history = rows.iter().map(points_from_row).collect::<Result<Vec<_>, _>>()?;
+        Ok(window_deltas(&history, |s| s.wallet_id))
+    }
+}
+
+#[async_trait]
+impl RiskStore for SqliteStorage {
+    async fn save_equity_snapshot(&self, snapshot: &EquitySnapshot, retain_since: DateTime<Utc>) -> Result<(), TradingError> {
+        sqlx::query("INSERT INTO equity_snapshots (taken_at, account_value, unrealized_pnl) VALUES (?1, ?2, ?3)")
+            .bind(snapshot.taken_at)
+            .bind(snapshot.account_value.to_string())
+            .bind(snapshot.unrealized_pnl.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("DELETE FROM equity_snapshots WHERE taken_at < ?1")
+            .bind(retain_since)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_equity_snapshots_since(&self, since: DateTime<Utc>) -> Result<Vec<EquitySnapshot>, TradingError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT taken_at, account_value, unrealized_pnl
+            FROM equity_snapshots WHERE taken_at >= ?1 ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(EquitySnapshot {
+                    taken_at: row.try_get("taken_at")?,
+                    account_value: decimal(row, "account_value")?,
+                    unrealized_pnl: decimal(row, "unrealized_pnl")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_kill_switch_state(&self) -> Result<KillSwitchState, TradingError> {
+        let row = sqlx::query("SELECT active, reason, source, triggered_at, cleared_at FROM kill_switch WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(KillSwitchState {
+            active: row.try_get("active")?,
+            reason: row.try_get("reason")?,
+            source: row.try_get("source")?,
+            triggered_at: row.try_get("triggered_at")?,
+            cleared_at: row.try_get("cleared_at")?,
+        })
+    }
+
+    async fn trigger_kill_switch(&self, reason: &str, source: &str) -> Result<bool, TradingError> {
+        let result = sqlx::query(
+            "UPDATE kill_switch SET active = TRUE, reason = ?1, source = ?2, triggered_at = ?3 WHERE id = 1 AND active = FALSE",
+        )
+        .bind(reason)
+        .bind(source)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn clear_kill_switch(&self) -> Result<bool, TradingError> {
+        let result = sqlx::query("UPDATE kill_switch SET active = FALSE, cleared_at = ?1 WHERE id = 1 AND active = TRUE")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn save_collateral_transfer(&self, transfer: &CollateralTransfer) -> Result<(), TradingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO collateral_transfers
+            (strategy_id, from_wallet_id, to_wallet_id, amount_usdc, tx_hash, error, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&transfer.strategy_id)
+        .bind(transfer.from_wallet_id as i64)
+        .bind(transfer.to_wallet_id as i64)
+        .bind(transfer.amount_usdc.to_string())
+        .bind(&transfer.tx_hash)
+        .bind(&transfer.error)
+        .bind(transfer.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_breaker_state(&self) -> Result<BreakerState, TradingError> {
+        let row = sqlx::query("SELECT tripped, reason, tripped_at, reset_at FROM circuit_breaker WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(BreakerState {
+            tripped: row.try_get("tripped")?,
+            reason: row.try_get("reason")?,
+            tripped_at: row.try_get("tripped_at")?,
+            reset_at: row.try_get("reset_at")?,
+        })
+    }
+
+    async fn trip_breaker(&self, reason: &str) -> Result<bool, TradingError> {
+        let result = sqlx::query("UPDATE circuit_breaker SET tripped = TRUE, reason = ?1, tripped_at = ?2 WHERE id = 1 AND tripped = FALSE")
+            .bind(reason)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn reset_breaker(&self) -> Result<(), TradingError> {
+        sqlx::query("UPDATE circuit_breaker SET tripped = FALSE, reset_at = ?1 WHERE id = 1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LeaseStore for SqliteStorage {
+    async fn claim_wallets(
+        &self,
+        holder: &str,
+        wallet_ids: &[u8],
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, TradingError> {
+        let mut tx = self.pool.begin().await?;
+        let mut claimed = Vec::new();
+
+        for &wallet_id in wallet_ids {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO wallet_leases (wallet_id, holder, acquired_at, expires_at)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (wallet_id) DO UPDATE SET
+                    holder = excluded.holder,
+                    acquired_at = CASE
+                        WHEN wallet_leases.holder = excluded.holder THEN wallet_leases.acquired_at
+                        ELSE excluded.acquired_at
+                    END,
+                    expires_at = excluded.expires_at
+                WHERE wallet_leases.holder = excluded.holder OR wallet_leases.expires_at <= excluded.acquired_at
+                "#,
+            )
+            .bind(wallet_id as i64)
+            .bind(holder)
+            .bind(now)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                claimed.push(wallet_id);
+            }
+        }
+
+        tx.commit().await?;
+        claimed.sort_unstable();
+
+        Ok(claimed)
+    }
+
+    async fn get_wallet_leases(&self) -> Result<Vec<WalletLease>, TradingError> {
+        let rows = sqlx::query("SELECT wallet_id, holder, acquired_at, expires_at FROM wallet_leases ORDER BY wallet_id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(lease_from_row).collect()
+    }
+}
+
+/// Parse a TEXT amount column
+fn decimal(row: &SqliteRow, column: &str) -> Result<Decimal, TradingError> {
+    let value: String = row.try_get(column)?;
+    Decimal::from_str(&value).map_err(|e| TradingError::InvalidInput(format!("Invalid {} '{}': {}", column, value, e)))
+}
+
+/// Parse a nullable TEXT amount column
+fn optional_decimal(row: &SqliteRow, column: &str) -> Result<Option<Decimal>, TradingError> {
+    match row.try_get::<Option<String>, _>(column)? {
+        Some(_) => decimal(row, column).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn sum_decimals(values: &[String]) -> Result<Decimal, TradingError> {
+    values.iter().try_fold(Decimal::ZERO, |sum, value| {
+        Decimal::from_str(value)
+            .map(|value| sum + value)
+            .map_err(|e| TradingError::InvalidInput(format!("Invalid amount '{}': {}", value, e)))
+    })
+}
+
+/// Split a `GROUP_CONCAT` column into sorted values
+fn concat_list(row: &SqliteRow, column: &str) -> Result<Vec<String>, TradingError> {
+    let mut values = parse_symbols(&row.try_get::<Option<String>, _>(column)?.unwrap_or_default());
+    values.sort();
+    Ok(values)
+}
+
+fn position_from_row(row: &SqliteRow) -> Result<Position, TradingError> {
+    Ok(Position {
+        wallet_id: row.try_get::<i64, _>("wallet_id")? as u8,
+        id: row.try_get("id")?,
+        strategy_id: row.try_get("strategy_id")?,
+        exchange: Exchange::from_str(row.try_get("exchange")?).map_err(|e| TradingError::InvalidInput(e.to_string()))?,
+        symbol: row.try_get("symbol")?,
+        side: PositionSide::from_str(row.try_get("side")?).map_err(TradingError::InvalidInput)?,
+        size: decimal(row, "size")?,
+        status: PositionStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        opened_at: row.try_get("opened_at")?,
+        close_at: row.try_get("close_at")?,
+        closed_at: row.try_get("closed_at")?,
+        close_price: optional_decimal(row, "close_price")?,
+        realized_pnl: optional_decimal(row, "realized_pnl")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn strategy_from_row(row: &SqliteRow) -> Result<StrategyMetadata, TradingError> {
+    let mut wallet_ids = concat_list(row, "wallet_ids")?
+        .iter()
+        .map(|id| id.parse::<u8>().map_err(|e| TradingError::InvalidInput(format!("Invalid wallet ID '{}': {}", id, e))))
+        .collect::<Result<Vec<u8>, _>>()?;
+    wallet_ids.sort_unstable();
+
+    Ok(StrategyMetadata {
+        id: row.try_get("id")?,
+        token_symbol: row.try_get("token_symbol")?,
+        wallet_ids,
+        longs_size: decimal(row, "longs_size")?,
+        shorts_size: decimal(row, "shorts_size")?,
+        status: StrategyStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        opened_at: row.try_get("opened_at")?,
+        updated_at: row.try_get("updated_at")?,
+        close_at: row.try_get("close_at")?,
+        closed_at: row.try_get("closed_at")?,
+        realized_pnl: optional_decimal(row, "realized_pnl")?,
+        long_position_ids: concat_list(row, "long_position_ids")?,
+        short_position_ids: concat_list(row, "short_position_ids")?,
+        strategy_type: StrategyType::from_str(row.try_get("strategy_type")?).map_err(TradingError::InvalidInput)?,
+        hedge_symbols: parse_symbols(row.try_get("hedge_symbols")?),
+        hedge_ratio: optional_decimal(row, "hedge_ratio")?,
+        estimated_cost_usdc: optional_decimal(row, "estimated_cost")?,
+        estimated_cost_bps: optional_decimal(row, "estimated_cost_bps")?,
+        entry_slippage_bps: optional_decimal(row, "entry_slippage_bps")?,
+        max_leg_slippage_bps: optional_decimal(row, "max_leg_slippage_bps")?,
+    })
+}
+
+fn event_from_row(row: &SqliteRow) -> Result<StrategyEvent, TradingError> {
+    Ok(StrategyEvent {
+        id: row.try_get("id")?,
+        strategy_id: row.try_get("strategy_id")?,
+        kind: StrategyEventKind::from_str(row.try_get("kind")?).map_err(TradingError::InvalidInput)?,
+        wallet_id: row.try_get::<Option<i64>, _>("wallet_id")?.map(|id| id as u8),
+        symbol: row.try_get("symbol")?,
+        details: row.try_get::<Json<Value>, _>("details")?.0,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn order_from_row(row: &SqliteRow) -> Result<OrderRecord, TradingError> {
+    Ok(OrderRecord {
+        exchange: Exchange::from_str(row.try_get("exchange")?).map_err(|e| TradingError::InvalidInput(e.to_string()))?,
+        wallet_id: row.try_get::<i64, _>("wallet_id")? as u8,
+        symbol: row.try_get("symbol")?,
+        side: PositionSide::from_str(row.try_get("side")?).map_err(TradingError::InvalidInput)?,
+        reduce_only: row.try_get("reduce_only")?,
+        base_amount: row.try_get("base_amount")?,
+        limit_price: decimal(row, "limit_price")?,
+        nonce: row.try_get("nonce")?,
+        client_order_index: row.try_get("client_order_index")?,
+        tx_hash: row.try_get("tx_hash")?,
+        submitted_at: row.try_get("submitted_at")?,
+        status: OrderStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        tx_status: row.try_get("tx_status")?,
+        executed_at: row.try_get("executed_at")?,
+        fill_price: optional_decimal(row, "fill_price")?,
+        fee_usdc: optional_decimal(row, "fee_usdc")?,
+    })
+}
+
+fn balance_from_row(row: &SqliteRow) -> Result<WalletBalanceSnapshot, TradingError> {
+    Ok(WalletBalanceSnapshot {
+        wallet_id: row.try_get::<i64, _>("wallet_id")? as u8,
+        taken_at: row.try_get("taken_at")?,
+        available_balance: decimal(row, "available_balance")?,
+        collateral: decimal(row, "collateral")?,
+        total_asset_value: decimal(row, "total_asset_value")?,
+    })
+}
+
+fn points_from_row(row: &SqliteRow) -> Result<WalletPointsSnapshot, TradingError> {
+    Ok(WalletPointsSnapshot {
+        wallet_id: row.try_get::<i64, _>("wallet_id")? as u8,
+        taken_at: row.try_get("taken_at")?,
+        total_points: decimal(row, "total_points")?,
+        last_week_points: decimal(row, "last_week_points")?,
+    })
+}
+
+fn lease_from_row(row: &SqliteRow) -> Result<WalletLease, TradingError> {
+    Ok(WalletLease {
+        wallet_id: row.try_get::<i64, _>("wallet_id")? as u8,
+        holder: row.try_get("holder")?,
+        acquired_at: row.try_get("acquired_at")?,
+        expires_at: row.try_get("expires_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{conformance, Storage};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    /// Migrated database in memory, on a single connection that is never recycled
+    async fn storage() -> Storage {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:").unwrap().foreign_keys(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await
+            .unwrap();
+        SQLITE_MIGRATOR.run(&pool).await.unwrap();
+
+        Storage::from_backend(Arc::new(SqliteStorage::new(pool).await.unwrap()))
+    }
+
+    #[tokio::test]
+    async fn strategy_status_transitions() {
+        conformance::strategy_status_transitions(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn strategy_positions() {
+        conformance::strategy_positions(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn strategy_positions_must_exist() {
+        let storage = storage().await;
+        let position = conformance::position("p1", "s1", 1, PositionSide::Long);
+
+        let strategy = conformance::strategy("s1", vec![position], Vec::new());
+        assert!(storage.strategies.save_strategy(&strategy).await.is_err());
+        assert!(storage.strategies.get_strategy_metadata("s1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn wallet_leases() {
+        conformance::wallet_leases(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn orders() {
+        conformance::orders(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn events() {
+        conformance::events(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn snapshots() {
+        conformance::snapshots(&storage().await).await;
+    }
+
+    #[tokio::test]
+    async fn risk_switches() {
+        conformance::risk_switches(&storage().await).await;
+    }
+}
@@ -1,13 +1,27 @@
 #![allow(unused)]
 
+use async_trait::async_trait;
 use crate::error::TradingError;
-use crate::trader::strategy::{StrategyStatus, TradingStrategy};
-use chrono::{DateTime, Utc};
+use crate::storage::traits::StrategyStore;
+use crate::config::RiskLimits;
+use crate::model::{token::Token, Exchange};
+use crate::trader::strategy::{StrategyStatus, StrategyType, TradingStrategy};
+use chrono::{DateTime, SubsecRound, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
-use sqlx::{PgPool, Row};
+use sqlx::{postgres::PgRow, PgPool, Row};
 use std::str::FromStr;
 
+/// Columns of `strategies s`, with its wallets and position IDs from the link tables
+const STRATEGY_COLUMNS: &str = r#"
+    s.id, s.token_symbol, s.longs_size, s.shorts_size, s.status, s.opened_at, s.updated_at,
+    s.close_at, s.closed_at, s.realized_pnl, s.strategy_type, s.hedge_symbols, s.hedge_ratio,
+    s.estimated_cost, s.estimated_cost_bps, s.entry_slippage_bps, s.max_leg_slippage_bps,
+    ARRAY(SELECT w.wallet_id FROM strategy_wallets w WHERE w.strategy_id = s.id ORDER BY w.wallet_id) AS wallet_ids,
+    ARRAY(SELECT p.position_id FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'LONG' ORDER BY p.position_id) AS long_position_ids,
+    ARRAY(SELECT p.position_id FROM strategy_positions p WHERE p.strategy_id = s.id AND p.side = 'SHORT' ORDER BY p.position_id) AS short_position_ids
+"#;
+
 /// PostgreSQL-based storage for trading strategies
 pub struct StrategyStorage {
     pool: PgPool,
@@ -16,89 +30,32 @@ pub struct StrategyStorage {
 impl StrategyStorage {
     /// Create a new storage instance with a database pool
     pub async fn new(pool: PgPool) -> Result<Self, TradingError> {
-        let storage = Self { pool };
-        storage.init_schema().await?;
-        Ok(storage)
+        Ok(Self { pool })
     }
 
-    /// Initialize the database schema
-    async fn init_schema(&self) -> Result<(), TradingError> {
-        // Create strategies table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS strategies (
-                id TEXT PRIMARY KEY,
-                token_symbol TEXT NOT NULL,
-                wallet_ids TEXT NOT NULL,
-                longs_size TEXT NOT NULL,
-                shorts_size TEXT NOT NULL,
-                status TEXT NOT NULL,
-                opened_at TIMESTAMPTZ NOT NULL,
-                updated_at TIMESTAMPTZ NOT NULL,
-                close_at TIMESTAMPTZ NOT NULL,
-                closed_at TIMESTAMPTZ,
-                realized_pnl TEXT,
-                long_position_ids TEXT NOT NULL,
-                short_position_ids TEXT NOT NULL
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create index for faster lookups
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_strategy_status 
-            ON strategies(status)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_strategy_close_at 
-            ON strategies(close_at)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Fetch strategies with a `WHERE` / `ORDER BY` clause on `strategies s`
+    async fn fetch_strategies(&self, clause: &str) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!("SELECT {} FROM strategies s {}", STRATEGY_COLUMNS, clause);
+        let rows = sqlx::query(&query).fetch_all(&self.pool).await?;
 
-        Ok(())
+        rows.iter().map(strategy_from_row).collect()
     }
+}
 
-    /// Save or update a strategy
-    pub async fn save_strategy(&self, strategy: &TradingStrategy) -> Result<(), TradingError> {
-        let wallet_ids = strategy
-            .wallet_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let long_position_ids = strategy
-            .longs
-            .iter()
-            .map(|p| p.id.clone())
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let short_position_ids = strategy
-            .shorts
-            .iter()
-            .map(|p| p.id.clone())
-            .collect::<Vec<_>>()
-            .join(",");
+#[async_trait]
+impl StrategyStore for StrategyStorage {
+    /// Save or update a strategy with its wallets and positions
+    async fn save_strategy(&self, strategy: &TradingStrategy) -> Result<(), TradingError> {
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
             r#"
             INSERT INTO strategies 
-            (id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, close_at, closed_at, realized_pnl, long_position_ids, short_position_ids)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            (id, token_symbol, longs_size, shorts_size, status, opened_at, updated_at, close_at, closed_at, realized_pnl,
+             strategy_type, hedge_symbols, hedge_ratio, estimated_cost, estimated_cost_bps, entry_slippage_bps, max_leg_slippage_bps)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             ON CONFLICT (id) DO UPDATE SET
                 token_symbol = EXCLUDED.token_symbol,
-                wallet_ids = EXCLUDED.wallet_ids,
                 longs_size = EXCLUDED.longs_size,
                 shorts_size = EXCLUDED.shorts_size,
                 status = EXCLUDED.status,
@@ -107,247 +64,195 @@ impl StrategyStorage {
                 close_at = EXCLUDED.close_at,
                 closed_at = EXCLUDED.closed_at,
                 realized_pnl = EXCLUDED.realized_pnl,
-                long_position_ids = EXCLUDED.long_position_ids,
-                short_position_ids = EXCLUDED.short_position_ids
+                strategy_type = EXCLUDED.strategy_type,
+                hedge_symbols = EXCLUDED.hedge_symbols,
+                hedge_ratio = EXCLUDED.hedge_ratio,
+                estimated_cost = EXCLUDED.estimated_cost,
+                estimated_cost_bps = EXCLUDED.estimated_cost_bps,
+                entry_slippage_bps = EXCLUDED.entry_slippage_bps,
+                max_leg_slippage_bps = EXCLUDED.max_leg_slippage_bps
             "#,
         )
         .bind(&strategy.id)
         .bind(&strategy.token_symbol)
-        .bind(wallet_ids)
-        .bind(strategy.longs_size.to_string())
-        .bind(strategy.shorts_size.to_string())
+        .bind(strategy.longs_size)
+        .bind(strategy.shorts_size)
         .bind(strategy.status.to_string())
         .bind(strategy.opened_at)
         .bind(strategy.updated_at)
         .bind(strategy.close_at)
         .bind(strategy.closed_at)
-        .bind(strategy.realized_pnl.map(|pnl| pnl.to_string()))
-        .bind(long_position_ids)
-        .bind(short_position_ids)
-        .execute(&self.pool)
+        .bind(strategy.realized_pnl)
+        .bind(strategy.strategy_type.to_string())
+        .bind(strategy.hedge_symbols.join(","))
+        .bind(strategy.hedge_ratio)
+        .bind(strategy.estimated_cost_usdc)
+        .bind(strategy.estimated_cost_bps)
+        .bind(strategy.entry_slippage_bps)
+        .bind(strategy.max_leg_slippage_bps)
+        .execute(&mut *tx)
+        .await?;
+
+        // Replace the strategy's wallets and positions
+        sqlx::query("DELETE FROM strategy_wallets WHERE strategy_id = $1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM strategy_positions WHERE strategy_id = $1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let wallet_ids: Vec<i16> = strategy.wallet_ids.iter().map(|&id| id as i16).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_wallets (strategy_id, wallet_id)
+            SELECT $1, wallet_id FROM UNNEST($2::SMALLINT[]) AS wallet_id
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&strategy.id)
+        .bind(wallet_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let (position_ids, sides): (Vec<String>, Vec<String>) = strategy
+            .longs
+            .iter()
+            .chain(strategy.shorts.iter())
+            .map(|p| (p.id.clone(), p.side.to_string()))
+            .unzip();
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_positions (strategy_id, position_id, side)
+            SELECT $1, position_id, side FROM UNNEST($2::TEXT[], $3::TEXT[]) AS p(position_id, side)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&strategy.id)
+        .bind(position_ids)
+        .bind(sides)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         Ok(())
     }
 
     /// Get a strategy by ID (without loading full position details)
-    pub async fn get_strategy_metadata(
+    async fn get_strategy_metadata(
         &self,
         id: &str,
     ) -> Result<Option<StrategyMetadata>, TradingError> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, 
-                   close_at, closed_at, realized_pnl, long_position_ids, short_position_ids
-            FROM strategies WHERE id = $1
-            "#,
-        )
-        .bind(id)
-        .fetch_optional(&self.pool)
-        .await?;
+        let query = format!("SELECT {} FROM strategies s WHERE s.id = $1", STRATEGY_COLUMNS);
+        let row = sqlx::query(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
 
         match row {
-            Some(row) => Ok(Some(StrategyMetadata {
-                id: row.try_get("id")?,
-                token_symbol: row.try_get("token_symbol")?,
-                wallet_ids: parse_wallet_ids(row.try_get("wallet_ids")?),
-                longs_size: Decimal::from_str(row.try_get("longs_size")?).unwrap(),
-                shorts_size: Decimal::from_str(row.try_get("shorts_size")?).unwrap(),
-                status: StrategyStatus::from_str(row.try_get("status")?).unwrap(),
-                opened_at: row.try_get("opened_at")?,
-                updated_at: row.try_get("updated_at")?,
-                close_at: row.try_get("close_at")?,
-                closed_at: row.try_get("closed_at")?,
-                realized_pnl: row
-                    .try_get::<Option<String>, _>("realized_pnl")?
-                    .and_then(|s| Decimal::from_str(&s).ok()),
-                long_position_ids: parse_position_ids(row.try_get("long_position_ids")?),
-                short_position_ids: parse_position_ids(row.try_get("short_position_ids")?),
-            })),
+            Some(row) => Ok(Some(strategy_from_row(&row)?)),
             None => Ok(None),
         }
     }
 
     /// Get all active strategies (Running or Closing status)
-    pub async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, 
-                   close_at, closed_at, realized_pnl, long_position_ids, short_position_ids
-            FROM strategies WHERE status IN ('RUNNING', 'CLOSING') ORDER BY close_at ASC
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await?;
-
-        let strategies = rows
-            .iter()
-            .map(|row| {
-                Ok(StrategyMetadata {
-                    id: row.try_get("id")?,
-                    token_symbol: row.try_get("token_symbol")?,
-                    wallet_ids: parse_wallet_ids(row.try_get("wallet_ids")?),
-                    longs_size: Decimal::from_str(row.try_get("longs_size")?).unwrap(),
-                    shorts_size: Decimal::from_str(row.try_get("shorts_size")?).unwrap(),
-                    status: StrategyStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    updated_at: row.try_get("updated_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    long_position_ids: parse_position_ids(row.try_get("long_position_ids")?),
-                    short_position_ids: parse_position_ids(row.try_get("short_position_ids")?),
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(strategies)
+    async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("WHERE s.status IN ('RUNNING', 'CLOSING') ORDER BY s.close_at ASC").await
     }
 
     /// Get strategies that should be closed (close_at <= now and status = Running)
-    pub async fn get_strategies_to_close(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
-        let now = Utc::now();
-        let rows = sqlx::query(
-            r#"
-            SELECT id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, 
-                   close_at, closed_at, realized_pnl, long_position_ids, short_position_ids
-            FROM strategies WHERE status = 'RUNNING' AND close_at <= $1 ORDER BY close_at ASC
-            "#,
-        )
-        .bind(now)
-        .fetch_all(&self.pool)
-        .await?;
+    async fn get_strategies_to_close(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!(
+            "SELECT {} FROM strategies s WHERE s.status = 'RUNNING' AND s.close_at <= $1 ORDER BY s.close_at ASC",
+            STRATEGY_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(Utc::now())
+            .fetch_all(&self.pool)
+            .await?;
 
-        let strategies = rows
-            .iter()
-            .map(|row| {
-                Ok(StrategyMetadata {
-                    id: row.try_get("id")?,
-                    token_symbol: row.try_get("token_symbol")?,
-                    wallet_ids: parse_wallet_ids(row.try_get("wallet_ids")?),
-                    longs_size: Decimal::from_str(row.try_get("longs_size")?).unwrap(),
-                    shorts_size: Decimal::from_str(row.try_get("shorts_size")?).unwrap(),
-                    status: StrategyStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    updated_at: row.try_get("updated_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    long_position_ids: parse_position_ids(row.try_get("long_position_ids")?),
-                    short_position_ids: parse_position_ids(row.try_get("short_position_ids")?),
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(strategies)
-    }
-
-    /// Update strategy status and related fields
-    pub async fn update_strategy_status(
+        rows.iter().map(strategy_from_row).collect()
+    }
+
+    /// Move a strategy to a new status if it still has the expected status and `updated_at`
+    async fn update_strategy_status(
         &self,
         id: &str,
-        status: StrategyStatus,
+        from: StrategyStatus,
+        updated_at: DateTime<Utc>,
+        to: StrategyStatus,
         closed_at: Option<DateTime<Utc>>,
         realized_pnl: Option<Decimal>,
-    ) -> Result<(), TradingError> {
-        let updated_at = Utc::now();
+    ) -> Result<DateTime<Utc>, TradingError> {
+        check_transition(id, from, to)?;
+        let now = status_timestamp();
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             UPDATE strategies 
             SET status = $1, closed_at = $2, realized_pnl = $3, updated_at = $4
-            WHERE id = $5
+            WHERE id = $5 AND status = $6 AND updated_at = $7
             "#,
         )
-        .bind(status.to_string())
+        .bind(to.to_string())
         .bind(closed_at)
-        .bind(realized_pnl.map(|pnl| pnl.to_string()))
-        .bind(updated_at)
+        .bind(realized_pnl)
+        .bind(now)
         .bind(id)
+        .bind(from.to_string())
+        .bind(updated_at)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        if result.rows_affected() == 0 {
+            let found = self.get_strategy_metadata(id).await?.map(|s| s.status);
+            return Err(stale_status_error(id, from, updated_at, found));
+        }
+
+        Ok(now)
     }
 
     /// Get all strategies
-    pub async fn get_all_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, 
-                   close_at, closed_at, realized_pnl, long_position_ids, short_position_ids
-            FROM strategies ORDER BY opened_at DESC
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await?;
-
-        let strategies = rows
-            .iter()
-            .map(|row| {
-                Ok(StrategyMetadata {
-                    id: row.try_get("id")?,
-                    token_symbol: row.try_get("token_symbol")?,
-                    wallet_ids: parse_wallet_ids(row.try_get("wallet_ids")?),
-                    longs_size: Decimal::from_str(row.try_get("longs_size")?).unwrap(),
-                    shorts_size: Decimal::from_str(row.try_get("shorts_size")?).unwrap(),
-                    status: StrategyStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    updated_at: row.try_get("updated_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    long_position_ids: parse_position_ids(row.try_get("long_position_ids")?),
-                    short_position_ids: parse_position_ids(row.try_get("short_position_ids")?),
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(strategies)
+    async fn get_all_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("ORDER BY s.opened_at DESC").await
     }
 
     /// Get all failed strategies (status = Failed)
-    pub async fn get_failed_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
-        let rows = sqlx::query(
+    async fn get_failed_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        self.fetch_strategies("WHERE s.status = 'FAILED' ORDER BY s.updated_at DESC").await
+    }
+
+    /// Get every strategy that traded with a wallet, newest first
+    async fn get_strategies_for_wallet(&self, wallet_id: u8) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let query = format!(
             r#"
-            SELECT id, token_symbol, wallet_ids, longs_size, shorts_size, status, opened_at, updated_at, 
-                   close_at, closed_at, realized_pnl, long_position_ids, short_position_ids
-            FROM strategies WHERE status = 'FAILED' ORDER BY updated_at DESC
+            SELECT {} FROM strategies s
+            WHERE EXISTS (SELECT 1 FROM strategy_wallets w WHERE w.strategy_id = s.id AND w.wallet_id = $1)
+            ORDER BY s.opened_at DESC
             "#,
+            STRATEGY_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(wallet_id as i16)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(strategy_from_row).collect()
+    }
+
+    /// Sum of the realized PnL of strategies closed since `since`
+    async fn get_realized_pnl_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError> {
+        let pnl: Option<Decimal> = sqlx::query_scalar(
+            "SELECT SUM(realized_pnl) FROM strategies WHERE closed_at >= $1",
         )
-        .fetch_all(&self.pool)
+        .bind(since)
+        .fetch_one(&self.pool)
         .await?;
 
-        let strategies = rows
-            .iter()
-            .map(|row| {
-                Ok(StrategyMetadata {
-                    id: row.try_get("id")?,
-                    token_symbol: row.try_get("token_symbol")?,
-                    wallet_ids: parse_wallet_ids(row.try_get("wallet_ids")?),
-                    longs_size: Decimal::from_str(row.try_get("longs_size")?).unwrap(),
-                    shorts_size: Decimal::from_str(row.try_get("shorts_size")?).unwrap(),
-                    status: StrategyStatus::from_str(row.try_get("status")?).unwrap(),
-                    opened_at: row.try_get("opened_at")?,
-                    updated_at: row.try_get("updated_at")?,
-                    close_at: row.try_get("close_at")?,
-                    closed_at: row.try_get("closed_at")?,
-                    realized_pnl: row
-                        .try_get::<Option<String>, _>("realized_pnl")?
-                        .and_then(|s| Decimal::from_str(&s).ok()),
-                    long_position_ids: parse_position_ids(row.try_get("long_position_ids")?),
-                    short_position_ids: parse_position_ids(row.try_get("short_position_ids")?),
-                })
-            })
-            .collect::<Result<Vec<_>, TradingError>>()?;
-
-        Ok(strategies)
+        Ok(pnl.unwrap_or_default())
     }
 }
 
@@ -367,9 +272,75 @@ pub struct StrategyMetadata {
     pub realized_pnl: Option<Decimal>,
     pub long_position_ids: Vec<String>,
     pub short_position_ids: Vec<String>,
+    pub strategy_type: StrategyType,
+    pub hedge_symbols: Vec<String>,
+    pub hedge_ratio: Option<Decimal>,
+    pub estimated_cost_usdc: Option<Decimal>,
+    pub estimated_cost_bps: Option<Decimal>,
+    pub entry_slippage_bps: Option<Decimal>,
+    pub max_leg_slippage_bps: Option<Decimal>,
+}
+
+impl From<&TradingStrategy> for StrategyMetadata {
+    fn from(strategy: &TradingStrategy) -> Self {
+        let mut wallet_ids = strategy.wallet_ids.clone();
+        wallet_ids.sort_unstable();
+        wallet_ids.dedup();
+
+        let mut long_position_ids: Vec<String> = strategy.longs.iter().map(|p| p.id.clone()).collect();
+        long_position_ids.sort();
+        let mut short_position_ids: Vec<String> = strategy.shorts.iter().map(|p| p.id.clone()).collect();
+        short_position_ids.sort();
+
+        Self {
+            id: strategy.id.clone(),
+            token_symbol: strategy.token_symbol.clone(),
+            wallet_ids,
+            longs_size: strategy.longs_size,
+            shorts_size: strategy.shorts_size,
+            status: strategy.status,
+            opened_at: strategy.opened_at,
+            updated_at: strategy.updated_at,
+            close_at: strategy.close_at,
+            closed_at: strategy.closed_at,
+            realized_pnl: strategy.realized_pnl,
+            long_position_ids,
+            short_position_ids,
+            strategy_type: strategy.strategy_type,
+            hedge_symbols: strategy.hedge_symbols.clone(),
+            hedge_ratio: strategy.hedge_ratio,
+            estimated_cost_usdc: strategy.estimated_cost_usdc,
+            estimated_cost_bps: strategy.estimated_cost_bps,
+            entry_slippage_bps: strategy.entry_slippage_bps,
+            max_leg_slippage_bps: strategy.max_leg_slippage_bps,
+        }
+    }
 }
 
 impl StrategyMetadata {
+    /// Symbols of every market the strategy trades (long token first)
+    pub fn market_symbols(&self) -> Vec<String> {
+        std::iter::once(self.token_symbol.clone())
+            .chain(self.hedge_symbols.iter().cloned())
+            .collect()
+    }
+
+    /// Tokens of every market the strategy trades on Lighter
+    pub fn market_tokens(&self) -> Result<Vec<Token>, TradingError> {
+        self.market_symbols()
+            .iter()
+            .map(|symbol| Token::from_symbol_string(Exchange::Lighter, symbol))
+            .collect()
+    }
+
+    /// Human-readable markets, e.g. `ETH` or `ETH / DYDX+TON` for correlated pairs
+    pub fn market_label(&self) -> String {
+        match self.strategy_type {
+            StrategyType::DeltaNeutral => self.token_symbol.clone(),
+            StrategyType::CorrelatedPair => format!("{} / {}", self.token_symbol, self.hedge_symbols.join("+")),
+        }
+    }
+
     /// Check if the strategy should be closed based on current time
     pub fn should_close(&self) -> bool {
         Utc::now() >= self.close_at && self.status == StrategyStatus::Running
@@ -386,7 +357,7 @@ impl StrategyMetadata {
 
     /// Check the current status of the strategy and determine if any action is needed
     /// Returns a tuple of (current_status, needs_action, action_reason)
-    pub fn check_strategy_status(&self, current_time: DateTime<Utc>) -> (StrategyStatus, bool, Option<String>) {
+    pub fn check_strategy_status(&self, current_time: DateTime<Utc>, limits: &RiskLimits) -> (StrategyStatus, bool, Option<String>) {
         match self.status {
             StrategyStatus::Running => {
                 // Check if strategy should be closed due to time
@@ -399,12 +370,12 @@ impl StrategyMetadata {
                 }
                 
                 // Check if strategy has been open for too long (safety check)
-                let max_duration = chrono::Duration::hours(24); // 24 hours max
+                let max_duration = chrono::Duration::hours(limits.max_lifetime_hours as i64);
                 if current_time - self.opened_at > max_duration {
                     return (
                         StrategyStatus::Running,
                         true,
-                        Some("Strategy exceeded maximum duration (24 hours)".to_string())
+                        Some(format!("Strategy exceeded maximum duration ({} hours)", limits.max_lifetime_hours))
                     );
                 }
                 
@@ -414,7 +385,7 @@ impl StrategyMetadata {
             
             StrategyStatus::Closing => {
                 // If strategy is already closing, check if it's taking too long
-                let closing_timeout = chrono::Duration::minutes(10); // 10 minutes max for closing
+                let closing_timeout = chrono::Duration::minutes(limits.closing_timeout_minutes as i64);
                 if let Some(started_closing_at) = self.updated_at.checked_add_signed(closing_timeout) {
                     if current_time > started_closing_at {
                         return (
@@ -428,7 +399,7 @@ impl StrategyMetadata {
                 (StrategyStatus::Closing, false, None)
             }
             
-            StrategyStatus::Closed | StrategyStatus::Failed => {
+            StrategyStatus::Closed | StrategyStatus::Failed | StrategyStatus::Killed => {
                 // No action needed for completed strategies
                 (self.status, false, None)
             }
@@ -440,9 +411,9 @@ impl StrategyMetadata {
         matches!(self.status, StrategyStatus::Running | StrategyStatus::Closing)
     }
 
-    /// Check if the strategy is completed (closed or failed)
+    /// Check if the strategy is completed (closed, failed or killed)
     pub fn is_completed(&self) -> bool {
-        matches!(self.status, StrategyStatus::Closed | StrategyStatus::Failed)
+        matches!(self.status, StrategyStatus::Closed | StrategyStatus::Failed | StrategyStatus::Killed)
     }
 
     /// Check if the strategy should be force-closed due to emergency conditions
@@ -577,14 +548,19 @@ impl StrategyMetadata {
     }
 
     /// Get strategy efficiency ratio (min(longs, shorts) / max(longs, shorts))
-    /// Higher values indicate better balance between long and short sides
+    /// Higher values indicate better balance between long and short sides.
+    /// Correlated pairs compare shorts against the beta-weighted long notional.
     pub fn efficiency_ratio(&self) -> Decimal {
         if self.longs_size.is_zero() && self.shorts_size.is_zero() {
             return Decimal::ONE;
         }
         
-        let min_size = self.longs_size.min(self.shorts_size);
-        let max_size = self.longs_size.max(self.shorts_size);
+        let target_longs = match self.hedge_ratio {
+            Some(ratio) if self.strategy_type == StrategyType::CorrelatedPair => self.longs_size * ratio,
+            _ => self.longs_size,
+        };
+        let min_size = target_longs.min(self.shorts_size);
+        let max_size = target_longs.max(self.shorts_size);
         
         min_size / max_size
     }
@@ -596,10 +572,11 @@ impl StrategyMetadata {
         let efficiency = self.efficiency_ratio();
         
         format!(
-            "{} [{}] | Token: {} | Wallets: {} | Positions: {}L/{}S | Size: {:.2}/{:.2} | Close in: {} | Age: {}min | Eff: {:.1}%",
+            "{} [{} {}] | Token: {} | Wallets: {} | Positions: {}L/{}S | Size: {:.2}/{:.2} | Close in: {} | Age: {}min | Eff: {:.1}%",
             self.id,
+            self.strategy_type,
             self.status,
-            self.token_symbol,
+            self.market_label(),
             self.wallet_count(),
             self.long_position_count(),
             self.short_position_count(),
@@ -613,20 +590,65 @@ impl StrategyMetadata {
 }
 
 
-/// Parse comma-separated position IDs
-fn parse_position_ids(ids_str: &str) -> Vec<String> {
-    if ids_str.is_empty() {
-        Vec::new()
+/// Build strategy metadata from a `strategies` row
+fn strategy_from_row(row: &PgRow) -> Result<StrategyMetadata, TradingError> {
+    Ok(StrategyMetadata {
+        id: row.try_get("id")?,
+        token_symbol: row.try_get("token_symbol")?,
+        wallet_ids: row
+            .try_get::<Vec<i16>, _>("wallet_ids")?
+            .into_iter()
+            .map(|id| id as u8)
+            .collect(),
+        longs_size: row.try_get("longs_size")?,
+        shorts_size: row.try_get("shorts_size")?,
+        status: StrategyStatus::from_str(row.try_get("status")?).map_err(TradingError::InvalidInput)?,
+        opened_at: row.try_get("opened_at")?,
+        updated_at: row.try_get("updated_at")?,
+        close_at: row.try_get("close_at")?,
+        closed_at: row.try_get("closed_at")?,
+        realized_pnl: row.try_get("realized_pnl")?,
+        long_position_ids: row.try_get("long_position_ids")?,
+        short_position_ids: row.try_get("short_position_ids")?,
+        strategy_type: StrategyType::from_str(row.try_get("strategy_type")?)
+            .map_err(TradingError::InvalidInput)?,
+        hedge_symbols: parse_symbols(row.try_get("hedge_symbols")?),
+        hedge_ratio: row.try_get("hedge_ratio")?,
+        estimated_cost_usdc: row.try_get("estimated_cost")?,
+        estimated_cost_bps: row.try_get("estimated_cost_bps")?,
+        entry_slippage_bps: row.try_get("entry_slippage_bps")?,
+        max_leg_slippage_bps: row.try_get("max_leg_slippage_bps")?,
+    })
+}
+
+/// Parse comma-separated token symbols
+pub(crate) fn parse_symbols(symbols_str: &str) -> Vec<String> {
+    symbols_str.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()
+}
+
+/// Check a status update against the transition table before it is written
+pub(crate) fn check_transition(id: &str, from: StrategyStatus, to: StrategyStatus) -> Result<(), TradingError> {
+    if from.can_transition_to(to) {
+        Ok(())
     } else {
-        ids_str.split(',').map(|s| s.to_string()).collect()
+        Err(TradingError::IllegalStatusTransition { id: id.to_string(), from, to })
     }
 }
 
-/// Parse comma-separated wallet IDs
-fn parse_wallet_ids(ids_str: &str) -> Vec<u8> {
-    if ids_str.is_empty() {
-        Vec::new()
-    } else {
-        ids_str.split(',').filter_map(|s| s.parse::<u8>().ok()).collect()
+/// Error for a guarded status update that matched no row, given the strategy's current status
+pub(crate) fn stale_status_error(
+    id: &str,
+    expected: StrategyStatus,
+    updated_at: DateTime<Utc>,
+    found: Option<StrategyStatus>,
+) -> TradingError {
+    match found {
+        Some(found) => TradingError::StaleStrategyStatus { id: id.to_string(), expected, updated_at, found },
+        None => TradingError::StrategyNotFound(id.to_string()),
     }
 }
+
+/// Timestamp of a status update, truncated to the microseconds Postgres keeps so it can guard the next one
+pub(crate) fn status_timestamp() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
@@ -0,0 +1,248 @@
+#![allow(unused)]
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use crate::error::TradingError;
+use crate::model::exchange::Exchange;
+use crate::model::position::{Position, PositionStatus};
+use crate::storage::storage_event::{StrategyEvent, StrategyEventKind};
+use crate::storage::storage_lease::WalletLease;
+use crate::storage::storage_order::{OrderRecord, OrderStatus};
+use crate::storage::storage_risk::{BreakerState, CollateralTransfer, EquitySnapshot, KillSwitchState};
+use crate::storage::storage_snapshot::{SnapshotDelta, WalletBalanceSnapshot, WalletPointsSnapshot};
+use crate::storage::storage_strategy::StrategyMetadata;
+use crate::trader::strategy::{StrategyStatus, TradingStrategy};
+
+/// Storage for the positions (legs) of strategies
+#[async_trait]
+pub trait PositionStore: Send + Sync {
+    /// Save or update a position
+    async fn save_position(&self, position: &Position) -> Result<(), TradingError>;
+
+    /// Get a position by ID
+    async fn get_position(&self, id: &str) -> Result<Option<Position>, TradingError>;
+
+    /// Get all positions, newest first
+    async fn get_all_positions(&self) -> Result<Vec<Position>, TradingError>;
+
+    /// Get positions by exchange, newest first
+    async fn get_positions_by_exchange(&self, exchange: Exchange) -> Result<Vec<Position>, TradingError>;
+
+    /// Get active positions (Open or Closing status), newest first
+    async fn get_active_positions(&self) -> Result<Vec<Position>, TradingError>;
+
+    /// Get every position of a strategy, oldest first
+    async fn get_positions_by_strategy(&self, strategy_id: &str) -> Result<Vec<Position>, TradingError>;
+
+    /// Set the status of every position of a strategy that is not closed yet
+    async fn update_strategy_positions_status(&self, strategy_id: &str, status: PositionStatus) -> Result<(), TradingError>;
+
+    /// Update position status and related fields
+    async fn update_position_status(
+        &self,
+        id: &str,
+        status: PositionStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<(), TradingError>;
+
+    /// Delete a position by ID (use sparingly - prefer status updates)
+    async fn delete_position(&self, id: &str) -> Result<(), TradingError>;
+}
+
+/// Storage for strategies, with their wallets and position IDs
+#[async_trait]
+pub trait StrategyStore: Send + Sync {
+    /// Save or update a strategy with its wallets and positions (which must already be saved)
+    async fn save_strategy(&self, strategy: &TradingStrategy) -> Result<(), TradingError>;
+
+    /// Get a strategy by ID (without loading full position details)
+    async fn get_strategy_metadata(&self, id: &str) -> Result<Option<StrategyMetadata>, TradingError>;
+
+    /// Get all active strategies (Running or Closing status), soonest close first
+    async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError>;
+
+    /// Get strategies that should be closed (close_at <= now and status = Running)
+    async fn get_strategies_to_close(&self) -> Result<Vec<StrategyMetadata>, TradingError>;
+
+    /// Move a strategy from status `from`, last updated at `updated_at`, to `to`
+    ///
+    /// The update only applies if the strategy still has that status and timestamp, so two
+    /// passes (or instances) cannot both act on the same state.
+    ///
+    /// # Returns
+    /// * `Ok(DateTime)` - The strategy's new `updated_at`
+    /// * `Err(TradingError::IllegalStatusTransition)` - If `to` cannot follow `from`
+    /// * `Err(TradingError::StaleStrategyStatus)` - If the strategy changed since it was read
+    /// * `Err(TradingError::StrategyNotFound)` - If there is no such strategy
+    async fn update_strategy_status(
+        &self,
+        id: &str,
+        from: StrategyStatus,
+        updated_at: DateTime<Utc>,
+        to: StrategyStatus,
+        closed_at: Option<DateTime<Utc>>,
+        realized_pnl: Option<Decimal>,
+    ) -> Result<DateTime<Utc>, TradingError>;
+
+    /// Get all strategies, newest first
+    async fn get_all_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError>;
+
+    /// Get all failed strategies, most recently updated first
+    async fn get_failed_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError>;
+
+    /// Get every strategy that traded with a wallet, newest first
+    async fn get_strategies_for_wallet(&self, wallet_id: u8) -> Result<Vec<StrategyMetadata>, TradingError>;
+
+    /// Sum of the realized PnL of strategies closed since `since`
+    async fn get_realized_pnl_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError>;
+}
+
+/// Append-only journal of strategy events
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Append an event to the journal
+    async fn record_event(&self, event: &StrategyEvent) -> Result<(), TradingError>;
+
+    /// Get every event of a strategy, oldest first
+    async fn get_strategy_events(&self, strategy_id: &str) -> Result<Vec<StrategyEvent>, TradingError>;
+
+    /// Get events of one kind recorded since `since`, newest first
+    async fn get_events_by_kind(&self, kind: StrategyEventKind, since: DateTime<Utc>) -> Result<Vec<StrategyEvent>, TradingError>;
+}
+
+/// Ledger of exchange orders
+#[async_trait]
+pub trait OrderStore: std::fmt::Debug + Send + Sync {
+    /// Record an order accepted by the exchange (no-op if its tx hash is already recorded)
+    async fn record_order(&self, order: &OrderRecord) -> Result<(), TradingError>;
+
+    /// Update an order once its tx hash was looked up
+    async fn update_order_status(
+        &self,
+        tx_hash: &str,
+        status: OrderStatus,
+        tx_status: Option<i64>,
+        executed_at: Option<i64>,
+    ) -> Result<(), TradingError>;
+
+    /// Set the fill price and fee of an order
+    async fn update_order_fill(&self, tx_hash: &str, fill_price: Option<Decimal>, fee_usdc: Option<Decimal>) -> Result<(), TradingError>;
+
+    /// Get an order by its tx hash
+    async fn get_order(&self, tx_hash: &str) -> Result<Option<OrderRecord>, TradingError>;
+
+    /// Get a wallet's orders submitted since `since`, oldest first
+    async fn get_wallet_orders(&self, wallet_id: u8, since: DateTime<Utc>) -> Result<Vec<OrderRecord>, TradingError>;
+
+    /// Total fees paid on orders submitted since `since` that were not reduce-only
+    ///
+    /// Exit fees are already netted out of each strategy's realized PnL.
+    async fn get_entry_fees_since(&self, since: DateTime<Utc>) -> Result<Decimal, TradingError>;
+}
+
+/// Time series of wallet balances and Lighter points
+#[async_trait]
+pub trait SnapshotStore: Send + Sync {
+    /// Record a wallet's balances
+    async fn save_balance_snapshot(&self, snapshot: &WalletBalanceSnapshot) -> Result<(), TradingError>;
+
+    /// Record a wallet's Lighter points
+    async fn save_points_snapshot(&self, snapshot: &WalletPointsSnapshot) -> Result<(), TradingError>;
+
+    /// Get a wallet's balance snapshots taken between `since` and `until`, oldest first
+    async fn get_balance_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletBalanceSnapshot>, TradingError>;
+
+    /// Get a wallet's points snapshots taken between `since` and `until`, oldest first
+    async fn get_points_history(
+        &self,
+        wallet_id: u8,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<WalletPointsSnapshot>, TradingError>;
+
+    /// Balance change of every wallet between its first and last snapshot in `[since, until]`
+    async fn get_balance_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletBalanceSnapshot>>, TradingError>;
+
+    /// Points earned by every wallet between its first and last snapshot in `[since, until]`
+    async fn get_points_deltas(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotDelta<WalletPointsSnapshot>>, TradingError>;
+}
+
+/// Equity snapshots, circuit breaker and kill switch state and collateral transfers
+#[async_trait]
+pub trait RiskStore: Send + Sync {
+    /// Save an equity snapshot and drop snapshots older than `retain_since`
+    async fn save_equity_snapshot(&self, snapshot: &EquitySnapshot, retain_since: DateTime<Utc>) -> Result<(), TradingError>;
+
+    /// Get equity snapshots taken since the given time, oldest first
+    async fn get_equity_snapshots_since(&self, since: DateTime<Utc>) -> Result<Vec<EquitySnapshot>, TradingError>;
+
+    /// Get the kill switch state
+    async fn get_kill_switch_state(&self) -> Result<KillSwitchState, TradingError>;
+
+    /// Activate the kill switch (no-op if it is already active)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was activated by this call
+    /// * `Ok(false)` - The kill switch was already active
+    async fn trigger_kill_switch(&self, reason: &str, source: &str) -> Result<bool, TradingError>;
+
+    /// Clear the kill switch
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The kill switch was active and is now cleared
+    /// * `Ok(false)` - The kill switch was not active
+    async fn clear_kill_switch(&self) -> Result<bool, TradingError>;
+
+    /// Record a collateral transfer, successful or not
+    async fn save_collateral_transfer(&self, transfer: &CollateralTransfer) -> Result<(), TradingError>;
+
+    /// Get the circuit breaker state
+    async fn get_breaker_state(&self) -> Result<BreakerState, TradingError>;
+
+    /// Trip the circuit breaker (no-op if it is already tripped)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The breaker was tripped by this call
+    /// * `Ok(false)` - The breaker was already tripped
+    async fn trip_breaker(&self, reason: &str) -> Result<bool, TradingError>;
+
+    /// Reset the circuit breaker after manual review
+    async fn reset_breaker(&self) -> Result<(), TradingError>;
+}
+
+/// Leases that let a single bot instance at a time trade each wallet
+#[async_trait]
+pub trait LeaseStore: Send + Sync {
+    /// Claim or renew `holder`'s leases on wallets until `expires_at`
+    ///
+    /// A wallet is only claimed if it has no lease, its lease expired by `now`, or `holder`
+    /// already holds it.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - Wallets among `wallet_ids` now held by `holder`, sorted
+    async fn claim_wallets(
+        &self,
+        holder: &str,
+        wallet_ids: &[u8],
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Vec<u8>, TradingError>;
+
+    /// Get every wallet lease, expired or not, by wallet
+    async fn get_wallet_leases(&self) -> Result<Vec<WalletLease>, TradingError>;
+}
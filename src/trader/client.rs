@@ -10,39 +10,118 @@
 /// - Telegram alerts for strategy failures
 
 use crate::{
-    alert::telegram::TelegramAlerter, error::TradingError, model::{
-		position::{Position, PositionSide, PositionStatus},
+    alert::telegram::TelegramAlerter, config::{AppConfig, LimitAction, ReconcileAction, RiskLimits}, error::TradingError, model::{
+		position::{Position, PositionStatus},
 		token::Token, Exchange,
-	}, perp::{lighter::client::LighterClient, PerpExchange}, storage::{
-		storage_position::PositionStorage,
-		storage_strategy::{StrategyMetadata, StrategyStorage},
-	}, trader::{strategy::{StrategyStatus, TradingStrategy}, wallet::{Wallet, WalletTradingClient}}
+	}, perp::{lighter::{client::LighterClient, models::LighterPosition}, PerpExchange}, storage::{
+		storage_event::{StrategyEvent, StrategyEventKind},
+		storage_risk::KillSwitchState,
+		storage_order::OrderRecord,
+		storage_snapshot::{WalletBalanceSnapshot, WalletPointsSnapshot},
+		storage_strategy::StrategyMetadata,
+		traits::{EventStore, OrderStore, PositionStore, SnapshotStore, StrategyStore},
+		Storage,
+	}, risk::{circuit_breaker::CircuitBreaker, kill_switch::KillSwitch, portfolio::{PortfolioExposure, PortfolioRisk}, rescue::{CollateralRescue, Donor}}, trader::{estimator::{CostEstimate, CostEstimator}, execution::{ExecutionScheduler, LegExecution, SlippageBreach}, lease::WalletLeases, pair::{lighter_symbols, CorrelatedPair}, reconcile::{find_mismatches, ExpectedPosition, LivePosition, MismatchKind, ReconciliationReport}, strategy::{StrategyStatus, StrategyType, TradingStrategy, WalletAllocation}, wallet::{Wallet, WalletTradingClient}}
 };
 
+use std::{collections::{HashMap, HashSet}, sync::{Arc, Mutex}};
+
 use chrono::{DateTime, Duration, Utc};
-use rust_decimal::{Decimal};
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal};
 use rand::{seq::SliceRandom, Rng};
-use rust_decimal_macros::dec;
-use sqlx::PgPool;
 use tokio::time::{sleep, Duration as TokioDuration};
 use colored::*;
+use serde_json::json;
 
 
 const MAX_ATTEMPTS: usize = 10;
 
+/// Why the monitor acts on a strategy
+enum MonitorSignal {
+    /// Close time, maximum lifetime or closing timeout reached (reason)
+    Scheduled(String),
+    /// A position is within the emergency liquidation distance (percent)
+    Liquidation(Decimal),
+    /// A correlated pair's spread PnL hit its stop (percent)
+    SpreadStop(Decimal),
+    /// Collateral ratio or PnL divergence limit crossed (description)
+    LimitBreach(String),
+    /// A position is close to liquidation, but not yet critical (percent)
+    Warning(Decimal),
+}
+
+/// A strategy's live legs when its close started, keyed by wallet and market symbol
+struct ClosingLegs {
+    started_at: DateTime<Utc>,
+    positions: HashMap<(u8, String), LighterPosition>,
+}
+
+/// Average price and fees of the reduce-only orders that closed a leg
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LegExit {
+    price: Decimal,
+    fees_usdc: Decimal,
+}
+
+impl LegExit {
+    /// Exit of a leg from its reduce-only orders, weighted by base amount
+    /// 
+    /// Returns `None` if there is no order or any order has no known fill price.
+    fn from_orders(orders: &[OrderRecord]) -> Option<Self> {
+        let (mut cost, mut size, mut fees_usdc) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        for order in orders {
+            let base_amount = Decimal::from(order.base_amount);
+            cost += order.fill_price? * base_amount;
+            size += base_amount;
+            fees_usdc += order.fee_usdc.unwrap_or_default();
+        }
+
+        if size <= Decimal::ZERO {
+            return None;
+        }
+
+        Some(Self { price: cost / size, fees_usdc })
+    }
+
+    /// PnL of closing the whole of `leg` at this exit, net of the exit fees
+    fn leg_pnl(&self, leg: &LighterPosition) -> Decimal {
+        let price_move = if leg.sign > 0 { self.price - leg.avg_entry_price } else { leg.avg_entry_price - self.price };
+        price_move * leg.position.abs() - self.fees_usdc
+    }
+}
+
 pub struct TraderClient {
     pub wallets: Vec<Wallet>,
-    position_storage: PositionStorage,
-    strategy_storage: StrategyStorage,
+    position_storage: Arc<dyn PositionStore>,
+    strategy_storage: Arc<dyn StrategyStore>,
+    event_storage: Arc<dyn EventStore>,
+    order_storage: Arc<dyn OrderStore>,
+    snapshot_storage: Arc<dyn SnapshotStore>,
     wallet_trading_clients: Vec<WalletTradingClient>,   
+    execution_scheduler: ExecutionScheduler,
+    cost_estimator: CostEstimator,
+    portfolio_risk: PortfolioRisk,
+    circuit_breaker: CircuitBreaker,
+    collateral_rescue: CollateralRescue,
+    kill_switch: KillSwitch,
+    wallet_leases: WalletLeases,
+    config: AppConfig,
+    /// Last risk limit action and number of reductions, per strategy ID
+    limit_actions: Mutex<HashMap<String, (DateTime<Utc>, u32)>>,
+    /// Time of the last repeated slippage breach alert
+    slippage_alerted_at: Mutex<Option<DateTime<Utc>>>,
+    /// Number of collateral rescues, per strategy ID
+    rescues: Mutex<HashMap<String, u32>>,
+    /// Closest liquidation distance journaled as a warning, per strategy ID
+    liquidation_warnings: Mutex<HashMap<String, Decimal>>,
 }
 
 impl TraderClient {
-    /// Create a new trader client with specified wallets and database connection
+    /// Create a new trader client with specified wallets and storage
     /// 
     /// # Arguments
     /// * `wallet_ids` - Vector of wallet IDs to load (must have at least 3 wallets)
-    /// * `pool` - PostgreSQL connection pool for data persistence
+    /// * `storage` - Stores for positions, strategies, ledgers and risk state
     /// 
     /// # Returns
     /// * `Ok(Self)` - Successfully initialized trader client
@@ -51,8 +130,7 @@ impl TraderClient {
     /// # Errors
     /// * `TradingError::InvalidInput` - If no wallet IDs provided or fewer than 3 wallets
     /// * `TradingError::WalletError` - If wallet configuration files cannot be loaded
-    /// * `TradingError::StorageError` - If database connections cannot be established
-    pub async fn new(wallet_ids: Vec<u8>, pool: PgPool) -> Result<Self, TradingError> {
+    pub async fn new(wallet_ids: Vec<u8>, storage: Storage) -> Result<Self, TradingError> {
         if wallet_ids.is_empty() {
             return Err(TradingError::InvalidInput("No wallet IDs provided".into()));
         }
@@ -71,18 +149,44 @@ impl TraderClient {
             Err(e) => return Err(e),
         };
 
-        let position_storage = PositionStorage::new(pool.clone()).await?;
-        let strategy_storage = StrategyStorage::new(pool).await?;
-
         let wallet_trading_clients = futures::future::try_join_all(
-            wallets.iter().cloned().map(WalletTradingClient::new)
+            wallets.iter().cloned().map(|wallet| WalletTradingClient::new(wallet, storage.orders.clone()))
+        ).await?;
+
+        let config = AppConfig::load()?;
+        let kill_switch = KillSwitch::new(config.kill_switch.clone(), storage.risk.clone()).await?;
+        let wallet_leases = WalletLeases::new(config.leases.clone(), wallets.iter().map(|w| w.id).collect(), storage.leases);
+        let execution_scheduler = ExecutionScheduler::new(config.execution.clone(), kill_switch.clone(), wallet_leases.clone());
+        let cost_estimator = CostEstimator::new(config.cost.clone());
+        let portfolio_risk = PortfolioRisk::new(config.exposure.clone());
+        let circuit_breaker = CircuitBreaker::new(
+            config.circuit_breaker.clone(),
+            storage.risk.clone(),
+            storage.strategies.clone(),
+            storage.orders.clone(),
         ).await?;
+        let collateral_rescue = CollateralRescue::new(config.rescue.clone(), storage.risk.clone()).await?;
 
         Ok(Self { 
             wallets, 
-            position_storage,
-            strategy_storage,
+            position_storage: storage.positions,
+            strategy_storage: storage.strategies,
+            event_storage: storage.events,
+            order_storage: storage.orders.clone(),
+            snapshot_storage: storage.snapshots,
             wallet_trading_clients,
+            execution_scheduler,
+            cost_estimator,
+            portfolio_risk,
+            circuit_breaker,
+            collateral_rescue,
+            kill_switch,
+            wallet_leases,
+            config,
+            limit_actions: Mutex::new(HashMap::new()),
+            slippage_alerted_at: Mutex::new(None),
+            rescues: Mutex::new(HashMap::new()),
+            liquidation_warnings: Mutex::new(HashMap::new()),
         })
     }
 
@@ -101,216 +205,518 @@ impl TraderClient {
         let strategies = self.get_active_strategies().await?;
 
         for strategy in strategies {
-            self.set_strategy_status(&strategy.id, 
-                StrategyStatus::Closing, 
-                None, None)
-            .await?;
+            self.close_strategy_now(&strategy).await?;
+        }
 
+        Ok(())
+    }
 
-            match self.close_positions_on_lighter_for_wallets_group(&strategy.wallet_ids).await {
-                Ok(_) => {
-                    info!("✅ Strategy {} closed successfully", strategy.id);
-                    self.set_strategy_status(&strategy.id, StrategyStatus::Closed, Some(Utc::now()), None).await?;
-                }
-                Err(e) => {
-                    error!("{}", format!("❌ Failed to close all positions: {} | YOU NEED TO CLOSE THE POSITIONS MANUALLY!", e).on_red());
-                    self.set_strategy_status(&strategy.id, StrategyStatus::Failed, Some(Utc::now()), None).await?;
+    /// Close a strategy's positions and mark it Closed, or Failed (with an alert) if they cannot be closed
+    /// 
+    /// If another pass or instance moves the strategy on first, its status is left as found.
+    /// 
+    /// # Returns
+    /// * `Ok(true)` - The strategy's positions were closed
+    /// * `Ok(false)` - Closing failed and the strategy was marked Failed, or it was skipped
+    /// * `Err(TradingError)` - If its status cannot be updated
+    async fn close_strategy_now(&self, strategy: &StrategyMetadata) -> Result<bool, TradingError> {
+        let mut strategy = strategy.clone();
+        let legs = match self.begin_strategy_close(&mut strategy).await {
+            Ok(legs) => legs,
+            Err(e) if e.is_strategy_conflict() => {
+                warn!("⚠️ Skipping close of strategy {}: {}", strategy.id, e);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
 
-                    let alerter = TelegramAlerter::new();                    
-                    if let Err(e) = alerter.send_strategy_error_alert(&strategy, &e).await {
-                        error!("{}", format!("❌ Failed to send strategy error alert: {}", e).on_red());
-                    }
-                }
+        let result = self.close_strategy_positions_on_lighter(&strategy).await;
+        match &result {
+            Ok(_) => info!("✅ Strategy {} closed successfully", strategy.id),
+            Err(e) => error!("{}", format!("❌ Failed to close all positions: {} | YOU NEED TO CLOSE THE POSITIONS MANUALLY!", e).on_red()),
+        }
+
+        match self.finish_strategy_close(&mut strategy, &legs, result.is_ok()).await {
+            Ok(_) => {}
+            Err(e) if e.is_strategy_conflict() => {
+                warn!("⚠️ Strategy {} changed while closing, status left as found: {}", strategy.id, e);
             }
+            Err(e) => return Err(e),
         }
 
-        Ok(())
+        if let Err(e) = &result {
+            let alerter = TelegramAlerter::new();
+            if let Err(e) = alerter.send_strategy_error_alert(&strategy, e).await {
+                error!("{}", format!("❌ Failed to send strategy error alert: {}", e).on_red());
+            }
+        }
+
+        Ok(result.is_ok())
+    }
+
+    /// Shortest risk check interval among the given strategies' markets
+    pub fn risk_check_interval(&self, strategies: &[StrategyMetadata]) -> TokioDuration {
+        let seconds = strategies
+            .iter()
+            .map(|s| self.config.risk.for_symbols(&s.market_symbols()).check_interval_seconds)
+            .min()
+            .unwrap_or(self.config.risk.check_interval_seconds);
+
+        TokioDuration::from_secs(seconds)
     }
 
-    /// Monitor strategies and automatically close them when their close time is reached
-    /// or if any position is within 15% of liquidation
+    /// Run one monitoring pass over active strategies
     /// 
-    /// This method provides automated strategy lifecycle management by:
-    /// 1. Continuously monitoring liquidation levels for ALL active strategies
-    /// 2. Closing strategies immediately if liquidation risk is detected in ANY strategy
-    /// 3. Closing strategies when their scheduled close time arrives
-    /// 4. Handling failures with appropriate status updates and alerts
+    /// Checks every strategy in parallel for its scheduled close time, liquidation risk
+    /// and (for correlated pairs) the spread stop, and closes those that need it.
+    /// Thresholds come from `[risk]`, using the most conservative limits of the
+    /// strategy's markets.
     /// 
     /// # Arguments
-    /// * `strategies` - Vector of strategy metadata to monitor and close
+    /// * `strategies` - Strategies to check
     /// 
     /// # Returns
-    /// * `Ok(())` - All strategies were processed (successfully or marked as failed)
-    /// * `Err(TradingError)` - If input validation fails or critical errors occur
-    /// 
-    /// # Behavior
-    /// - ALL strategies are monitored for liquidation continuously
-    /// - Liquidation checks are performed every 30 seconds across all strategies
-    /// - Strategies are closed at their scheduled times or immediately on liquidation risk
-    /// - Failed closures are marked and alerted but don't stop other strategies
-    /// - Local time display is adjusted to UTC+8 for user convenience
-    pub async fn monitor_and_close_strategies(
-        &self,
-        strategies: Vec<StrategyMetadata>,
-    ) -> Result<(), TradingError> {
-        if strategies.is_empty() {
-            return Err(TradingError::InvalidInput("No strategies provided".into()));
-        }
-
-        // Display strategies being monitored
-        info!("📋 Monitoring {} strategies:", strategies.len());
-        for strategy in &strategies {
-            let close_at_local = strategy.close_at + chrono::Duration::hours(8);
-            info!("🎯 Strategy {} | Token: {} | Wallets: {:?} | Close at: {}", 
-                strategy.id, strategy.token_symbol, strategy.wallet_ids, close_at_local.format("%H:%M"));
-        }
+    /// * `Ok(Vec<StrategyMetadata>)` - Strategies closed (or marked failed) during this pass
+    /// * `Err(TradingError)` - If a status update fails
+    pub async fn monitor_pass(&self, strategies: &[StrategyMetadata]) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let spread_stop_pct = Decimal::from_f64(self.config.pair.spread_stop_pct).unwrap_or(Decimal::ZERO);
 
-        // Track which strategies are still active
-        let mut active_strategies: Vec<StrategyMetadata> = strategies.clone();
+        let mut closed = Vec::new();
+        let mut strategies_to_close = Vec::new();
         
-        const CHECK_INTERVAL_SECS: u64 = 15;
-        const LIQUIDATION_THRESHOLD: Decimal = dec!(13.0);
+        // Create futures for all liquidation checks
+        let check_futures = strategies.iter().map(|strategy| {
+            let strategy_clone = strategy.clone();
+            let limits = self.config.risk.for_symbols(&strategy.market_symbols());
+            async move {
+                // Check close time and maximum lifetime, and retry closes stuck in CLOSING
+                let (status, needs_action, reason) = strategy_clone.check_strategy_status(Utc::now(), &limits);
+                if needs_action {
+                    let reason = reason.unwrap_or_else(|| "Strategy needs to be closed".to_string());
+                    return (strategy_clone, limits, Some(MonitorSignal::Scheduled(reason)));
+                }
 
-        // Main monitoring loop - continues until all strategies are closed
-        while !active_strategies.is_empty() {            
-            let mut strategies_to_close = Vec::new();
-            
-            // Create futures for all liquidation checks
-            let check_futures = active_strategies.iter().map(|strategy| {
-                let strategy_clone = strategy.clone();
-                async move {
-                    // Check if it's time to close this strategy normally
-                    if Utc::now() >= strategy_clone.close_at {
-                        return (strategy_clone, Some((Decimal::ZERO, false, true))); // (percentage, is_emergency, is_scheduled)
-                    }
-                    
-                    // Check liquidation levels
-                    match self.check_strategy_liquidation_levels(&strategy_clone).await {
-                        Ok(Some(min_percentage)) => {
-                            (strategy_clone, Some((min_percentage, min_percentage < LIQUIDATION_THRESHOLD, false)))
-                        }
-                        Ok(None) => {
-                            (strategy_clone, None)
+                // Another pass (or instance) is closing this strategy
+                if status == StrategyStatus::Closing {
+                    return (strategy_clone, limits, None);
+                }
+
+                let emergency_distance = Decimal::from_f64(limits.emergency_distance_pct).unwrap_or(Decimal::ZERO);
+                let warning_distance = Decimal::from_f64(limits.warning_distance_pct).unwrap_or(Decimal::ZERO);
+
+                // Correlated pairs are not delta-neutral, so they also carry a spread stop
+                if strategy_clone.strategy_type == StrategyType::CorrelatedPair {
+                    match self.check_pair_spread(&strategy_clone).await {
+                        Ok(Some(spread_pct)) if spread_pct <= -spread_stop_pct => {
+                            return (strategy_clone, limits, Some(MonitorSignal::SpreadStop(spread_pct)));
                         }
+                        Ok(_) => {}
+                        Err(e) => warn!("⚠️ Failed to check spread for strategy {}: {}", strategy_clone.id, e),
+                    }
+                }
+                
+                // Check liquidation levels
+                let liquidation_signal = match self.check_strategy_liquidation_levels(&strategy_clone).await {
+                    Ok(Some(min_percentage)) if min_percentage < emergency_distance => {
+                        return (strategy_clone, limits, Some(MonitorSignal::Liquidation(min_percentage)));
+                    }
+                    Ok(Some(min_percentage)) if min_percentage < warning_distance => {
+                        Some(MonitorSignal::Warning(min_percentage))
+                    }
+                    Ok(_) => None,
+                    Err(e) => {
+                        warn!("⚠️ Failed to check liquidation for strategy {}: {}", strategy_clone.id, e);
+                        None
+                    }
+                };
+
+                // Check collateral ratio and PnL divergence limits
+                match self.check_risk_limits(&strategy_clone).await {
+                    Ok(Some(breach)) => (strategy_clone, limits, Some(MonitorSignal::LimitBreach(breach))),
+                    Ok(None) => (strategy_clone, limits, liquidation_signal),
+                    Err(e) => {
+                        warn!("⚠️ Failed to check risk limits for strategy {}: {}", strategy_clone.id, e);
+                        (strategy_clone, limits, liquidation_signal)
+                    }
+                }
+            }
+        });
+        
+        // Execute all checks in parallel
+        let check_results = futures::future::join_all(check_futures).await;
+        
+        // Process results
+        for (strategy, limits, result) in check_results {
+            match result {
+                Some(MonitorSignal::Scheduled(reason)) => {
+                    info!("⏰ Strategy {}: {}", strategy.id, reason);
+                    strategies_to_close.push((strategy, None)); // None = normal close
+                }
+                Some(MonitorSignal::Liquidation(min_percentage)) => {
+                    error!(
+                        "⚠️ {} Position in strategy {} is within {:.2}% of liquidation (threshold: {:.2}%)",
+                        "CRITICAL:".on_red().bold(),
+                        strategy.id,
+                        min_percentage,
+                        limits.emergency_distance_pct
+                    );
+
+                    // Top up the at-risk wallets first; close only if that is not possible
+                    let reason = format!("within {:.2}% of liquidation", min_percentage);
+                    match self.rescue_strategy(&strategy, &limits).await {
+                        Ok(true) => {}
+                        Ok(false) => strategies_to_close.push((strategy, Some(reason))), // Some = emergency close
                         Err(e) => {
-                            warn!("⚠️ Failed to check liquidation for strategy {}: {}", strategy_clone.id, e);
-                            (strategy_clone, None)
+                            error!("❌ Collateral rescue failed for strategy {}: {}", strategy.id, e);
+                            strategies_to_close.push((strategy, Some(reason)));
                         }
                     }
                 }
-            });
-            
-            // Execute all checks in parallel
-            let check_results = futures::future::join_all(check_futures).await;
-            
-            // Process results
-            for (strategy, result) in check_results {
-                if let Some((min_percentage, is_emergency, is_scheduled)) = result {
-                    if is_scheduled {
-                        info!("⏰ Strategy {} has reached its scheduled close time", strategy.id);
-                        strategies_to_close.push((strategy, false)); // false = normal close
-                    } else if is_emergency {
-                        error!(
-                            "⚠️ {} Position in strategy {} is within {:.2}% of liquidation (threshold: {:.2}%)",
-                            "CRITICAL:".on_red().bold(),
-                            strategy.id,
-                            min_percentage,
-                            LIQUIDATION_THRESHOLD
-                        );
-                        strategies_to_close.push((strategy, true)); // true = emergency close
-                    } else if min_percentage < Decimal::from(16) {
-                        warn!(
-                            "⚠️ Strategy {} has positions within {:.2}% of liquidation",
-                            strategy.id, min_percentage
-                        );
+                Some(MonitorSignal::SpreadStop(spread_pct)) => {
+                    error!(
+                        "⚠️ {} Pair {} ({}) spread PnL is {:.2}% (stop: -{:.2}%)",
+                        "CRITICAL:".on_red().bold(),
+                        strategy.id,
+                        strategy.market_label(),
+                        spread_pct,
+                        spread_stop_pct
+                    );
+                    strategies_to_close.push((strategy, Some(format!("spread PnL {:.2}%", spread_pct))));
+                }
+                Some(MonitorSignal::LimitBreach(breach)) => {
+                    warn!("⚠️ Strategy {} crossed a risk limit: {}", strategy.id, breach);
+                    if self.apply_limit_action(&strategy, &breach).await {
+                        strategies_to_close.push((strategy, Some(breach)));
+                    }
+                }
+                Some(MonitorSignal::Warning(min_percentage)) => {
+                    warn!(
+                        "⚠️ Strategy {} has positions within {:.2}% of liquidation",
+                        strategy.id, min_percentage
+                    );
+
+                    // Journal a warning only when the distance reaches a new low
+                    let is_new_low = {
+                        let mut warnings = self.liquidation_warnings.lock().unwrap_or_else(|e| e.into_inner());
+                        let lowest = warnings.entry(strategy.id.clone()).or_insert(Decimal::MAX);
+                        let is_new_low = min_percentage < *lowest;
+                        *lowest = (*lowest).min(min_percentage);
+                        is_new_low
+                    };
+                    if is_new_low {
+                        self.record_event(StrategyEvent::new(
+                            &strategy.id,
+                            StrategyEventKind::LiquidationWarning,
+                            json!({ "distance_pct": min_percentage, "warning_pct": limits.warning_distance_pct }),
+                        ))
+                        .await;
                     }
                 }
+                None => {}
+            }
+        }
+        
+        // Close strategies that need closing
+        for (mut strategy, emergency_reason) in strategies_to_close {
+            let is_emergency = emergency_reason.is_some();
+            if let Some(reason) = emergency_reason {
+                warn!("🚨 Initiating EMERGENCY close for strategy {}", strategy.id);
+                self.record_event(StrategyEvent::new(&strategy.id, StrategyEventKind::EmergencyClose, json!({ "reason": reason })))
+                    .await;
+            } else {
+                info!("🔄 Closing strategy {} ({}) - scheduled time reached", strategy.id, strategy.token_symbol);
             }
             
-            // Close strategies that need closing
-            for (strategy, is_emergency) in strategies_to_close {
-                if is_emergency {
-                    warn!("🚨 Initiating EMERGENCY close for strategy {} due to liquidation risk", strategy.id);
-                } else {
-                    info!("🔄 Closing strategy {} ({}) - scheduled time reached", strategy.id, strategy.token_symbol);
+            // Update strategy and position statuses to Closing, unless another pass or instance got there first
+            let legs = match self.begin_strategy_close(&mut strategy).await {
+                Ok(legs) => legs,
+                Err(e) if e.is_strategy_conflict() => {
+                    warn!("⚠️ Skipping close of strategy {}: {}", strategy.id, e);
+                    continue;
                 }
-                
-                // Update strategy status to Closing
-                self
-                    .set_strategy_status(&strategy.id, StrategyStatus::Closing, None, None)
-                    .await?;
-
-                let mut has_failures = false;
-                match self.close_positions_on_lighter_for_wallets_group(&strategy.wallet_ids).await {
-                    Ok(_) => {
-                        if is_emergency {
-                            info!("✅ Emergency close successful for strategy {}", strategy.id);
-                        } else {
-                            info!("✅ All positions closed successfully for strategy {}", strategy.id);
-                        }
-                    }
-                    Err(e) => {
-                        error!("❌ Failed to close positions for strategy {}: {}", strategy.id, e);
-                        has_failures = true;
-                        let strategy_clone = strategy.clone();
-
-                        tokio::spawn(async move {
-                            let alerter = TelegramAlerter::new();
-                            
-                            if let Err(e) = alerter.send_strategy_error_alert(&strategy_clone, &e).await {
-                                error!("{}", format!("❌ Failed to send strategy error alert: {}", e).on_red());
-                            }
-                        });
+                Err(e) => return Err(e),
+            };
+
+            // Scheduled closes unwind gradually; emergencies flatten immediately
+            if !is_emergency {
+                self.unwind_strategy_in_slices(&strategy).await;
+
+                // Its new owner resumes the close of a strategy left in CLOSING
+                if !self.wallet_leases.owns(&strategy.wallet_ids) {
+                    warn!("🔓 Lost the lease on strategy {} while unwinding, leaving its close to the new owner", strategy.id);
+                    continue;
+                }
+            }
+
+            let mut has_failures = false;
+            match self.close_strategy_positions_on_lighter(&strategy).await {
+                Ok(_) => {
+                    if is_emergency {
+                        info!("✅ Emergency close successful for strategy {}", strategy.id);
+                    } else {
+                        info!("✅ All positions closed successfully for strategy {}", strategy.id);
                     }
                 }
+                Err(e) => {
+                    error!("❌ Failed to close positions for strategy {}: {}", strategy.id, e);
+                    has_failures = true;
+                    let strategy_clone = strategy.clone();
+
+                    tokio::spawn(async move {
+                        let alerter = TelegramAlerter::new();
+                        
+                        if let Err(e) = alerter.send_strategy_error_alert(&strategy_clone, &e).await {
+                            error!("{}", format!("❌ Failed to send strategy error alert: {}", e).on_red());
+                        }
+                    });
+                }
+            }
+
+            // Settle positions and update strategy status based on whether there were failures
+            let final_status = match self.finish_strategy_close(&mut strategy, &legs, !has_failures).await {
+                Ok(status) => status,
+                Err(e) if e.is_strategy_conflict() => {
+                    warn!("⚠️ Strategy {} changed while closing, status left as found: {}", strategy.id, e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            self.limit_actions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&strategy.id);
+            self.rescues
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&strategy.id);
+            self.liquidation_warnings
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&strategy.id);
+
+            if is_emergency {
+                info!("💰 Strategy {} EMERGENCY closed | Status: {}", strategy.id, final_status);
+            } else {
+                info!("💰 Strategy {} completed | Status: {}", strategy.id, final_status);
+            }
+            
+            closed.push(strategy);
+        }
+
+        Ok(closed)
+    }
+
+    /// Combined unrealized PnL of a correlated pair as a percentage of its long notional
+    /// 
+    /// Both legs move together when the pair is correlated, so the combined PnL tracks
+    /// the spread between the long token and its basket.
+    /// 
+    /// # Arguments
+    /// * `strategy` - Correlated pair strategy
+    /// 
+    /// # Returns
+    /// * `Ok(Some(Decimal))` - Spread PnL in percent (negative when the spread moves against the pair)
+    /// * `Ok(None)` - The strategy has no long notional to compare against
+    /// * `Err(TradingError)` - Markets could not be resolved or positions fetched
+    async fn check_pair_spread(&self, strategy: &StrategyMetadata) -> Result<Option<Decimal>, TradingError> {
+        if strategy.longs_size.is_zero() {
+            return Ok(None);
+        }
 
-                // Update strategy status based on whether there were failures
-                let final_status = if has_failures { StrategyStatus::Failed } else { StrategyStatus::Closed };
+        let market_ids: Vec<i32> = strategy
+            .market_tokens()?
+            .iter()
+            .map(|t| t.get_market_index(Exchange::Lighter))
+            .collect();
 
-                self
-                    .set_strategy_status(&strategy.id, final_status, Some(Utc::now()), None)
-                    .await?;
+        let mut combined_pnl = Decimal::ZERO;
+        for &wallet_id in &strategy.wallet_ids {
+            let client = self.get_lighter_client(wallet_id)?;
+            let positions = client.get_active_positions().await?;
 
-                if is_emergency {
-                    info!("💰 Strategy {} EMERGENCY closed | Status: {}", strategy.id, final_status);
+            combined_pnl += positions
+                .iter()
+                .filter(|p| market_ids.contains(&p.market_id))
+                .map(|p| p.unrealized_pnl)
+                .sum::<Decimal>();
+        }
+
+        Ok(Some(combined_pnl / strategy.longs_size * Decimal::from(100)))
+    }
+
+    /// Check a strategy against `min_collateral_ratio` (for each of its wallets) and
+    /// `max_pnl_divergence` (|long PnL + short PnL| relative to the live long notional)
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy to check
+    /// 
+    /// # Returns
+    /// * `Ok(Some(String))` - Description of the first limit crossed
+    /// * `Ok(None)` - The strategy is within its limits
+    /// * `Err(TradingError)` - Error fetching accounts or positions
+    async fn check_risk_limits(&self, strategy: &StrategyMetadata) -> Result<Option<String>, TradingError> {
+        let min_ratio = Decimal::from_f64(self.config.trading.min_collateral_ratio).unwrap_or(Decimal::ZERO);
+        let max_divergence = Decimal::from_f64(self.config.trading.max_pnl_divergence).unwrap_or(Decimal::ONE);
+        let market_ids: Vec<i32> = strategy
+            .market_tokens()?
+            .iter()
+            .map(|t| t.get_market_index(Exchange::Lighter))
+            .collect();
+
+        let (mut long_pnl, mut short_pnl, mut long_notional) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+
+        for &wallet_id in &strategy.wallet_ids {
+            let client = self.get_lighter_client(wallet_id)?;
+
+            if let Some(ratio) = client.get_collateral_ratio().await? {
+                if ratio < min_ratio {
+                    return Ok(Some(format!(
+                        "Wallet #{} collateral ratio is {:.2} (minimum: {:.2})",
+                        wallet_id, ratio, min_ratio
+                    )));
+                }
+            }
+
+            let positions = client.get_active_positions().await?;
+            for position in positions.iter().filter(|p| market_ids.contains(&p.market_id)) {
+                if position.sign > 0 {
+                    long_pnl += position.unrealized_pnl;
+                    long_notional += position.position_value;
                 } else {
-                    info!("💰 Strategy {} completed | Status: {}", strategy.id, final_status);
+                    short_pnl += position.unrealized_pnl;
                 }
-                
-                // Remove from active strategies
-                active_strategies.retain(|s| s.id != strategy.id);
             }
-            
-            // If there are still active strategies, sleep before next check
-            if !active_strategies.is_empty() {
-                // Calculate time until next scheduled close
-                let next_close = active_strategies
-                    .iter()
-                    .map(|s| s.close_at)
-                    .min()
-                    .unwrap_or_else(|| Utc::now() + Duration::seconds(CHECK_INTERVAL_SECS as i64));
-                
-                let time_until_next = (next_close - Utc::now())
-                    .to_std()
-                    .unwrap_or(TokioDuration::from_secs(CHECK_INTERVAL_SECS));
-                
-                // Sleep for the shorter of: time until next close or check interval
-                let sleep_duration = time_until_next.min(TokioDuration::from_secs(CHECK_INTERVAL_SECS));
-                
-                if sleep_duration.as_secs() > 0 {
-                    sleep(sleep_duration).await;
+        }
+
+        if long_notional.is_zero() {
+            return Ok(None);
+        }
+
+        let divergence = (long_pnl + short_pnl).abs() / long_notional;
+        if divergence > max_divergence {
+            return Ok(Some(format!(
+                "Long PnL {:.2} and short PnL {:.2} diverge by {:.2}% of notional (maximum: {:.2}%)",
+                long_pnl,
+                short_pnl,
+                divergence * Decimal::ONE_HUNDRED,
+                max_divergence * Decimal::ONE_HUNDRED
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Apply `risk.limit_action` to a strategy that crossed a risk limit
+    /// 
+    /// Alerts and reductions are throttled by `limit_action_cooldown_seconds`; a strategy
+    /// still over its limits after `max_reductions` reductions is closed.
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy over its limits
+    /// * `breach` - Description of the limit crossed
+    /// 
+    /// # Returns
+    /// * `true` if the strategy must be closed now
+    async fn apply_limit_action(&self, strategy: &StrategyMetadata, breach: &str) -> bool {
+        let risk = &self.config.risk;
+        let now = Utc::now();
+        let cooldown = Duration::seconds(risk.limit_action_cooldown_seconds as i64);
+
+        let reductions = {
+            let actions = self.limit_actions.lock().unwrap_or_else(|e| e.into_inner());
+            match actions.get(&strategy.id) {
+                Some((last_action_at, _)) if risk.limit_action != LimitAction::Close && now - *last_action_at < cooldown => {
+                    return false;
+                }
+                Some((_, reductions)) => *reductions,
+                None => 0,
+            }
+        };
+
+        let action = match risk.limit_action {
+            LimitAction::Reduce if reductions >= risk.max_reductions => LimitAction::Close,
+            action => action,
+        };
+
+        let description = match action {
+            LimitAction::Alert => "Alert only, positions unchanged".to_string(),
+            LimitAction::Close => format!("Closing strategy (limit action: {})", risk.limit_action),
+            LimitAction::Reduce => {
+                let fraction = Decimal::from_f64(risk.reduce_fraction).unwrap_or(Decimal::ZERO);
+                match self.reduce_strategy(strategy, fraction).await {
+                    Ok(()) => format!(
+                        "Reduced every leg by {:.0}% ({}/{})",
+                        fraction * Decimal::ONE_HUNDRED,
+                        reductions + 1,
+                        risk.max_reductions
+                    ),
+                    Err(e) => format!("Reduce failed: {}", e),
                 }
             }
+        };
+
+        warn!("🛡️ Strategy {} | {} | {}", strategy.id, breach, description);
+
+        self.limit_actions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(strategy.id.clone(), (now, reductions + u32::from(action == LimitAction::Reduce)));
+
+        let alerter = TelegramAlerter::new();
+        if let Err(e) = alerter.send_risk_limit_alert(strategy, breach, &description).await {
+            error!("Failed to send Telegram alert: {}", e);
         }
-        
-        info!("🎊 All strategies have been closed!");
+
+        action == LimitAction::Close
+    }
+
+    /// Close the same share of every leg of a strategy, keeping it balanced
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy to reduce
+    /// * `fraction` - Share of each position to close (0 < fraction < 1)
+    /// 
+    /// # Returns
+    /// * `Ok(())` - Every leg was reduced
+    /// * `Err(TradingError)` - The first leg that could not be reduced
+    async fn reduce_strategy(&self, strategy: &StrategyMetadata, fraction: Decimal) -> Result<(), TradingError> {
+        let market_ids: Vec<i32> = strategy
+            .market_tokens()?
+            .iter()
+            .map(|t| t.get_market_index(Exchange::Lighter))
+            .collect();
+
+        // Wallets reduce in parallel, one order at a time per wallet
+        let reduce_futures = strategy.wallet_ids.iter().map(|&wallet_id| {
+            let market_ids = &market_ids;
+            async move {
+                let client = self.get_lighter_client(wallet_id)?;
+                let positions = client.get_active_positions().await?;
+
+                for position in positions.iter().filter(|p| market_ids.contains(&p.market_id)) {
+                    client.reduce_position_slice(position, fraction).await?;
+                }
+
+                Ok::<(), TradingError>(())
+            }
+        });
+
+        futures::future::join_all(reduce_futures)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
         Ok(())
     }
 
     /// Check liquidation levels for all positions in a strategy
     /// 
+    /// Only the strategy's own markets are considered, since its wallets may also
+    /// hold positions for other strategies on other tokens.
+    /// 
     /// # Arguments
-    /// * `strategy` - Strategy metadata containing wallet IDs
+    /// * `strategy` - Strategy metadata containing wallet IDs and token
     /// 
     /// # Returns
     /// * `Ok(Some(Decimal))` - Minimum percentage to liquidation across all positions
@@ -321,6 +727,10 @@ impl TraderClient {
         strategy: &StrategyMetadata,
     ) -> Result<Option<Decimal>, TradingError> {
         let mut min_percentage: Option<Decimal> = None;
+        let market_ids: Option<Vec<i32>> = strategy
+            .market_tokens()
+            .ok()
+            .map(|tokens| tokens.iter().map(|t| t.get_market_index(Exchange::Lighter)).collect());
 
         for &wallet_id in &strategy.wallet_ids {
             let client = match self.get_lighter_client(wallet_id) {
@@ -340,6 +750,10 @@ impl TraderClient {
             };
 
             for position in positions {
+                if market_ids.as_ref().is_some_and(|ids| !ids.contains(&position.market_id)) {
+                    continue;
+                }
+
                 let percentage = position.get_percentage_to_liquidation();
 
                 if percentage == Decimal::ZERO {    
@@ -359,63 +773,49 @@ impl TraderClient {
         Ok(min_percentage)
     }
 
-
-    /// Execute a market-neutral farming strategy on Lighter exchange with wallet grouping
+    /// Open strategies for a single cohort of idle wallets
+    /// 
+    /// Used by the cohort scheduler, which leases the wallets and reconciles their positions beforehand.
     /// 
-    /// Enhanced version that automatically groups wallets when more than 3 wallets are available.
-    /// Each group trades a different random token for better diversification.
+    /// # Arguments
+    /// * `wallet_ids` - Wallets of the cohort (at least 3)
+    /// * `max_strategies` - Maximum number of strategies to open (one per token)
     /// 
-    /// # Enhanced Features
-    /// - **Wallet Grouping**: Automatically creates groups of 3-5 wallets when >3 wallets available
-    /// - **Multi-Token Diversification**: Each group trades a different random token
-    /// - **Group-based Strategies**: Separate strategy tracking for each wallet group
-    /// - **Pre-trade preview**: Displays strategy details before execution
-    /// - **Partial failure handling**: Automatically rolls back if any position fails
-    /// - **Detailed logging**: Comprehensive progress and status reporting
-    /// - **Time flexibility**: Duration specified in minutes for precise control
+    /// # Returns
+    /// * `Ok(Vec<TradingStrategy>)` - Strategies opened for the cohort
+    /// * `Err(TradingError)` - If balances cannot be fetched or every token failed
+    pub async fn open_cohort(&self, wallet_ids: &[u8], max_strategies: usize) -> Result<Vec<TradingStrategy>, TradingError> {
+        self.open_wallet_groups(vec![wallet_ids.to_vec()], max_strategies).await
+    }
+
+    /// Open strategies for the given wallet groups, one strategy per (group, token)
     /// 
     /// # Arguments
-    /// * `duration_minutes` - Duration in minutes for position lifetime
+    /// * `wallet_groups` - Wallet IDs of every group
+    /// * `max_strategies` - Stop once this many strategies are open
     /// 
     /// # Returns
-    /// * `Ok(TradingStrategy)` - Complete strategy with all opened positions (first group's strategy)
-    /// * `Err(TradingError)` - If any position fails (with automatic rollback)
-    /// 
-    /// # Grouping Logic
-    /// - If ≤3 wallets: Single group with one random token
-    /// - If >3 wallets: Random groups of 3-5 wallets, each with different random tokens
-    /// - Groups are created randomly for better distribution
-    /// - Each group executes as an independent market-neutral strategy
-    /// 
-    /// # Safety Mechanisms
-    /// - All-or-nothing position opening with automatic rollback on failures
-    /// - Balance verification before trading
-    /// - Strategy preview for user confirmation (commented out but available)
-    /// - Comprehensive error handling and cleanup
-    pub async fn farm_points_on_lighter_from_multiple_wallets(&self) -> Result<Vec<TradingStrategy>, TradingError> {
-        info!("🎯 Starting Lighter farming strategy with {} wallets", self.wallets.len());
+    /// * `Ok(Vec<TradingStrategy>)` - Strategies opened successfully
+    /// * `Err(TradingError)` - If balances cannot be fetched or every group failed
+    async fn open_wallet_groups(&self, wallet_groups: Vec<Vec<u8>>, max_strategies: usize) -> Result<Vec<TradingStrategy>, TradingError> {
+        if let Some(state) = self.kill_switch.active_state().await? {
+            return Err(TradingError::KillSwitchActive(state.reason.unwrap_or_default()));
+        }
+
+        if let Some(state) = self.circuit_breaker.tripped_state().await? {
+            return Err(TradingError::CircuitBreakerTripped(state.reason.unwrap_or_default()));
+        }
+
         let mut rng = rand::thread_rng();
         let duration_minutes = rng.gen_range(120..=300);
 
-        // Handle conflicting strategies across our wallets (retry-after-close behavior)
-        self.handle_conflicting_strategies().await?;
-        // Handle conflicting positions on Lighter exchange
-        self.handle_conflicting_positions().await?;
-
-        // Step 1: Fetch USDC balances from all wallets on Lighter
-        let wallet_balances = self.fetch_wallet_balances_on_lighter().await?;
+        // Step 1: Fetch USDC balances from the groups' wallets on Lighter
+        let group_wallet_ids: Vec<u8> = wallet_groups.iter().flatten().cloned().collect();
+        let wallet_balances = self.fetch_wallet_balances_for(&group_wallet_ids).await?;
         for (id, balance) in wallet_balances.iter() {
             info!("💰 Wallet #{}: {:.2} USDC", id, balance);
         }
 
-        // Step 2: Create wallet groups based on total wallet count
-        let wallet_groups = if self.wallets.len() > 3 {
-            self.create_random_wallet_groups(3, 5)?
-        } else {
-            // Single group with all wallets
-            vec![self.wallets.iter().map(|w| w.id).collect()]
-        };
-
         info!("📊 Created {} wallet group(s) for trading:", wallet_groups.len());
         for (i, group) in wallet_groups.iter().enumerate() {
             info!("   Group {}: {:?}", i + 1, group);
@@ -423,8 +823,15 @@ impl TraderClient {
 
         // Step 3: Execute strategies for each group
         let mut all_strategies: Vec<TradingStrategy> = Vec::new();
+        let mut failed_groups: Vec<String> = Vec::new();
+        let mut last_error: Option<TradingError> = None;
 
         for (group_index, wallet_group) in wallet_groups.into_iter().enumerate() {
+            if all_strategies.len() >= max_strategies {
+                info!("🛑 Reached the limit of {} new strategies, skipping remaining groups", max_strategies);
+                break;
+            }
+
             info!("🚀 Executing strategy for group {} (wallets: {:?})", group_index + 1, wallet_group);
 
             let close_at = Utc::now() + Duration::minutes(duration_minutes + rng.gen_range(1..=5));
@@ -442,133 +849,68 @@ impl TraderClient {
                 continue;
             }
 
-            // Generate balanced allocations for this group
-            let allocations = TradingStrategy::generate_balanced_allocations(&group_balances)?;
-
-            // Select a random token for this group (different for each group)
-            let selected_token = self.select_random_token(&Exchange::Lighter)?;
-            let token_symbol = selected_token.symbol.to_string();
-            info!("🎲 Group {} selected token: {:?}", group_index + 1, selected_token.symbol);
-
-            // Create futures for all position openings in this group
-            let mut position_futures = Vec::new();
-            
-            for allocation in allocations {
-                let token = selected_token.clone();
-                let side = allocation.side;
-                let usdc_amount = allocation.usdc_amount;
-                
-                let future = async move {
-                    let lighter_client = self.get_lighter_client(allocation.wallet_id)?;
-                    let position = lighter_client
-                        .open_position(
-                            token,
-                            side,
-                            close_at,
-                            usdc_amount,
-                        )
-                        .await?;
-                    
-                    Ok::<(Position, PositionSide, u8, LighterClient), TradingError>
-                    ((position.clone(), side, allocation.wallet_id, lighter_client))
-                };
-                
-                position_futures.push(future);
-            }
-        
-            // Execute all position openings in parallel for this group
-            info!("🚀 Opening {} positions for group {}...", position_futures.len(), group_index + 1);
-            let results = futures::future::join_all(position_futures).await;
-
-            // Check all results for this group
-            let mut opened_positions = Vec::new();
-            let mut errors = Vec::new();
-
-            for result in results {
-                match result {
-                    Ok((position, side, wallet, client)) => {
-                        opened_positions.push((position, side, wallet, client));
-                    }
-                    Err(e) => {
-                        errors.push(e);
-                    }
-                }
-            }
+            // Split the group's balance across several tokens, each hedged on its own
+            let token_count = self
+                .token_count_for_group(&group_balances)
+                .min(max_strategies - all_strategies.len());
+            let selected_tokens = self.select_random_tokens(&Exchange::Lighter, token_count)?;
+            let token_share = Decimal::ONE / Decimal::from(selected_tokens.len());
+            info!(
+                "🎲 Group {} selected token(s): {:?}",
+                group_index + 1,
+                selected_tokens.iter().map(|t| t.symbol.to_string()).collect::<Vec<_>>()
+            );
 
-            // If there were any errors in this group, roll back and return error
-            if !errors.is_empty() {
-                let error = errors
-                    .into_iter()
-                    .next()
-                    .unwrap_or_else(|| TradingError::InvalidInput("Unknown error opening positions".to_string()));
+            // Markets already traded by this group, a basket must not overlap them
+            let mut used_tokens: Vec<Token> = selected_tokens.clone();
 
-                error!("❌ Position opening failed for group {}: {}", group_index + 1, error);
+            // Tokens are opened one after another so each wallet sends one order at a time
+            for selected_token in selected_tokens {
+                let token_balances: Vec<(u8, Decimal)> = group_balances
+                    .iter()
+                    .map(|(id, balance)| (*id, *balance * token_share))
+                    .collect();
 
-                if !opened_positions.is_empty() {
-                    warn!(
-                        "🔄 Rolling back {} successfully opened position(s) from group {}...",
-                        opened_positions.len(),
-                        group_index + 1
-                    );
-                    
-                    // Roll back only positions from this group
-                    self.close_positions_on_lighter_for_wallets_group(&wallet_group).await?;
-                } else {
-                    warn!("No positions succeeded in group {}, nothing to roll back.", group_index + 1);
+                let pair = self.select_correlated_pair(&wallet_group, &selected_token, &used_tokens).await;
+                if let Some(pair) = &pair {
+                    used_tokens.extend(pair.basket.iter().cloned());
                 }
 
-                return Err(error);
-            }
-        
-            // Separate results into longs and shorts for this group
-            let mut long_positions = Vec::new();
-            let mut short_positions = Vec::new();
-            
-            for (position, side, _wallet_id, _client) in opened_positions {
-                match side {
-                    crate::model::PositionSide::Long => long_positions.push(position),
-                    crate::model::PositionSide::Short => short_positions.push(position),
+                match self
+                    .open_group_strategy(group_index, &wallet_group, &selected_token, &token_balances, close_at, pair.as_ref())
+                    .await
+                {
+                    Ok(strategy) => all_strategies.push(strategy),
+                    Err(e) => {
+                        error!("❌ Group {} failed on {}: {}", group_index + 1, selected_token.symbol, e);
+                        failed_groups.push(format!("{} ({})", group_index + 1, selected_token.symbol));
+                        last_error = Some(e);
+                    }
                 }
             }
+        }
         
-            info!("✅ All positions opened successfully for group {}!", group_index + 1);
-
-            // Build the trading strategy for this group
-            let mut strategy = TradingStrategy::build_from_positions(
-                selected_token.get_symbol_string(Exchange::Lighter),
-                long_positions.clone(), 
-                short_positions.clone()
-            )?;
+        if !failed_groups.is_empty() {
+            warn!("⚠️ {} group token(s) failed and were rolled back: {:?}", failed_groups.len(), failed_groups);
+        }
 
-            // Add group information to strategy metadata
-            strategy.wallet_ids = wallet_group;
-        
-            // Link positions to strategy and save
-            let strategy_id = strategy.id.clone();
-            for position in strategy.longs.iter_mut().chain(strategy.shorts.iter_mut()) {
-                position.strategy_id = Some(strategy_id.clone());
+        if all_strategies.is_empty() {
+            if let Some(e) = last_error {
+                return Err(e);
             }
-
-            // Save strategy
-            self.strategy_storage.save_strategy(&strategy).await?;
-            all_strategies.push(strategy.clone());
-
-            info!("✅ Strategy {} for group {} executed successfully!", strategy_id, group_index + 1);
-            info!("   Token: {}", token_symbol);
-            info!("   Long positions: {} | Total size: {:.2} USDC", strategy.longs.len(), strategy.longs_size);
-            info!("   Short positions: {} | Total size: {:.2} USDC", strategy.shorts.len(), strategy.shorts_size);
         }
-        
+
         // Display summary of all executed strategies
-        info!("🎊 All {} strategy groups completed successfully!", all_strategies.len());
+        info!("🎊 {} strategy group(s) completed successfully!", all_strategies.len());
         for (i, strategy) in all_strategies.iter().enumerate() {
             let close_at_local = strategy.close_at + chrono::Duration::hours(8);
             let now_local = Utc::now() + chrono::Duration::hours(8);
             let minutes_from_now = ((close_at_local - now_local).num_minutes()).max(0);
             
             info!(
-                "   Group {}: {} | Wallets: {:?} | Close in {} minutes",
+                "   Group {}: {} {} | Wallets: {:?} | Close in {} minutes",
                 i + 1,
+                strategy.strategy_type,
                 strategy.token_symbol,
                 strategy.wallet_ids,
                 minutes_from_now
@@ -578,6 +920,549 @@ impl TraderClient {
         Ok(all_strategies)
     }
 
+    /// Open one strategy for a wallet group on a single token
+    /// 
+    /// Delta-neutral strategies are long and short `token` across wallets. Correlated
+    /// pairs are long `token` and short a beta-weighted basket of correlated tokens.
+    /// 
+    /// # Arguments
+    /// * `group_index` - Index of the wallet group (for logging)
+    /// * `wallet_group` - Wallet IDs of the group
+    /// * `token` - Token traded by this strategy (the long token of a pair)
+    /// * `balances` - Share of each wallet's balance assigned to this token
+    /// * `close_at` - Scheduled close time of the strategy
+    /// * `pair` - Short basket when opening a correlated pair
+    /// 
+    /// # Returns
+    /// * `Ok(TradingStrategy)` - Saved strategy with all opened positions
+    /// * `Err(TradingError)` - If the legs could not be opened neutrally (this strategy's markets are rolled back)
+    async fn open_group_strategy(
+        &self,
+        group_index: usize,
+        wallet_group: &[u8],
+        token: &Token,
+        balances: &Vec<(u8, Decimal)>,
+        close_at: DateTime<Utc>,
+        pair: Option<&CorrelatedPair>,
+    ) -> Result<TradingStrategy, TradingError> {
+        let token_symbol = token.get_symbol_string(Exchange::Lighter);
+
+        // Generate balanced allocations for this token
+        let mut allocations = TradingStrategy::generate_balanced_allocations(balances, &self.config.trading)?;
+
+        // Pairs short the basket instead of the same token, sized by beta
+        let hedge = match pair {
+            Some(pair) => Some(pair.hedge(&mut allocations)?),
+            None => None,
+        };
+        let leg_tokens = match &hedge {
+            Some(hedge) => {
+                info!(
+                    "🔗 {} hedged by {:?} | beta: {:.3} | correlation: {:.3}",
+                    token_symbol, lighter_symbols(&hedge.basket), hedge.hedge_ratio, pair.map_or(0.0, |p| p.correlation)
+                );
+                hedge.tokens.clone()
+            }
+            None => vec![token.clone(); allocations.len()],
+        };
+        let mut strategy_tokens = vec![token.clone()];
+        if let Some(hedge) = &hedge {
+            strategy_tokens.extend(hedge.basket.iter().cloned());
+        }
+
+        // Check the plan against the portfolio exposure limits before any order is sent
+        self.check_plan_exposure(&strategy_tokens, &leg_tokens, &mut allocations)
+            .await
+            .inspect_err(|e| warn!("📏 Skipping {} for group {}: {}", token_symbol, group_index + 1, e))?;
+
+        // Estimate the round-trip cost before any order is sent; plans above the ceiling
+        // are resized or skipped
+        let estimate = self
+            .estimate_plan_cost(wallet_group, &strategy_tokens, &leg_tokens, &mut allocations, close_at)
+            .await
+            .inspect_err(|e| warn!("💸 Skipping {} for group {}: {}", token_symbol, group_index + 1, e))?;
+
+        let preview_label = match &hedge {
+            Some(hedge) => format!("{} / {}", token_symbol, lighter_symbols(&hedge.basket).join("+")),
+            None => token_symbol.clone(),
+        };
+        TradingStrategy::display_strategy_preview(
+            "Lighter",
+            &preview_label,
+            &allocations,
+            balances,
+            (close_at - Utc::now()).num_minutes(),
+            estimate.as_ref(),
+        );
+
+        // Pair every allocation with its token and wallet's client for the execution scheduler
+        let legs = allocations
+            .into_iter()
+            .zip(leg_tokens)
+            .map(|(allocation, leg_token)| {
+                let client = self.get_lighter_client(allocation.wallet_id)?;
+                Ok((leg_token, allocation, client))
+            })
+            .collect::<Result<Vec<_>, TradingError>>()?;
+
+        // The strategy ID is fixed before execution so the plan and its orders are journaled under it
+        let strategy_id = uuid::Uuid::new_v4().to_string();
+        let planned_legs: Vec<_> = legs
+            .iter()
+            .map(|(leg_token, allocation, _)| json!({
+                "wallet_id": allocation.wallet_id,
+                "symbol": leg_token.get_symbol_string(Exchange::Lighter),
+                "side": allocation.side.to_string(),
+                "usdc_amount": allocation.usdc_amount,
+                "leverage": allocation.leverage,
+            }))
+            .collect();
+        self.record_event(StrategyEvent::new(
+            &strategy_id,
+            StrategyEventKind::Planned,
+            json!({
+                "market": preview_label,
+                "group": group_index + 1,
+                "wallet_ids": wallet_group,
+                "close_at": close_at,
+                "estimated_cost_usdc": estimate.as_ref().map(|e| e.total_usdc),
+                "legs": planned_legs,
+            }),
+        ))
+        .await;
+
+        // Every leg's market must use the plan's leverage and margin mode before any order
+        self.apply_margin_settings(&legs)
+            .await
+            .inspect_err(|e| warn!("⚙️ Skipping {} for group {}: {}", token_symbol, group_index + 1, e))?;
+
+        // Execute all legs in paired rounds of child orders
+        info!("🚀 Opening {} {} positions for group {}...", legs.len(), token_symbol, group_index + 1);
+        let mut execution = self.execution_scheduler
+            .open_group(close_at, legs)
+            .await;
+
+        self.record_slippage_breaches(&strategy_id, &execution.slippage_breaches).await;
+        self.record_leg_events(&strategy_id, &execution.legs).await;
+
+        // Legs that could not be repaired leave the strategy unbalanced: roll back its
+        // markets only, the group's other tokens and other groups keep going
+        if !execution.is_complete() {
+            let opened_count = execution.opened_count();
+            let error = execution
+                .take_error()
+                .unwrap_or_else(|| TradingError::InvalidInput("Unknown error opening positions".to_string()));
+
+            error!("❌ Position opening failed for group {} on {}: {}", group_index + 1, token_symbol, error);
+
+            let mut rolled_back = true;
+            if opened_count > 0 {
+                warn!(
+                    "🔄 Rolling back {} partially or fully opened {} position(s) from group {} (net exposure: {:.2} USDC)...",
+                    opened_count,
+                    token_symbol,
+                    group_index + 1,
+                    execution.net_exposure()
+                );
+
+                if let Err(e) = self.close_markets_on_lighter_for_wallets_group(wallet_group, &strategy_tokens).await {
+                    error!("❌ Rollback failed for group {} on {}: {}", group_index + 1, token_symbol, e);
+                    rolled_back = false;
+                }
+            } else {
+                warn!("No positions succeeded in group {} on {}, nothing to roll back.", group_index + 1, token_symbol);
+            }
+
+            self.record_event(StrategyEvent::new(
+                &strategy_id,
+                StrategyEventKind::RolledBack,
+                json!({
+                    "error": error.to_string(),
+                    "opened_legs": opened_count,
+                    "net_exposure_usdc": execution.net_exposure(),
+                    "rolled_back": rolled_back,
+                }),
+            ))
+            .await;
+
+            // Keep a record of the legs that were opened, and whether they are still live
+            let now = Utc::now();
+            for mut position in execution.legs.into_iter().filter_map(|leg| leg.position) {
+                position.status = if rolled_back { PositionStatus::Closed } else { PositionStatus::Failed };
+                position.closed_at = Some(now);
+                position.updated_at = now;
+                if let Err(e) = self.position_storage.save_position(&position).await {
+                    error!("❌ Failed to save rolled back position {}: {}", position.id, e);
+                }
+            }
+
+            let alerter = TelegramAlerter::new();
+            if let Err(e) = alerter.send_group_rollback_alert(&token_symbol, wallet_group, &error, rolled_back).await {
+                error!("Failed to send Telegram alert: {}", e);
+            }
+
+            return Err(error);
+        }
+
+        let entry_slippage_bps = execution.slippage_bps();
+        let max_leg_slippage_bps = execution.max_leg_slippage_bps();
+
+        // Separate results into longs and shorts
+        let mut long_positions = Vec::new();
+        let mut short_positions = Vec::new();
+
+        for leg in execution.legs {
+            let Some(position) = leg.position else { continue };
+
+            match leg.allocation.side {
+                crate::model::PositionSide::Long => long_positions.push(position),
+                crate::model::PositionSide::Short => short_positions.push(position),
+            }
+        }
+
+        info!("✅ All {} positions opened successfully for group {}!", token_symbol, group_index + 1);
+
+        // Build the trading strategy for this token
+        let mut strategy = TradingStrategy::build_from_positions(
+            token_symbol.clone(),
+            long_positions,
+            short_positions
+        )?;
+
+        // Add group information to strategy metadata
+        strategy.id = strategy_id.clone();
+        strategy.wallet_ids = wallet_group.to_vec();
+
+        if let Some(estimate) = &estimate {
+            strategy.estimated_cost_usdc = Some(estimate.total_usdc);
+            strategy.estimated_cost_bps = Some(estimate.cost_bps);
+        }
+        strategy.entry_slippage_bps = entry_slippage_bps;
+        strategy.max_leg_slippage_bps = max_leg_slippage_bps;
+
+        if let Some(hedge) = hedge {
+            strategy.strategy_type = StrategyType::CorrelatedPair;
+            strategy.hedge_symbols = lighter_symbols(&hedge.basket);
+            strategy.hedge_ratio = Some(hedge.hedge_ratio);
+        }
+
+        // Link positions to strategy and save
+        for position in strategy.longs.iter_mut().chain(strategy.shorts.iter_mut()) {
+            position.strategy_id = Some(strategy_id.clone());
+        }
+
+        // Positions first: the strategy's position links reference them
+        for position in strategy.longs.iter().chain(strategy.shorts.iter()) {
+            self.position_storage.save_position(position).await?;
+        }
+        self.strategy_storage.save_strategy(&strategy).await?;
+        self.record_event(StrategyEvent::new(
+            &strategy_id,
+            StrategyEventKind::StatusChange,
+            json!({ "status": strategy.status.to_string() }),
+        ))
+        .await;
+
+        info!("✅ Strategy {} for group {} executed successfully!", strategy_id, group_index + 1);
+        info!("   Type: {} | Token: {}", strategy.strategy_type, token_symbol);
+        if !strategy.hedge_symbols.is_empty() {
+            info!("   Hedge basket: {:?} | Hedge ratio: {:?}", strategy.hedge_symbols, strategy.hedge_ratio);
+        }
+        info!("   Long positions: {} | Total size: {:.2} USDC", strategy.longs.len(), strategy.longs_size);
+        info!("   Short positions: {} | Total size: {:.2} USDC", strategy.shorts.len(), strategy.shorts_size);
+
+        Ok(strategy)
+    }
+
+    /// Move USDC to a strategy's wallets that are near liquidation
+    /// 
+    /// Donors are the strategy's wallets whose positions are in profit, richest first,
+    /// then the reserve wallet. The rescue is only attempted if the donors can cover
+    /// every at-risk wallet, and at most `max_rescues` times per strategy.
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy with a leg within the emergency distance
+    /// * `limits` - Risk limits of the strategy's markets
+    /// 
+    /// # Returns
+    /// * `Ok(true)` - Every at-risk wallet was topped up, the strategy keeps running
+    /// * `Ok(false)` - No rescue was possible, the strategy must be emergency closed
+    /// * `Err(TradingError)` - If positions or balances cannot be fetched
+    async fn rescue_strategy(&self, strategy: &StrategyMetadata, limits: &RiskLimits) -> Result<bool, TradingError> {
+        if !self.collateral_rescue.is_enabled() {
+            return Ok(false);
+        }
+
+        let attempts = self.rescues.lock().unwrap_or_else(|e| e.into_inner()).get(&strategy.id).copied().unwrap_or(0);
+        if attempts >= self.collateral_rescue.max_rescues() {
+            warn!("🛟 Strategy {} was already rescued {} time(s), falling back to emergency close", strategy.id, attempts);
+            return Ok(false);
+        }
+
+        let emergency_distance = Decimal::from_f64(limits.emergency_distance_pct).unwrap_or(Decimal::ZERO);
+        let market_ids: Vec<i32> = strategy
+            .market_tokens()?
+            .iter()
+            .map(|t| t.get_market_index(Exchange::Lighter))
+            .collect();
+
+        // Collateral needed by at-risk wallets and PnL of the others on the strategy's markets
+        let mut needs: Vec<(u8, Decimal)> = Vec::new();
+        let mut winners: Vec<(u8, Decimal)> = Vec::new();
+        let mut min_distance: Option<Decimal> = None;
+
+        for &wallet_id in &strategy.wallet_ids {
+            let positions: Vec<LighterPosition> = self
+                .get_lighter_client(wallet_id)?
+                .get_active_positions()
+                .await?
+                .into_iter()
+                .filter(|p| market_ids.contains(&p.market_id))
+                .collect();
+
+            let mut need = Decimal::ZERO;
+            for position in &positions {
+                let distance = position.get_percentage_to_liquidation();
+                if distance > Decimal::ZERO && distance < emergency_distance {
+                    need += self.collateral_rescue.required_collateral(position);
+                    min_distance = Some(min_distance.map_or(distance, |d: Decimal| d.min(distance)));
+                }
+            }
+
+            if need > Decimal::ZERO {
+                needs.push((wallet_id, need));
+            } else {
+                let pnl: Decimal = positions.iter().map(|p| p.unrealized_pnl).sum();
+                if pnl > Decimal::ZERO {
+                    winners.push((wallet_id, pnl));
+                }
+            }
+        }
+
+        if needs.is_empty() {
+            return Ok(true);
+        }
+
+        winners.sort_by_key(|(_, pnl)| std::cmp::Reverse(*pnl));
+
+        let mut donors = Vec::new();
+        for (wallet_id, _) in winners {
+            let available = self.get_lighter_client(wallet_id)?.get_available_balance().await?;
+            donors.push(Donor { wallet_id, budget_usdc: self.collateral_rescue.donor_budget(available, false) });
+        }
+
+        if let Some(reserve_id) = self.collateral_rescue.reserve_wallet_id() {
+            match self.get_lighter_client(reserve_id) {
+                Ok(client) if !strategy.wallet_ids.contains(&reserve_id) => {
+                    let available = client.get_available_balance().await?;
+                    donors.push(Donor { wallet_id: reserve_id, budget_usdc: self.collateral_rescue.donor_budget(available, true) });
+                }
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ Reserve wallet #{} is not available: {}", reserve_id, e),
+            }
+        }
+
+        let Some(transfers) = self.collateral_rescue.plan(&needs, &donors) else {
+            warn!("🛟 Not enough spare collateral to rescue strategy {} (needs: {:?})", strategy.id, needs);
+            return Ok(false);
+        };
+
+        info!("🛟 Rescuing strategy {} with {} transfer(s)", strategy.id, transfers.len());
+        for transfer in &transfers {
+            let from = self.get_lighter_client(transfer.from_wallet_id)?;
+            let to_account = self.get_lighter_client(transfer.to_wallet_id)?.account_index();
+
+            if self.collateral_rescue.execute(&strategy.id, transfer, &from, to_account).await.is_err() {
+                return Ok(false);
+            }
+        }
+
+        *self.rescues.lock().unwrap_or_else(|e| e.into_inner()).entry(strategy.id.clone()).or_insert(0) += 1;
+
+        let alerter = TelegramAlerter::new();
+        if let Err(e) = alerter.send_collateral_rescue_alert(strategy, min_distance.unwrap_or_default(), &transfers).await {
+            error!("Failed to send Telegram alert: {}", e);
+        }
+
+        Ok(true)
+    }
+
+    /// Journal slippage breaches and alert once they repeat within the configured window
+    /// 
+    /// Breaches are counted from the journal, so they add up across restarts. Breaches
+    /// already reported by an alert are not counted again.
+    /// 
+    /// # Arguments
+    /// * `strategy_id` - Strategy the breaching child orders belong to
+    /// * `breaches` - Child orders of one group that exceeded `max_slippage_bps`
+    async fn record_slippage_breaches(&self, strategy_id: &str, breaches: &[SlippageBreach]) {
+        if breaches.is_empty() {
+            return;
+        }
+
+        for breach in breaches {
+            self.record_event(
+                StrategyEvent::new(strategy_id, StrategyEventKind::SlippageBreach, json!({ "slippage_bps": breach.slippage_bps }))
+                    .with_leg(breach.wallet_id, &breach.symbol),
+            )
+            .await;
+        }
+
+        let now = Utc::now();
+        let window = Duration::minutes(self.config.execution.slippage_alert_window_minutes as i64);
+        let alerted_at = *self.slippage_alerted_at.lock().unwrap_or_else(|e| e.into_inner());
+        let since = alerted_at.map_or(now - window, |at| at.max(now - window));
+
+        let count = match self.event_storage.get_events_by_kind(StrategyEventKind::SlippageBreach, since).await {
+            Ok(events) => events.len(),
+            Err(e) => {
+                error!("❌ Failed to count recent slippage breaches: {}", e);
+                return;
+            }
+        };
+        if count < self.config.execution.slippage_alert_count {
+            return;
+        }
+        *self.slippage_alerted_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(now);
+
+        warn!("⚠️ {} slippage breaches in the last {} minutes", count, window.num_minutes());
+
+        let alerter = TelegramAlerter::new();
+        if let Err(e) = alerter.send_slippage_alert(count, window.num_minutes(), breaches).await {
+            error!("Failed to send Telegram alert: {}", e);
+        }
+    }
+
+    /// Set the leverage and margin mode of every leg's market on its wallet, then verify them
+    /// 
+    /// Wallets are configured concurrently, and each wallet's markets one after the other
+    /// so their transactions do not race for the same nonce. A wallet trading the same
+    /// market on several legs uses the highest leverage among them.
+    /// 
+    /// # Arguments
+    /// * `legs` - Token, allocation and client of every leg
+    /// 
+    /// # Returns
+    /// * `Ok(())` - Every market is configured
+    /// * `Err(TradingError)` - If a market could not be configured or verified
+    async fn apply_margin_settings(&self, legs: &[(Token, WalletAllocation, LighterClient)]) -> Result<(), TradingError> {
+        let margin = &self.config.margin;
+
+        let mut wallets: HashMap<u8, (LighterClient, Vec<(Token, f64)>)> = HashMap::new();
+        for (token, allocation, client) in legs {
+            let (_, markets) = wallets
+                .entry(allocation.wallet_id)
+                .or_insert_with(|| (client.clone(), Vec::new()));
+
+            match markets.iter_mut().find(|(t, _)| t == token) {
+                Some((_, leverage)) => *leverage = leverage.max(allocation.leverage),
+                None => markets.push((token.clone(), allocation.leverage)),
+            }
+        }
+
+        let updates = wallets.into_values().map(|(client, markets)| async move {
+            for (token, leverage) in markets {
+                let mode = margin.mode_for(&token.get_symbol_string(Exchange::Lighter));
+                client.ensure_margin_settings(&token, leverage, mode, margin.verify_attempts).await?;
+            }
+            Ok::<(), TradingError>(())
+        });
+
+        futures::future::try_join_all(updates).await?;
+        Ok(())
+    }
+
+    /// Check a plan against the portfolio exposure limits, scaling it down or rejecting it
+    /// 
+    /// # Arguments
+    /// * `strategy_tokens` - Every market traded by the plan
+    /// * `leg_tokens` - Token traded by each allocation
+    /// * `allocations` - Planned legs, scaled down in place when a limit binds
+    /// 
+    /// # Returns
+    /// * `Ok(())` - The (possibly scaled) plan is within every limit
+    /// * `Err(TradingError)` - If exposure cannot be fetched or the plan is rejected
+    async fn check_plan_exposure(
+        &self,
+        strategy_tokens: &[Token],
+        leg_tokens: &[Token],
+        allocations: &mut [WalletAllocation],
+    ) -> Result<(), TradingError> {
+        let clients = self
+            .wallets
+            .iter()
+            .map(|w| Ok((w.id, self.get_lighter_client(w.id)?)))
+            .collect::<Result<Vec<_>, TradingError>>()?;
+
+        let exposure = PortfolioExposure::fetch(&clients).await?;
+
+        let mut open_interest = HashMap::new();
+        if let Some((_, client)) = clients.first() {
+            for token in strategy_tokens {
+                match client.get_market_stats(token).await {
+                    Ok(stats) => {
+                        open_interest.insert(token.get_symbol_string(Exchange::Lighter), stats.open_interest_usdc());
+                    }
+                    Err(e) => warn!("⚠️ Could not fetch open interest for {}: {}", token.symbol, e),
+                }
+            }
+        }
+
+        self.portfolio_risk.check_plan(&exposure, &open_interest, leg_tokens, allocations)?;
+        Ok(())
+    }
+
+    /// Estimate the round-trip cost of a plan and enforce the configured cost ceiling
+    /// 
+    /// # Arguments
+    /// * `wallet_group` - Wallets of the plan (the first one is used for market data)
+    /// * `strategy_tokens` - Every market traded by the plan
+    /// * `leg_tokens` - Token traded by each allocation
+    /// * `allocations` - Planned legs, scaled down in place when resized
+    /// * `close_at` - Scheduled close time, used to project funding
+    /// 
+    /// # Returns
+    /// * `Ok(Some(CostEstimate))` - Estimate of the plan that will be executed
+    /// * `Ok(None)` - Estimation is disabled or market data is unavailable
+    /// * `Err(TradingError::CostCeilingExceeded)` - If the plan must be skipped
+    async fn estimate_plan_cost(
+        &self,
+        wallet_group: &[u8],
+        strategy_tokens: &[Token],
+        leg_tokens: &[Token],
+        allocations: &mut [WalletAllocation],
+        close_at: DateTime<Utc>,
+    ) -> Result<Option<CostEstimate>, TradingError> {
+        if !self.cost_estimator.is_enabled() {
+            return Ok(None);
+        }
+
+        let Some(&wallet_id) = wallet_group.first() else {
+            return Ok(None);
+        };
+        let client = self.get_lighter_client(wallet_id)?;
+
+        let markets = match self.cost_estimator.fetch_markets(&client, strategy_tokens).await {
+            Ok(markets) => markets,
+            Err(e) => {
+                warn!("⚠️ Could not estimate cost for {:?}, opening without an estimate: {}", lighter_symbols(strategy_tokens), e);
+                return Ok(None);
+            }
+        };
+
+        let duration_hours = Decimal::from((close_at - Utc::now()).num_minutes().max(0)) / Decimal::from(60);
+        let estimate = self.cost_estimator.fit_to_ceiling(
+            &markets,
+            leg_tokens,
+            allocations,
+            &self.execution_scheduler,
+            duration_hours,
+        )?;
+
+        info!("🧾 {}", estimate.to_log_string());
+        Ok(Some(estimate))
+    }
+
     /// Close all open positions on Lighter exchange across all wallets, in parallel, retrying up to 5 attempts.
     ///
     /// This method attempts to close every open position for every wallet,
@@ -593,14 +1478,7 @@ impl TraderClient {
         for attempt in 1..=MAX_ATTEMPTS {
             info!("Attempt {} to close all positions on Lighter (all wallets)...", attempt);
 
-            let close_futures = self.wallets.iter().map(|wallet| {
-                let wallet = wallet.clone();
-
-                async move {
-                    let client = LighterClient::new(&wallet).await?;
-                    client.close_all_positions().await
-                }
-            });
+            let close_futures = self.wallet_trading_clients.iter().map(|w| w.lighter_client.close_all_positions());
 
             let results = try_join_all(close_futures).await;
 
@@ -639,7 +1517,52 @@ impl TraderClient {
     /// # Returns
     /// * `Ok(())` - All positions closed successfully for the group
     /// * `Err(TradingError)` - If any close operation fails after all attempts
+    #[allow(unused)]
     pub async fn close_positions_on_lighter_for_wallets_group(&self, wallet_ids: &[u8]) -> Result<(), TradingError> {
+        self.close_wallets_group_on_lighter(wallet_ids, None, None).await
+    }
+
+    /// Close a group's positions on the given markets, leaving the wallets' other markets open.
+    ///
+    /// # Arguments
+    /// * `wallet_ids` - Wallet IDs to close positions for
+    /// * `tokens` - Tokens whose markets should be flattened
+    ///
+    /// # Returns
+    /// * `Ok(())` - The markets are flat on every wallet of the group
+    /// * `Err(TradingError)` - If any close operation fails after all attempts
+    pub async fn close_markets_on_lighter_for_wallets_group(&self, wallet_ids: &[u8], tokens: &[Token]) -> Result<(), TradingError> {
+        self.close_wallets_group_on_lighter(wallet_ids, Some(tokens), None).await
+    }
+
+    /// Close the positions belonging to a strategy.
+    ///
+    /// Only the strategy's markets are closed (its token, plus the hedge basket for
+    /// correlated pairs). Strategies whose tokens cannot be resolved fall back to
+    /// closing every position of their wallets.
+    ///
+    /// Failed attempts are journaled on the strategy.
+    ///
+    /// # Arguments
+    /// * `strategy` - Strategy metadata containing wallet IDs and token
+    pub async fn close_strategy_positions_on_lighter(&self, strategy: &StrategyMetadata) -> Result<(), TradingError> {
+        match strategy.market_tokens() {
+            Ok(tokens) => self.close_wallets_group_on_lighter(&strategy.wallet_ids, Some(&tokens), Some(&strategy.id)).await,
+            Err(e) => {
+                warn!("⚠️ {} | closing all positions of wallet group {:?}", e, strategy.wallet_ids);
+                self.close_wallets_group_on_lighter(&strategy.wallet_ids, None, Some(&strategy.id)).await
+            }
+        }
+    }
+
+    /// Close a group's positions on the given markets, or on every market when `tokens` is `None`,
+    /// retrying up to `MAX_ATTEMPTS` times. Failed attempts are journaled on `strategy_id`, if given.
+    async fn close_wallets_group_on_lighter(
+        &self,
+        wallet_ids: &[u8],
+        tokens: Option<&[Token]>,
+        strategy_id: Option<&str>,
+    ) -> Result<(), TradingError> {
         use futures::future::try_join_all;
 
         for attempt in 1..=MAX_ATTEMPTS {
@@ -649,14 +1572,20 @@ impl TraderClient {
             );
 
             let close_futures = wallet_ids.iter().map(|&wallet_id| {
-                let wallet = self.find_wallet(wallet_id);
+                let client = self.get_lighter_client(wallet_id);
                 async move {
-                    let wallet = wallet?;
-                    let client = LighterClient::new(wallet).await?;
-                    client.close_all_positions().await
+                    let client = client?;
+                    match tokens {
+                        Some(tokens) => {
+                            for token in tokens {
+                                client.close_market_position(token).await?;
+                            }
+                            Ok(())
+                        }
+                        None => client.close_all_positions().await,
+                    }
                 }
             });
-
             let results = try_join_all(close_futures).await;
 
             match results {
@@ -670,6 +1599,15 @@ impl TraderClient {
                         attempt, wallet_ids, e
                     );
 
+                    if let Some(strategy_id) = strategy_id {
+                        self.record_event(StrategyEvent::new(
+                            strategy_id,
+                            StrategyEventKind::CloseAttemptFailed,
+                            json!({ "attempt": attempt, "max_attempts": MAX_ATTEMPTS, "error": e.to_string() }),
+                        ))
+                        .await;
+                    }
+
                     if attempt < MAX_ATTEMPTS {
                         info!("Retrying in 350ms...");
                         sleep(crate::Duration::from_millis(350)).await;
@@ -689,6 +1627,41 @@ impl TraderClient {
         Ok(())
     }
 
+    /// Gradually reduce a strategy's positions before the final close.
+    ///
+    /// Uses the execution scheduler to unwind the strategy's markets in paired reduce-only
+    /// rounds. Any failure is logged and left to `close_strategy_positions_on_lighter`,
+    /// which flattens whatever remains.
+    ///
+    /// # Arguments
+    /// * `strategy` - Strategy whose positions should be unwound
+    async fn unwind_strategy_in_slices(&self, strategy: &StrategyMetadata) {
+        let wallet_ids = &strategy.wallet_ids;
+        let tokens = match strategy.market_tokens() {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                warn!("⚠️ Skipping sliced unwind for strategy {}: {}", strategy.id, e);
+                return;
+            }
+        };
+
+        let clients = match wallet_ids
+            .iter()
+            .map(|&wallet_id| Ok((wallet_id, self.get_lighter_client(wallet_id)?)))
+            .collect::<Result<Vec<_>, TradingError>>()
+        {
+            Ok(clients) => clients,
+            Err(e) => {
+                warn!("⚠️ Skipping sliced unwind for wallet group {:?}: {}", wallet_ids, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.execution_scheduler.unwind_group(&tokens, clients).await {
+            warn!("⚠️ Sliced unwind stopped for wallet group {:?}: {} | closing remainder immediately", wallet_ids, e);
+        }
+    }
+
     /// Close open positions on Lighter exchange for a single wallet, retrying up to 5 attempts.
     ///
     /// This method attempts to close all open positions for a given wallet by ID,
@@ -733,79 +1706,7 @@ impl TraderClient {
                 }
             }
         }
-        Ok(())
-    }
-
-    /// Create random wallet groups with 3-5 wallets per group
-    /// 
-    /// # Arguments
-    /// * `min_group_size` - Minimum number of wallets per group (default: 3)
-    /// * `max_group_size` - Maximum number of wallets per group (default: 5)
-    /// 
-    /// # Returns
-    /// * `Ok(Vec<Vec<u8>>)` - Vector of wallet groups, each containing wallet IDs
-    /// * `Err(TradingError)` - If grouping is not possible
-    fn create_random_wallet_groups(&self, min_group_size: usize, max_group_size: usize) -> Result<Vec<Vec<u8>>, TradingError> {
-        if min_group_size > max_group_size {
-            return Err(TradingError::InvalidInput("min_group_size cannot be greater than max_group_size".into()));
-        }
-
-        if self.wallets.len() < min_group_size {
-            return Err(TradingError::InvalidInput(format!(
-                "Not enough wallets ({}) for minimum group size ({})",
-                self.wallets.len(),
-                min_group_size
-            )));
-        }
-
-        let mut wallet_ids: Vec<u8> = self.wallets.iter().map(|w| w.id).collect();
-        let mut rng = rand::thread_rng();
-        
-        // Shuffle wallets for random distribution
-        wallet_ids.shuffle(&mut rng);
-        
-        let mut groups = Vec::new();
-        let mut current_index = 0;
-        
-        while current_index < wallet_ids.len() {
-            // Calculate remaining wallets
-            let remaining = wallet_ids.len() - current_index;
-            
-            // Determine group size
-            let group_size = if remaining <= max_group_size {
-                // Last group - take all remaining
-                remaining
-            } else {
-                // Calculate optimal group size to avoid very small last groups
-                let optimal_size = if remaining - min_group_size <= max_group_size {
-                    // Distribute evenly to avoid small last group
-                    let groups_remaining = (remaining as f64 / max_group_size as f64).ceil() as usize;
-                    (remaining + groups_remaining - 1) / groups_remaining // ceiling division
-                } else {
-                    // Use random size within bounds
-                    rng.gen_range(min_group_size..=max_group_size)
-                };
-                
-                optimal_size.min(remaining)
-            };
-            
-            // Create group
-            let group = wallet_ids[current_index..current_index + group_size].to_vec();
-            groups.push(group);
-            current_index += group_size;
-        }
-        
-        // Ensure last group meets minimum size requirement by redistributing if needed
-        if let Some(last_group) = groups.last() {
-            if last_group.len() < min_group_size && groups.len() > 1 {
-                let last_group = groups.pop().unwrap();
-                if let Some(prev_group) = groups.last_mut() {
-                    prev_group.extend(last_group);
-                }
-            }
-        }
-        
-        Ok(groups)
+        Ok(())
     }
 
 
@@ -840,24 +1741,309 @@ impl TraderClient {
 
     /// Update the status of a strategy in storage
     /// 
+    /// The update is guarded by the status and `updated_at` the strategy was read with, which
+    /// are then moved forward so the strategy can go through its next transition.
+    /// 
     /// # Arguments
-    /// * `strategy_id` - Unique strategy identifier
+    /// * `strategy` - Strategy as last read or updated
     /// * `status` - New status to set
     /// * `closed_at` - Optional close timestamp (for completed strategies)
     /// * `total_pnl` - Optional PNL value (for completed strategies)
+    /// 
+    /// # Returns
+    /// * `Ok(())` - The strategy moved to `status`
+    /// * `Err(TradingError)` - If the transition is illegal, the strategy changed since it was read, or storage fails
 	async fn set_strategy_status(
 		&self,
-		strategy_id: &str,
+		strategy: &mut StrategyMetadata,
 		status: StrategyStatus,
 		closed_at: Option<DateTime<Utc>>,
 		total_pnl: Option<Decimal>,
 	) -> Result<(), TradingError> {
-		self
+		strategy.updated_at = self
 			.strategy_storage
-			.update_strategy_status(strategy_id, status, closed_at, total_pnl)
-			.await
+			.update_strategy_status(&strategy.id, strategy.status, strategy.updated_at, status, closed_at, total_pnl)
+			.await?;
+		strategy.status = status;
+		strategy.closed_at = closed_at;
+		strategy.realized_pnl = total_pnl;
+
+		self.record_event(StrategyEvent::new(
+			&strategy.id,
+			StrategyEventKind::StatusChange,
+			json!({ "status": status.to_string(), "realized_pnl": total_pnl }),
+		))
+		.await;
+
+		Ok(())
 	}
 
+    /// Append an event to the strategy journal
+    /// 
+    /// The journal is for post-mortems only, so a failed write is logged and never
+    /// interrupts trading.
+    /// 
+    /// # Arguments
+    /// * `event` - Event to record
+    async fn record_event(&self, event: StrategyEvent) {
+        if let Err(e) = self.event_storage.record_event(&event).await {
+            error!("❌ Failed to journal {} for strategy {}: {}", event.kind, event.strategy_id, e);
+        }
+    }
+
+    /// Journal the orders and confirmed positions of every leg of an execution
+    /// 
+    /// # Arguments
+    /// * `strategy_id` - Strategy the legs belong to
+    /// * `legs` - Executed legs
+    async fn record_leg_events(&self, strategy_id: &str, legs: &[LegExecution]) {
+        for leg in legs {
+            let symbol = leg.token.get_symbol_string(Exchange::Lighter);
+
+            for fill in &leg.fills {
+                self.record_event(
+                    StrategyEvent::new(
+                        strategy_id,
+                        StrategyEventKind::LegOrderSent,
+                        json!({
+                            "side": leg.allocation.side.to_string(),
+                            "tx_hash": fill.tx_hash,
+                            "nonce": fill.nonce,
+                            "price": fill.price,
+                            "base_amount": fill.base_amount,
+                            "amount_usdc": fill.amount_usdc,
+                            "expected_price": fill.expected_price,
+                            "fill_price": fill.fill_price,
+                            "executed_at": fill.executed_at,
+                        }),
+                    )
+                    .with_leg(leg.allocation.wallet_id, &symbol),
+                )
+                .await;
+            }
+
+            if let Some(position) = &leg.position {
+                self.record_event(
+                    StrategyEvent::new(
+                        strategy_id,
+                        StrategyEventKind::LegConfirmed,
+                        json!({
+                            "side": position.side.to_string(),
+                            "size_usdc": position.size,
+                            "slices": leg.fills.len(),
+                            "slippage_bps": leg.slippage_bps(),
+                        }),
+                    )
+                    .with_leg(leg.allocation.wallet_id, &symbol),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Get the journal of a strategy, oldest event first
+    #[allow(unused)]
+    pub async fn get_strategy_events(&self, strategy_id: &str) -> Result<Vec<StrategyEvent>, TradingError> {
+        self.event_storage.get_strategy_events(strategy_id).await
+    }
+
+
+    /// Mark a strategy and its stored positions as Closing before its markets are flattened
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy about to be closed
+    /// 
+    /// # Returns
+    /// * `Ok(ClosingLegs)` - Live positions on the strategy's markets, used to settle its positions once closed
+    /// * `Err(TradingError)` - If the statuses cannot be updated, or another pass already moved the strategy on
+    async fn begin_strategy_close(&self, strategy: &mut StrategyMetadata) -> Result<ClosingLegs, TradingError> {
+        let started_at = Utc::now();
+        let positions = self
+            .fetch_strategy_legs(strategy)
+            .await
+            .inspect_err(|e| warn!("⚠️ Failed to fetch positions of strategy {} before closing: {}", strategy.id, e))
+            .unwrap_or_default();
+
+        self.set_strategy_status(strategy, StrategyStatus::Closing, None, None).await?;
+        self.position_storage
+            .update_strategy_positions_status(&strategy.id, PositionStatus::Closing)
+            .await?;
+
+        Ok(ClosingLegs { started_at, positions })
+    }
+
+    /// Settle a strategy's positions after a close attempt and mark it Closed, or Failed
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy that was closed
+    /// * `legs` - Live positions returned by `begin_strategy_close`
+    /// * `closed` - Whether every market of the strategy was flattened
+    /// 
+    /// # Returns
+    /// * `Ok(StrategyStatus)` - The strategy's final status
+    /// * `Err(TradingError)` - If the statuses cannot be updated
+    async fn finish_strategy_close(
+        &self,
+        strategy: &mut StrategyMetadata,
+        legs: &ClosingLegs,
+        closed: bool,
+    ) -> Result<StrategyStatus, TradingError> {
+        let realized_pnl = self.settle_strategy_positions(strategy, legs, closed).await?;
+
+        if closed {
+            self.set_strategy_status(strategy, StrategyStatus::Closed, Some(Utc::now()), realized_pnl).await?;
+            Ok(StrategyStatus::Closed)
+        } else {
+            self.set_strategy_status(strategy, StrategyStatus::Failed, Some(Utc::now()), None).await?;
+            Ok(StrategyStatus::Failed)
+        }
+    }
+
+    /// Record how a strategy's stored positions ended after a close attempt
+    /// 
+    /// Positions still open on the exchange are marked Failed. The others are marked Closed
+    /// with the exit price and PnL of their leg, derived from the reduce-only fills recorded
+    /// in the order ledger since the close started; a leg shared by several positions is
+    /// split between them by size. Both are left unknown for legs without a known fill.
+    /// 
+    /// # Arguments
+    /// * `strategy` - Strategy that was closed
+    /// * `legs` - Live positions taken before the close
+    /// * `closed` - Whether every market of the strategy was flattened
+    /// 
+    /// # Returns
+    /// * `Ok(Some(Decimal))` - Realized PnL of the positions that were closed
+    /// * `Ok(None)` - The PnL of some closed position is unknown, or none was closed
+    /// * `Err(TradingError)` - If positions cannot be loaded or saved
+    async fn settle_strategy_positions(
+        &self,
+        strategy: &StrategyMetadata,
+        legs: &ClosingLegs,
+        closed: bool,
+    ) -> Result<Option<Decimal>, TradingError> {
+        let positions: Vec<Position> = self
+            .position_storage
+            .get_positions_by_strategy(&strategy.id)
+            .await?
+            .into_iter()
+            .filter(|p| p.status != PositionStatus::Closed)
+            .collect();
+        if positions.is_empty() {
+            return Ok(None);
+        }
+
+        // After a failed close, positions whose leg cannot be checked are assumed to be open
+        let remaining = if closed {
+            Some(HashMap::new())
+        } else {
+            self.fetch_strategy_legs(strategy)
+                .await
+                .inspect_err(|e| warn!("⚠️ Failed to fetch remaining positions of strategy {}: {}", strategy.id, e))
+                .ok()
+        };
+
+        let mut leg_sizes: HashMap<(u8, String), Decimal> = HashMap::new();
+        for position in &positions {
+            *leg_sizes.entry((position.wallet_id, position.symbol.clone())).or_default() += position.size;
+        }
+
+        let exits = self.fetch_leg_exits(&strategy.wallet_ids, legs.started_at).await;
+
+        let now = Utc::now();
+        let mut realized_pnl: Option<Decimal> = None;
+        let mut pnl_known = true;
+        for mut position in positions {
+            let key = (position.wallet_id, position.symbol.clone());
+
+            position.closed_at = Some(now);
+            position.updated_at = now;
+
+            if remaining.as_ref().is_none_or(|remaining| remaining.contains_key(&key)) {
+                position.status = PositionStatus::Failed;
+            } else {
+                position.status = PositionStatus::Closed;
+                position.close_price = None;
+                position.realized_pnl = None;
+
+                match (legs.positions.get(&key), exits.get(&key).copied().flatten()) {
+                    (Some(leg), Some(exit)) => {
+                        let leg_size = leg_sizes.get(&key).copied().unwrap_or_default();
+                        let share = if leg_size.is_zero() { Decimal::ONE } else { position.size / leg_size };
+                        let pnl = exit.leg_pnl(leg) * share;
+
+                        position.close_price = Some(exit.price);
+                        position.realized_pnl = Some(pnl);
+                        *realized_pnl.get_or_insert(Decimal::ZERO) += pnl;
+                    }
+                    _ => {
+                        warn!("⚠️ No exit fill known for {} on wallet #{}, its close price and PnL are left unknown", key.1, key.0);
+                        pnl_known = false;
+                    }
+                }
+            }
+
+            self.position_storage.save_position(&position).await?;
+        }
+
+        Ok(realized_pnl.filter(|_| pnl_known))
+    }
+
+    /// Average exit price and fees of the reduce-only orders sent on each leg since `since`
+    /// 
+    /// A leg maps to `None` when any of its orders has no known fill price. Wallets whose
+    /// orders cannot be read are left out.
+    async fn fetch_leg_exits(&self, wallet_ids: &[u8], since: DateTime<Utc>) -> HashMap<(u8, String), Option<LegExit>> {
+        let mut orders_by_leg: HashMap<(u8, String), Vec<OrderRecord>> = HashMap::new();
+        for &wallet_id in wallet_ids {
+            match self.order_storage.get_wallet_orders(wallet_id, since).await {
+                Ok(orders) => {
+                    for order in orders.into_iter().filter(|o| o.reduce_only) {
+                        orders_by_leg.entry((wallet_id, order.symbol.clone())).or_default().push(order);
+                    }
+                }
+                Err(e) => warn!("⚠️ Failed to read the exit orders of wallet #{}: {}", wallet_id, e),
+            }
+        }
+
+        orders_by_leg
+            .into_iter()
+            .map(|(key, orders)| (key, LegExit::from_orders(&orders)))
+            .collect()
+    }
+
+    /// Live positions on a strategy's markets, keyed by wallet and market symbol
+    async fn fetch_strategy_legs(
+        &self,
+        strategy: &StrategyMetadata,
+    ) -> Result<HashMap<(u8, String), LighterPosition>, TradingError> {
+        let symbols = strategy.market_symbols();
+
+        let fetches = strategy.wallet_ids.iter().map(|&wallet_id| async move {
+            let positions = match self.get_lighter_client(wallet_id) {
+                Ok(client) => client.get_active_positions().await,
+                Err(e) => Err(e),
+            };
+            (wallet_id, positions)
+        });
+
+        let mut legs = HashMap::new();
+        for (wallet_id, result) in futures::future::join_all(fetches).await {
+            match result {
+                Ok(positions) => legs.extend(
+                    positions
+                        .into_iter()
+                        .filter(|p| symbols.contains(&p.symbol))
+                        .map(|p| ((wallet_id, p.symbol.clone()), p)),
+                ),
+                // Accounts that never traded report no positions at all
+                Err(TradingError::PositionNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(legs)
+    }
+
 
     /// Get a Lighter client for a specific wallet
     /// 
@@ -883,20 +2069,33 @@ impl TraderClient {
     /// * `Ok(Vec<(u8, Decimal)>)` - Vector of (wallet_id, balance) pairs
     /// * `Err(TradingError)` - If any wallet has insufficient balance or API fails
     pub async fn fetch_wallet_balances_on_lighter(&self) -> Result<Vec<(u8, Decimal)>, TradingError> {
+        let wallet_ids: Vec<u8> = self.wallets.iter().map(|w| w.id).collect();
+        self.fetch_wallet_balances_for(&wallet_ids).await
+    }
+
+    /// Fetch USDC balances for the given wallets from Lighter exchange in parallel
+    /// 
+    /// # Arguments
+    /// * `wallet_ids` - Wallets whose balances should be fetched
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<(u8, Decimal)>)` - Vector of (wallet_id, balance) pairs
+    /// * `Err(TradingError)` - If any wallet has insufficient balance or API fails
+    async fn fetch_wallet_balances_for(&self, wallet_ids: &[u8]) -> Result<Vec<(u8, Decimal)>, TradingError> {
         use futures::future::try_join_all;
 
         // For each wallet, spawn an async block to fetch its balance
-        let balance_futures = self.wallets.iter().map(|wallet| {
+        let balance_futures = wallet_ids.iter().map(|&wallet_id| {
             async move {
-                let client = self.get_lighter_client(wallet.id)?;
+                let client = self.get_lighter_client(wallet_id)?;
                 let balance = client.get_usdc_balance().await?;
                 if balance <= Decimal::ZERO {
                     return Err(TradingError::InvalidInput(format!(
                         "Wallet #{} has insufficient USDC balance: {}",
-                        wallet.id, balance
+                        wallet_id, balance
                     )));
                 }
-                Ok((wallet.id, balance))
+                Ok((wallet_id, balance))
             }
         });
 
@@ -904,72 +2103,319 @@ impl TraderClient {
         Ok(balances)
     }
 
+    /// Record the balances and Lighter points of every wallet this instance holds in the snapshot time series
+    /// 
+    /// Wallets are fetched in parallel. A wallet whose account or points cannot be fetched
+    /// is logged and skipped, so one failing wallet does not leave a gap for the others.
+    /// 
+    /// # Returns
+    /// * `Ok(usize)` - Number of wallets whose balances and points were both recorded
+    /// * `Err(TradingError)` - If every wallet failed
+    pub async fn snapshot_wallets(&self) -> Result<usize, TradingError> {
+        let taken_at = Utc::now();
+        let held = self.wallet_leases.held();
+        let clients: Vec<&WalletTradingClient> = self.wallet_trading_clients.iter().filter(|w| held.contains(&w.wallet.id)).collect();
+
+        let fetches = clients.iter().map(|w| async move {
+            let (account, points) = tokio::join!(w.lighter_client.get_account(), w.lighter_client.get_account_points());
+            (w.wallet.id, account, points)
+        });
+
+        let parse_amount = |value: &str| {
+            value
+                .parse::<Decimal>()
+                .map_err(|e| TradingError::InvalidInput(format!("Invalid account amount '{}': {}", value, e)))
+        };
+
+        let mut recorded = 0;
+        for (wallet_id, account, points) in futures::future::join_all(fetches).await {
+            let mut complete = true;
+
+            match account.and_then(|account| {
+                Ok(WalletBalanceSnapshot {
+                    wallet_id,
+                    taken_at,
+                    available_balance: parse_amount(&account.available_balance)?,
+                    collateral: parse_amount(&account.collateral)?,
+                    total_asset_value: parse_amount(&account.total_asset_value)?,
+                })
+            }) {
+                Ok(snapshot) => {
+                    if let Err(e) = self.snapshot_storage.save_balance_snapshot(&snapshot).await {
+                        error!("❌ Failed to save balance snapshot of wallet #{}: {}", wallet_id, e);
+                        complete = false;
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch balances of wallet #{}: {}", wallet_id, e);
+                    complete = false;
+                }
+            }
+
+            match points {
+                Ok(points) => {
+                    let snapshot = WalletPointsSnapshot {
+                        wallet_id,
+                        taken_at,
+                        total_points: Decimal::from_f64(points.user_total_points).unwrap_or_default(),
+                        last_week_points: Decimal::from_f64(points.user_last_week_points).unwrap_or_default(),
+                    };
+                    if let Err(e) = self.snapshot_storage.save_points_snapshot(&snapshot).await {
+                        error!("❌ Failed to save points snapshot of wallet #{}: {}", wallet_id, e);
+                        complete = false;
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch points of wallet #{}: {}", wallet_id, e);
+                    complete = false;
+                }
+            }
+
+            if complete {
+                recorded += 1;
+            }
+        }
+
+        if recorded == 0 && !clients.is_empty() {
+            return Err(TradingError::ExchangeError("No wallet could be snapshotted".into()));
+        }
+
+        info!("📸 Snapshotted balances and points of {}/{} wallets", recorded, clients.len());
+        Ok(recorded)
+    }
+
 
 
-    /// Randomly select a token from the supported tokens list
+    /// Randomly select distinct tokens from the supported tokens list
+    /// 
+    /// # Arguments
+    /// * `exchange` - Exchange whose supported tokens are used
+    /// * `count` - Number of tokens to select (capped by the number of supported tokens)
     /// 
     /// # Returns
-    /// * `Ok(Token)` - Randomly selected token for trading
+    /// * `Ok(Vec<Token>)` - Randomly selected tokens for trading
     /// * `Err(TradingError)` - If no supported tokens are available
-	pub fn select_random_token(&self, exchange: &Exchange) -> Result<Token, TradingError> {
+	pub fn select_random_tokens(&self, exchange: &Exchange, count: usize) -> Result<Vec<Token>, TradingError> {
 		let supported = Token::get_supported_tokens(exchange);
 		let mut rng = rand::thread_rng();
 
-		supported
-			.choose(&mut rng)
+		let selected: Vec<Token> = supported
+			.choose_multiple(&mut rng, count.max(1))
 			.cloned()
-			.ok_or_else(|| TradingError::InvalidInput("No tokens available".into()))
+			.collect();
+
+		if selected.is_empty() {
+			return Err(TradingError::InvalidInput("No tokens available".into()));
+		}
+
+		Ok(selected)
 	}
 
-    /// Check for and handle conflicting active strategies
+    /// Decide whether a group token is traded as a correlated pair and find its basket
     /// 
-    /// This method prevents strategy conflicts by:
-    /// 1. Identifying active strategies that use any of this client's wallets
-    /// 2. Waiting for those strategies to complete naturally
-    /// 3. Returning an error instructing the caller to retry
+    /// # Arguments
+    /// * `wallet_group` - Wallet IDs of the group (any client is used to fetch candles)
+    /// * `long_token` - Token that would be bought
+    /// * `used_tokens` - Markets already traded by the group, excluded from the basket
     /// 
     /// # Returns
-    /// * `Ok(())` - No conflicting strategies found
-    /// * `Err(TradingError)` - Conflicts were found and handled, retry needed
-	async fn handle_conflicting_strategies(&self) -> Result<(), TradingError> {
-		let active_strategies = self.strategy_storage.get_active_strategies().await?;
-
-		let conflicting: Vec<StrategyMetadata> = active_strategies
-			.into_iter()
-			.filter(|strategy| strategy.wallet_ids.iter().any(|id| self.wallets.iter().any(|w| w.id == *id)))
-			.collect();
+    /// * `Some(CorrelatedPair)` - The token should be traded as a pair
+    /// * `None` - Pairs are disabled, not drawn, or no basket is correlated enough (delta-neutral)
+    async fn select_correlated_pair(&self, wallet_group: &[u8], long_token: &Token, used_tokens: &[Token]) -> Option<CorrelatedPair> {
+        let config = &self.config.pair;
+        if !config.enabled || !rand::thread_rng().gen_bool(config.probability) {
+            return None;
+        }
 
-		if !conflicting.is_empty() {
-			info!("⚠️  Found {} active strategies involving the provided wallets", conflicting.len());
-			info!("📋 Waiting for these strategies to complete before starting new trades...");
-			self.monitor_and_close_strategies(conflicting).await?;
+        let client = self.get_lighter_client(*wallet_group.first()?).ok()?;
+        let candidates: Vec<Token> = Token::get_supported_tokens(&Exchange::Lighter)
+            .into_iter()
+            .filter(|t| !used_tokens.contains(t))
+            .collect();
+
+        info!("🔗 Looking for tokens correlated with {}...", long_token.symbol);
+        match CorrelatedPair::find(&client, long_token, &candidates, config).await {
+            Ok(Some(pair)) => Some(pair),
+            Ok(None) => {
+                info!("No basket correlated enough with {}, trading it delta-neutral", long_token.symbol);
+                None
+            }
+            Err(e) => {
+                warn!("⚠️ Could not evaluate pair for {}: {} | trading it delta-neutral", long_token.symbol, e);
+                None
+            }
+        }
+    }
 
-            info!("🔄 Active strategies were completed. Starting new trades...");
-		}
+    /// Pick how many tokens a wallet group splits its balance across
+    /// 
+    /// Random between 1 and `max_tokens_per_group`, reduced so that the smallest
+    /// wallet still puts at least `min_token_balance_usdc` into every token.
+    /// 
+    /// # Arguments
+    /// * `balances` - Wallet balances of the group
+    fn token_count_for_group(&self, balances: &[(u8, Decimal)]) -> usize {
+        let max_tokens = self.config.trading.max_tokens_per_group
+            .min(Token::get_supported_tokens(&Exchange::Lighter).len())
+            .max(1);
+        let min_share = Decimal::from_f64(self.config.trading.min_token_balance_usdc).unwrap_or(Decimal::ONE);
+        let smallest_balance = balances.iter().map(|(_, b)| *b).min().unwrap_or(Decimal::ZERO);
+        let affordable = (smallest_balance / min_share).floor().to_usize().unwrap_or(1);
+
+        let upper = max_tokens.min(affordable).max(1);
+        rand::thread_rng().gen_range(1..=upper)
+    }
 
-        info!("✅ No active strategies found for these wallets.");
-		Ok(())
-	}
+    /// Compare active strategies and their stored positions with the live positions of every wallet
+    /// 
+    /// Mismatches are remediated according to `[reconciliation]`: orphans are flattened on
+    /// their wallet, and strategies with missing, resized or flipped legs are closed when
+    /// configured to. A Telegram report is sent whenever something was found.
+    /// 
+    /// # Returns
+    /// * `Ok(ReconciliationReport)` - Every mismatch found, and how many were remediated
+    /// * `Err(TradingError)` - If stored or live positions cannot be fetched
+    pub async fn reconcile_positions(&self) -> Result<ReconciliationReport, TradingError> {
+        let config = &self.config.reconciliation;
+
+        // Strategies owned by other instances, and their wallets, are theirs to reconcile
+        let (strategies, others): (Vec<StrategyMetadata>, Vec<StrategyMetadata>) = self
+            .strategy_storage
+            .get_active_strategies()
+            .await?
+            .into_iter()
+            .partition(|s| self.wallet_leases.owns(&s.wallet_ids));
+        let mut wallets: HashSet<u8> = self.wallet_leases.held().into_iter().collect();
+        wallets.extend(strategies.iter().flat_map(|s| s.wallet_ids.iter().copied()));
+        for strategy in &others {
+            for wallet_id in &strategy.wallet_ids {
+                wallets.remove(wallet_id);
+            }
+        }
+        let stored = self.position_storage.get_active_positions().await?;
+
+        let mut expected = Vec::new();
+        for strategy in &strategies {
+            let positions: Vec<&Position> = stored
+                .iter()
+                .filter(|p| p.strategy_id.as_deref() == Some(strategy.id.as_str()))
+                .collect();
+
+            if positions.is_empty() {
+                // Opened before positions were stored: only its wallets and markets are known
+                for &wallet_id in &strategy.wallet_ids {
+                    for symbol in strategy.market_symbols() {
+                        expected.push(ExpectedPosition { strategy_id: strategy.id.clone(), wallet_id, symbol, stored: None });
+                    }
+                }
+            } else {
+                expected.extend(positions.iter().map(|p| ExpectedPosition::from_position(&strategy.id, p)));
+            }
+        }
+        expected.retain(|e| wallets.contains(&e.wallet_id));
+
+        let fetches = self.wallet_trading_clients.iter().filter(|w| wallets.contains(&w.wallet.id)).map(|w| async move {
+            (w.wallet.id, w.lighter_client.get_active_positions().await)
+        });
+
+        let mut live = Vec::new();
+        for (wallet_id, result) in futures::future::join_all(fetches).await {
+            match result {
+                Ok(positions) => live.extend(positions.iter().map(|p| LivePosition::from_lighter(wallet_id, p))),
+                // Accounts that never traded report no positions at all
+                Err(TradingError::PositionNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let tolerance = Decimal::from_f64(config.size_tolerance_pct).unwrap_or_default();
+        let mut report = ReconciliationReport {
+            checked_at: Utc::now(),
+            wallets: wallets.len(),
+            strategies: strategies.len(),
+            live_positions: live.len(),
+            mismatches: find_mismatches(&expected, &live, tolerance),
+            remediated: 0,
+            failed: 0,
+        };
+
+        if report.is_clean() {
+            info!("🧾 Reconciliation clean | {}", report.summary());
+            return Ok(report);
+        }
+
+        for mismatch in &report.mismatches {
+            warn!("🧾 {}", mismatch);
+        }
+
+        // Orphans are flattened on their wallet and market; other mismatches close their strategy once
+        let mut closed_strategies: HashMap<String, bool> = HashMap::new();
+        for mismatch in &report.mismatches {
+            let action = match mismatch.kind {
+                MismatchKind::Orphan => config.orphan_action,
+                MismatchKind::Missing => config.missing_action,
+                MismatchKind::Size | MismatchKind::Side => config.mismatch_action,
+            };
+            if action == ReconcileAction::Alert {
+                continue;
+            }
 
-    /// Check for and handle conflicting positions on Lighter exchange
+            let closed = match &mismatch.strategy_id {
+                None => {
+                    let closed = match Token::from_symbol_string(Exchange::Lighter, &mismatch.symbol) {
+                        Ok(token) => self.close_markets_on_lighter_for_wallets_group(&[mismatch.wallet_id], &[token]).await,
+                        Err(e) => Err(e),
+                    };
+                    closed.inspect_err(|e| error!("❌ Failed to close orphan {}: {}", mismatch, e)).is_ok()
+                }
+                Some(strategy_id) => match closed_strategies.get(strategy_id) {
+                    Some(&closed) => closed,
+                    None => {
+                        let Some(strategy) = strategies.iter().find(|s| &s.id == strategy_id) else { continue };
+                        warn!("🧾 Closing strategy {} after {}", strategy_id, mismatch.kind);
+                        let closed = self.close_strategy_now(strategy).await?;
+                        closed_strategies.insert(strategy_id.clone(), closed);
+                        closed
+                    }
+                },
+            };
+
+            if closed {
+                report.remediated += 1;
+            } else {
+                report.failed += 1;
+            }
+        }
+
+        warn!("🧾 Reconciliation found mismatches | {}", report.summary());
+
+        let alerter = TelegramAlerter::new();
+        if let Err(e) = alerter.send_reconciliation_alert(&report).await {
+            error!("Failed to send Telegram alert: {}", e);
+        }
+
+        Ok(report)
+    }
+
+    /// Close positions left open on wallets that are not part of any active strategy
     /// 
-    /// This method prevents position conflicts by:
-    /// 1. Identifying active positions on any of this client's wallets
-    /// 2. Waiting for those positions to complete naturally
-    /// 3. Returning an error instructing the caller to retry
+    /// # Arguments
+    /// * `wallet_ids` - Idle wallets to check
     /// 
     /// # Returns
-    async fn handle_conflicting_positions(&self) -> Result<(), TradingError> {
-        for wallet in self.wallets.iter() {
-            let client = self.get_lighter_client(wallet.id)?;
+    /// * `Ok(())` - The wallets hold no positions
+    /// * `Err(TradingError)` - If positions cannot be fetched or closed
+    pub async fn close_stray_positions(&self, wallet_ids: &[u8]) -> Result<(), TradingError> {
+        for &wallet_id in wallet_ids {
+            let client = self.get_lighter_client(wallet_id)?;
             let positions = client.get_active_positions().await?;
             if positions.is_empty() {
                 continue;
             }
 
-            info!("⚠️  Found {} active positions on wallet {}", positions.len(), wallet.id);
+            info!("⚠️  Found {} active positions on wallet {}", positions.len(), wallet_id);
             info!("📋 Waiting for these positions to complete before starting new trades...");
-            self.close_positions_on_lighter_for_wallet(wallet.id).await?;
+            self.close_positions_on_lighter_for_wallet(wallet_id).await?;
         }
 
         info!("✅ No active positions found on any wallets.");
@@ -1032,6 +2478,143 @@ impl TraderClient {
         self.strategy_storage.save_strategy(strategy).await
     }
 
+    /// Snapshot equity and trip the circuit breaker if a loss or drawdown limit is crossed
+    /// 
+    /// When the breaker trips, a Telegram alert is sent and, if `flatten_on_trip` is set,
+    /// every active strategy is closed. The breaker stays tripped until it is reset manually.
+    /// 
+    /// # Returns
+    /// * `Ok(Some(String))` - The breaker is tripped, with the reason
+    /// * `Ok(None)` - New strategies may be opened
+    /// * `Err(TradingError)` - If the breaker state or account values cannot be read
+    pub async fn check_circuit_breaker(&self) -> Result<Option<String>, TradingError> {
+        if let Some(state) = self.circuit_breaker.tripped_state().await? {
+            return Ok(Some(state.reason.unwrap_or_default()));
+        }
+
+        if !self.circuit_breaker.is_enabled() {
+            return Ok(None);
+        }
+
+        let clients = self
+            .wallets
+            .iter()
+            .map(|w| Ok((w.id, self.get_lighter_client(w.id)?)))
+            .collect::<Result<Vec<_>, TradingError>>()?;
+
+        let Some(snapshot) = self.circuit_breaker.snapshot_if_due(&clients).await? else {
+            return Ok(None);
+        };
+
+        let Some(reason) = self.circuit_breaker.evaluate(&snapshot).await? else {
+            return Ok(None);
+        };
+
+        if self.circuit_breaker.trip(&reason).await? {
+            error!("🔌 Circuit breaker tripped: {}", reason);
+
+            let flatten = self.circuit_breaker.flatten_on_trip();
+            let alerter = TelegramAlerter::new();
+            if let Err(e) = alerter.send_circuit_breaker_alert(&reason, flatten).await {
+                error!("Failed to send Telegram alert: {}", e);
+            }
+
+            if flatten {
+                warn!("🔌 Closing all active strategies");
+                self.close_all_active_strategies().await?;
+            }
+        }
+
+        Ok(Some(reason))
+    }
+
+    /// Reset a tripped circuit breaker so new strategies can be opened again
+    /// 
+    /// # Returns
+    /// * `Ok(Some(String))` - The reason the breaker had tripped
+    /// * `Ok(None)` - The breaker was not tripped
+    pub async fn reset_circuit_breaker(&self) -> Result<Option<String>, TradingError> {
+        let state = self.circuit_breaker.tripped_state().await?;
+        self.circuit_breaker.reset().await?;
+        Ok(state.map(|s| s.reason.unwrap_or_default()))
+    }
+
+    /// Process-wide kill switch
+    pub fn kill_switch(&self) -> &KillSwitch {
+        &self.kill_switch
+    }
+
+    /// Leases on the wallets this instance may trade
+    pub fn wallet_leases(&self) -> &WalletLeases {
+        &self.wallet_leases
+    }
+
+    /// Stop all trading after the kill switch was activated
+    ///
+    /// Cancels resting orders and closes every position on every wallet, then marks
+    /// all active strategies as killed. New strategies stay blocked until the kill
+    /// switch is cleared.
+    ///
+    /// # Arguments
+    /// * `state` - Active kill switch state
+    ///
+    /// # Returns
+    /// * `Ok(())` - Every wallet is flat
+    /// * `Err(TradingError)` - If positions could not be closed
+    pub async fn engage_kill_switch(&self, state: &KillSwitchState) -> Result<(), TradingError> {
+        let reason = state.reason.clone().unwrap_or_default();
+        let source = state.source.clone().unwrap_or_default();
+        error!("🛑 Engaging kill switch ({}): {}", source, reason);
+
+        let cancellations = self.wallet_trading_clients.iter().map(|w| async move {
+            (w.wallet.id, w.lighter_client.cancel_all_orders().await)
+        });
+        for (wallet_id, result) in futures::future::join_all(cancellations).await {
+            if let Err(e) = result {
+                error!("❌ Failed to cancel orders for wallet #{}: {}", wallet_id, e);
+            }
+        }
+
+        let mut strategies = self.strategy_storage.get_active_strategies().await?;
+        let started_at = Utc::now();
+        let strategy_legs = futures::future::join_all(strategies.iter().map(|strategy| async move {
+            let positions = self
+                .fetch_strategy_legs(strategy)
+                .await
+                .inspect_err(|e| warn!("⚠️ Failed to fetch positions of strategy {}: {}", strategy.id, e))
+                .unwrap_or_default();
+            ClosingLegs { started_at, positions }
+        }))
+        .await;
+
+        let flattened = self.close_all_positions_on_lighter_for_all_wallets().await;
+
+        for (strategy, legs) in strategies.iter_mut().zip(&strategy_legs) {
+            let realized_pnl = self.settle_strategy_positions(strategy, legs, flattened.is_ok()).await?;
+            match self.set_strategy_status(strategy, StrategyStatus::Killed, Some(Utc::now()), realized_pnl).await {
+                Err(e) if e.is_strategy_conflict() => warn!("⚠️ Strategy {} not marked Killed: {}", strategy.id, e),
+                result => result?,
+            }
+        }
+        self.limit_actions.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.rescues.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.liquidation_warnings.lock().unwrap_or_else(|e| e.into_inner()).clear();
+
+        let alerter = TelegramAlerter::new();
+        if let Err(e) = alerter.send_kill_switch_alert(&reason, &source, strategies.len(), flattened.is_ok()).await {
+            error!("Failed to send Telegram alert: {}", e);
+        }
+
+        flattened?;
+        warn!("🛑 Kill switch engaged, {} strategies killed", strategies.len());
+        Ok(())
+    }
+
+    /// Application configuration loaded at startup
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
     /// Get all active strategies
     #[allow(unused)]
     pub async fn get_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
@@ -1063,7 +2646,12 @@ impl TraderClient {
     /// * `Ok(())` - All failed strategies were processed (successfully or remain failed)
     /// * `Err(TradingError)` - If critical errors occur during processing
     pub async fn retry_failed_strategies(&self) -> Result<(), TradingError> {
-        let failed_strategies = self.get_failed_strategies().await?;
+        let failed_strategies: Vec<StrategyMetadata> = self
+            .get_failed_strategies()
+            .await?
+            .into_iter()
+            .filter(|s| self.wallet_leases.owns(&s.wallet_ids))
+            .collect();
 
         if failed_strategies.is_empty() {
             info!("✅ No failed strategies found in database");
@@ -1073,15 +2661,31 @@ impl TraderClient {
         info!("🔄 Found {} failed strategy(ies) in database", failed_strategies.len());
         info!("🔄 Attempting to close positions for failed strategies...");
 
-        for strategy in failed_strategies {
+        for mut strategy in failed_strategies {
             info!("🔄 Retrying strategy {} | Token: {} | Wallets: {:?}", 
-                strategy.id, strategy.token_symbol, strategy.wallet_ids);
+                strategy.id, strategy.market_label(), strategy.wallet_ids);
 
-            match self.close_positions_on_lighter_for_wallets_group(&strategy.wallet_ids).await {
-                Ok(_) => {
-                    info!("✅ Successfully closed positions for failed strategy {}", strategy.id);
-                    self.set_strategy_status(&strategy.id, StrategyStatus::Closed, Some(Utc::now()), None).await?;
+            let legs = match self.begin_strategy_close(&mut strategy).await {
+                Ok(legs) => legs,
+                Err(e) if e.is_strategy_conflict() => {
+                    warn!("⚠️ Skipping retry of strategy {}: {}", strategy.id, e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let result = self.close_strategy_positions_on_lighter(&strategy).await;
+            match self.finish_strategy_close(&mut strategy, &legs, result.is_ok()).await {
+                Ok(_) => {}
+                Err(e) if e.is_strategy_conflict() => {
+                    warn!("⚠️ Strategy {} changed while retrying, status left as found: {}", strategy.id, e);
+                    continue;
                 }
+                Err(e) => return Err(e),
+            }
+
+            match result {
+                Ok(_) => info!("✅ Successfully closed positions for failed strategy {}", strategy.id),
                 Err(e) => {
                     error!("❌ Still unable to close positions for strategy {}: {}", strategy.id, e);
                     error!("   Strategy {} remains in FAILED status", strategy.id);
@@ -1098,3 +2702,51 @@ impl TraderClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::PositionSide, perp::lighter::client::tests::position, storage::storage_order::OrderStatus};
+    use rust_decimal_macros::dec;
+
+    fn reduce_order(base_amount: i64, fill_price: Option<Decimal>, fee_usdc: Decimal) -> OrderRecord {
+        OrderRecord {
+            exchange: Exchange::Lighter,
+            wallet_id: 1,
+            symbol: "ETH".to_string(),
+            side: PositionSide::Short,
+            reduce_only: true,
+            base_amount,
+            limit_price: dec!(2900),
+            nonce: 1,
+            client_order_index: 0,
+            tx_hash: format!("0x{}", base_amount),
+            submitted_at: Utc::now(),
+            status: OrderStatus::Executed,
+            tx_status: None,
+            executed_at: None,
+            fill_price,
+            fee_usdc: Some(fee_usdc),
+        }
+    }
+
+    #[test]
+    fn leg_exit_is_weighted_by_base_amount() {
+        let orders = [reduce_order(100, Some(dec!(3000)), dec!(0.3)), reduce_order(300, Some(dec!(3040)), dec!(0.9))];
+        let exit = LegExit::from_orders(&orders).unwrap();
+
+        assert_eq!(exit, LegExit { price: dec!(3030), fees_usdc: dec!(1.2) });
+
+        // 2 ETH long @ 3000 sold @ 3030, less fees; the short side of the same exit loses
+        assert_eq!(exit.leg_pnl(&position(1, dec!(2), dec!(3000))), dec!(58.8));
+        assert_eq!(exit.leg_pnl(&position(-1, dec!(2), dec!(3000))), dec!(-61.2));
+    }
+
+    #[test]
+    fn leg_exit_is_unknown_without_every_fill() {
+        assert_eq!(LegExit::from_orders(&[]), None);
+
+        let orders = [reduce_order(100, Some(dec!(3000)), Decimal::ZERO), reduce_order(300, None, Decimal::ZERO)];
+        assert_eq!(LegExit::from_orders(&orders), None);
+    }
+}
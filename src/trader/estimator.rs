@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::{CostAction, CostConfig},
+    error::TradingError,
+    model::{token::Token, Exchange, PositionSide},
+    perp::lighter::{client::LighterClient, models::{LighterBookLevel, LighterOrderBook}},
+    trader::{execution::ExecutionScheduler, strategy::WalletAllocation},
+};
+
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+/// Expected round-trip cost of a plan (open and close every leg)
+#[derive(Debug, Clone)]
+pub struct CostEstimate {
+    /// Notional traded on entry and exit
+    pub volume_usdc: Decimal,
+    pub fee_usdc: Decimal,
+    /// Half-spread plus book impact of every child order, on entry and exit
+    pub spread_usdc: Decimal,
+    /// Funding paid over the duration (negative when received)
+    pub funding_usdc: Decimal,
+    pub total_usdc: Decimal,
+    /// Total cost per unit of volume (in basis points)
+    pub cost_bps: Decimal,
+}
+
+impl CostEstimate {
+    /// Volume generated per USDC of cost, the points-efficiency of the plan
+    pub fn volume_per_usdc(&self) -> Option<Decimal> {
+        if self.total_usdc > Decimal::ZERO {
+            Some(self.volume_usdc / self.total_usdc)
+        } else {
+            None
+        }
+    }
+
+    /// Create a simple string representation for logging
+    pub fn to_log_string(&self) -> String {
+        format!(
+            "Cost: {:.2} USDC ({:.2} bps) | Fees: {:.2} | Spread/slippage: {:.2} | Funding: {:.2} | Volume: {:.2}",
+            self.total_usdc, self.cost_bps, self.fee_usdc, self.spread_usdc, self.funding_usdc, self.volume_usdc
+        )
+    }
+}
+
+/// Order book and average hourly funding of one market
+pub struct MarketCosts {
+    book: LighterOrderBook,
+    /// Funding paid by longs per hour, as a fraction of notional
+    hourly_funding: Decimal,
+}
+
+/// Estimates the cost of a plan before execution and enforces the cost ceiling
+pub struct CostEstimator {
+    config: CostConfig,
+}
+
+impl CostEstimator {
+    pub fn new(config: CostConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Maximum cost per unit of volume (in basis points)
+    fn max_cost_bps(&self) -> Decimal {
+        Decimal::from_f64(self.config.max_cost_bps).unwrap_or(Decimal::ZERO)
+    }
+
+    /// Fetch order books and funding history of the given markets
+    ///
+    /// # Arguments
+    /// * `client` - Any Lighter client, used for market data
+    /// * `tokens` - Markets traded by the plan
+    ///
+    /// # Returns
+    /// * `Ok(HashMap)` - Market costs keyed by Lighter symbol
+    /// * `Err(TradingError)` - If an order book cannot be fetched
+    pub async fn fetch_markets(
+        &self,
+        client: &LighterClient,
+        tokens: &[Token],
+    ) -> Result<HashMap<String, MarketCosts>, TradingError> {
+        let mut markets = HashMap::new();
+
+        for token in tokens {
+            let symbol = token.get_symbol_string(Exchange::Lighter);
+            if markets.contains_key(&symbol) {
+                continue;
+            }
+
+            let book = client.get_order_book(token, self.config.order_book_depth).await?;
+
+            // Missing funding history only weakens the estimate, it does not block it
+            let hourly_funding = match client.get_fundings(token, self.config.funding_lookback_hours).await {
+                Ok(fundings) if !fundings.is_empty() => {
+                    fundings.iter().map(|f| f.long_rate()).sum::<Decimal>() / Decimal::from(fundings.len())
+                }
+                Ok(_) => Decimal::ZERO,
+                Err(e) => {
+                    warn!("⚠️ No funding history for {}, assuming zero funding: {}", symbol, e);
+                    Decimal::ZERO
+                }
+            };
+
+            markets.insert(symbol, MarketCosts { book, hourly_funding });
+        }
+
+        Ok(markets)
+    }
+
+    /// Estimate the round-trip cost of a plan
+    ///
+    /// # Arguments
+    /// * `markets` - Market data from `fetch_markets`
+    /// * `tokens` - Token traded by each allocation
+    /// * `allocations` - Planned legs
+    /// * `slices` - Child orders per leg (each child walks the book on its own)
+    /// * `duration_hours` - Expected holding time
+    pub fn estimate(
+        &self,
+        markets: &HashMap<String, MarketCosts>,
+        tokens: &[Token],
+        allocations: &[WalletAllocation],
+        slices: usize,
+        duration_hours: Decimal,
+    ) -> Result<CostEstimate, TradingError> {
+        let fee_rate = Decimal::from_f64(self.config.taker_fee_bps).unwrap_or(Decimal::ZERO) / BPS;
+        let slices = Decimal::from(slices.max(1));
+
+        let (mut volume_usdc, mut fee_usdc, mut spread_usdc, mut funding_usdc) =
+            (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+
+        for (token, allocation) in tokens.iter().zip(allocations) {
+            let symbol = token.get_symbol_string(Exchange::Lighter);
+            let market = markets.get(&symbol).ok_or_else(|| {
+                TradingError::MarketDataUnavailable(format!("No market data fetched for {}", symbol))
+            })?;
+
+            let notional = allocation.usdc_amount;
+            let slice = notional / slices;
+
+            // Longs buy from the asks and sell to the bids on exit, shorts the other way round
+            let impact = book_impact(&market.book.asks, &market.book, slice)?
+                + book_impact(&market.book.bids, &market.book, slice)?;
+
+            let funding_sign = match allocation.side {
+                PositionSide::Long => Decimal::ONE,
+                PositionSide::Short => -Decimal::ONE,
+            };
+
+            volume_usdc += notional * Decimal::TWO;
+            fee_usdc += notional * Decimal::TWO * fee_rate;
+            spread_usdc += notional * impact;
+            funding_usdc += funding_sign * notional * market.hourly_funding * duration_hours;
+        }
+
+        let total_usdc = fee_usdc + spread_usdc + funding_usdc;
+        let cost_bps = if volume_usdc > Decimal::ZERO {
+            total_usdc / volume_usdc * BPS
+        } else {
+            Decimal::ZERO
+        };
+
+        Ok(CostEstimate {
+            volume_usdc,
+            fee_usdc: fee_usdc.round_dp(4),
+            spread_usdc: spread_usdc.round_dp(4),
+            funding_usdc: funding_usdc.round_dp(4),
+            total_usdc: total_usdc.round_dp(4),
+            cost_bps: cost_bps.round_dp(2),
+        })
+    }
+
+    /// Estimate a plan and enforce the cost ceiling
+    ///
+    /// With `action = "resize"`, every allocation is scaled down by the same factor (so the
+    /// plan stays balanced) until the estimate fits or `min_resize_factor` is reached.
+    /// Smaller child orders walk less of the book, but fees, spread and funding per unit
+    /// of volume do not shrink, so resizing only helps plans dominated by slippage.
+    ///
+    /// # Arguments
+    /// * `markets` - Market data from `fetch_markets`
+    /// * `tokens` - Token traded by each allocation
+    /// * `allocations` - Planned legs, scaled in place when resized
+    /// * `scheduler` - Execution scheduler, used to derive child orders per leg
+    /// * `duration_hours` - Expected holding time
+    ///
+    /// # Returns
+    /// * `Ok(CostEstimate)` - Estimate of the (possibly resized) plan
+    /// * `Err(TradingError::CostCeilingExceeded)` - If the plan must be skipped
+    pub fn fit_to_ceiling(
+        &self,
+        markets: &HashMap<String, MarketCosts>,
+        tokens: &[Token],
+        allocations: &mut [WalletAllocation],
+        scheduler: &ExecutionScheduler,
+        duration_hours: Decimal,
+    ) -> Result<CostEstimate, TradingError> {
+        let ceiling = self.max_cost_bps();
+        let min_factor = Decimal::from_f64(self.config.min_resize_factor).unwrap_or(Decimal::ONE);
+        let step = Decimal::new(75, 2);
+        let planned: Vec<WalletAllocation> = allocations.to_vec();
+
+        let mut factor = Decimal::ONE;
+        loop {
+            for (allocation, original) in allocations.iter_mut().zip(&planned) {
+                allocation.usdc_amount = original.usdc_amount * factor;
+                allocation.percentage = original.percentage * factor;
+            }
+
+            let smallest_leg = allocations.iter().map(|a| a.usdc_amount).min().unwrap_or(Decimal::ZERO);
+            let estimate = self.estimate(markets, tokens, allocations, scheduler.slice_count(smallest_leg), duration_hours)?;
+
+            if estimate.cost_bps <= ceiling {
+                if factor < Decimal::ONE {
+                    info!("📐 Plan resized to {:.0}% of its notional to fit the cost ceiling", factor * Decimal::ONE_HUNDRED);
+                }
+                return Ok(estimate);
+            }
+
+            let next_factor = factor * step;
+            if self.config.action == CostAction::Skip || next_factor < min_factor {
+                allocations.clone_from_slice(&planned);
+                return Err(TradingError::CostCeilingExceeded(format!(
+                    "estimated {:.2} bps per unit of volume (ceiling: {:.2} bps) | {}",
+                    estimate.cost_bps,
+                    ceiling,
+                    estimate.to_log_string()
+                )));
+            }
+
+            factor = next_factor;
+        }
+    }
+}
+
+/// Cost of filling `notional` against one side of the book, as a fraction of notional
+///
+/// Measured against the mid price, so it includes the half-spread. Notional beyond the
+/// fetched depth is assumed to fill at the last fetched level.
+fn book_impact(levels: &[LighterBookLevel], book: &LighterOrderBook, notional: Decimal) -> Result<Decimal, TradingError> {
+    let mid = book
+        .mid_price()
+        .ok_or_else(|| TradingError::MarketDataUnavailable("Order book has no mid price".into()))?;
+
+    if notional <= Decimal::ZERO || mid <= Decimal::ZERO {
+        return Ok(Decimal::ZERO);
+    }
+
+    let mut remaining = notional;
+    let mut base_filled = Decimal::ZERO;
+    let mut last_price = mid;
+
+    for level in levels {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+
+        let level_notional = level.price * level.remaining_base_amount;
+        let take = remaining.min(level_notional);
+        base_filled += take / level.price;
+        remaining -= take;
+        last_price = level.price;
+    }
+
+    if remaining > Decimal::ZERO {
+        base_filled += remaining / last_price;
+    }
+
+    let avg_price = notional / base_filled;
+    Ok(((avg_price - mid) / mid).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ExecutionConfig, ExecutionMode},
+        model::token::SupportedToken,
+        trader::execution::tests::scheduler,
+    };
+    use rust_decimal_macros::dec;
+
+    fn level(price: Decimal, remaining_base_amount: Decimal) -> LighterBookLevel {
+        LighterBookLevel { price, remaining_base_amount }
+    }
+
+    /// Book around a mid of 100 with one base unit at ±0.5 and deep liquidity at ±10
+    fn thin_book() -> LighterOrderBook {
+        LighterOrderBook {
+            asks: vec![level(dec!(100.5), dec!(1)), level(dec!(110), dec!(100))],
+            bids: vec![level(dec!(99.5), dec!(1)), level(dec!(90), dec!(100))],
+        }
+    }
+
+    fn allocation(wallet_id: u8, side: PositionSide, usdc_amount: Decimal) -> WalletAllocation {
+        WalletAllocation {
+            wallet_id,
+            side,
+            usdc_amount,
+            percentage: dec!(50),
+            capacity_usdc: usdc_amount,
+            leverage: 2.0,
+        }
+    }
+
+    #[test]
+    fn book_impact_walks_the_levels_from_the_mid() {
+        let book = LighterOrderBook {
+            asks: vec![level(dec!(101), dec!(10)), level(dec!(102), dec!(10))],
+            bids: vec![level(dec!(99), dec!(10)), level(dec!(98), dec!(10))],
+        };
+
+        // Within the best level only the half-spread is paid
+        assert_eq!(book_impact(&book.asks, &book, dec!(505)).unwrap(), dec!(0.01));
+        assert_eq!(book_impact(&book.bids, &book, dec!(495)).unwrap(), dec!(0.01));
+
+        // 10 @ 101 and 10 @ 102
+        assert_eq!(book_impact(&book.asks, &book, dec!(2030)).unwrap(), dec!(0.015));
+
+        // Beyond the fetched depth the rest fills at the last level
+        assert_eq!(book_impact(&book.asks, &book, dec!(3050)).unwrap(), dec!(0.0166666666666666666666666667));
+        assert_eq!(book_impact(&book.asks, &book, Decimal::ZERO).unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn no_book_impact_without_a_mid() {
+        let book = LighterOrderBook { asks: vec![level(dec!(101), dec!(10))], bids: Vec::new() };
+        assert!(book_impact(&book.asks, &book, dec!(100)).is_err());
+    }
+
+    #[tokio::test]
+    async fn plans_are_resized_until_they_fit_the_ceiling() {
+        let scheduler = scheduler(ExecutionConfig { mode: ExecutionMode::Single, ..Default::default() }).await;
+        let estimator = CostEstimator::new(CostConfig {
+            max_cost_bps: 60.0,
+            action: CostAction::Resize,
+            min_resize_factor: 0.05,
+            ..Default::default()
+        });
+        let markets = HashMap::from([("ETH".to_string(), MarketCosts { book: thin_book(), hourly_funding: Decimal::ZERO })]);
+        let tokens = vec![Token::new(SupportedToken::ETH); 2];
+        let mut allocations = vec![allocation(1, PositionSide::Long, dec!(1000)), allocation(2, PositionSide::Short, dec!(1000))];
+
+        // Only legs that barely walk past the best level cost less than 60 bps: 1000 × 0.75^8
+        let estimate = estimator.fit_to_ceiling(&markets, &tokens, &mut allocations, &scheduler, dec!(24)).unwrap();
+        assert!(estimate.cost_bps <= dec!(60), "{}", estimate.to_log_string());
+        for allocation in &allocations {
+            assert_eq!(allocation.usdc_amount, dec!(100.1129150390625));
+            assert_eq!(allocation.percentage, dec!(5.005645751953125));
+        }
+    }
+
+    #[tokio::test]
+    async fn plans_above_the_ceiling_are_skipped_unchanged() {
+        let scheduler = scheduler(ExecutionConfig { mode: ExecutionMode::Single, ..Default::default() }).await;
+        let markets = HashMap::from([("ETH".to_string(), MarketCosts { book: thin_book(), hourly_funding: Decimal::ZERO })]);
+        let tokens = vec![Token::new(SupportedToken::ETH); 2];
+
+        for config in [
+            CostConfig { max_cost_bps: 60.0, action: CostAction::Skip, ..Default::default() },
+            CostConfig { max_cost_bps: 60.0, action: CostAction::Resize, min_resize_factor: 0.25, ..Default::default() },
+        ] {
+            let mut allocations = vec![allocation(1, PositionSide::Long, dec!(1000)), allocation(2, PositionSide::Short, dec!(1000))];
+            let error = CostEstimator::new(config)
+                .fit_to_ceiling(&markets, &tokens, &mut allocations, &scheduler, dec!(24))
+                .unwrap_err();
+
+            assert!(matches!(error, TradingError::CostCeilingExceeded(_)), "{}", error);
+            assert!(allocations.iter().all(|a| a.usdc_amount == dec!(1000) && a.percentage == dec!(50)));
+        }
+    }
+}
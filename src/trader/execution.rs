@@ -0,0 +1,900 @@
+use crate::{
+    config::{ExecutionConfig, ExecutionMode},
+    error::TradingError,
+    model::{token::Token, Exchange, Position, PositionSide},
+    perp::lighter::{client::LighterClient, models::{LighterOrderFill, LighterPosition}},
+    risk::kill_switch::KillSwitch,
+    trader::{lease::WalletLeases, strategy::WalletAllocation},
+};
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use tokio::time::{sleep, Duration};
+
+/// Execution state of a single leg (one wallet, one token, one side)
+pub struct LegExecution {
+    pub token: Token,
+    pub allocation: WalletAllocation,
+    pub client: LighterClient,
+    pub fills: Vec<LighterOrderFill>,
+    pub filled_usdc: Decimal,
+    pub position: Option<Position>,
+    pub error: Option<TradingError>,
+    /// Set when the leg's remaining notional was moved to other wallets
+    pub abandoned: bool,
+}
+
+impl LegExecution {
+    fn new(token: Token, allocation: WalletAllocation, client: LighterClient) -> Self {
+        Self {
+            token,
+            allocation,
+            client,
+            fills: Vec::new(),
+            filled_usdc: Decimal::ZERO,
+            position: None,
+            error: None,
+            abandoned: false,
+        }
+    }
+
+    /// Notional still to be executed for this leg
+    fn remaining_usdc(&self) -> Decimal {
+        (self.allocation.usdc_amount - self.filled_usdc).max(Decimal::ZERO)
+    }
+
+    /// Extra notional this leg can take without exceeding its wallet capacity
+    fn spare_capacity(&self) -> Decimal {
+        (self.allocation.capacity_usdc - self.allocation.usdc_amount).max(Decimal::ZERO)
+    }
+
+    /// Signed filled notional (positive for longs, negative for shorts)
+    fn signed_filled_usdc(&self) -> Decimal {
+        match self.allocation.side {
+            PositionSide::Long => self.filled_usdc,
+            PositionSide::Short => -self.filled_usdc,
+        }
+    }
+
+    /// Signed target notional (positive for longs, negative for shorts)
+    fn signed_target_usdc(&self) -> Decimal {
+        match self.allocation.side {
+            PositionSide::Long => self.allocation.usdc_amount,
+            PositionSide::Short => -self.allocation.usdc_amount,
+        }
+    }
+
+    /// Notional-weighted slippage of the leg's fills with a known fill price (in bps)
+    pub fn slippage_bps(&self) -> Option<Decimal> {
+        weighted_slippage(self.fills.iter().map(|fill| (fill, self.allocation.side)))
+    }
+}
+
+/// A child order that filled further from the mid than `max_slippage_bps`
+#[derive(Debug, Clone)]
+pub struct SlippageBreach {
+    pub wallet_id: u8,
+    pub symbol: String,
+    pub slippage_bps: Decimal,
+}
+
+/// Notional-weighted average slippage of fills with a known fill price (in bps)
+fn weighted_slippage<'a>(fills: impl Iterator<Item = (&'a LighterOrderFill, PositionSide)>) -> Option<Decimal> {
+    let (weighted, notional) = fills
+        .filter_map(|(fill, side)| Some((fill.slippage_bps(side)? * fill.amount_usdc, fill.amount_usdc)))
+        .fold((Decimal::ZERO, Decimal::ZERO), |(sum, total), (value, amount)| (sum + value, total + amount));
+
+    if notional.is_zero() {
+        None
+    } else {
+        Some(weighted / notional)
+    }
+}
+
+/// Result of executing all legs of a wallet group
+pub struct GroupExecution {
+    pub legs: Vec<LegExecution>,
+    /// Set when the scheduler stopped the group for a reason not tied to a single leg
+    pub halt_reason: Option<TradingError>,
+    /// Child orders that exceeded the slippage limit
+    pub slippage_breaches: Vec<SlippageBreach>,
+}
+
+impl GroupExecution {
+    /// Check if every leg was fully executed and confirmed on the exchange.
+    /// Abandoned legs that never filled are not expected to hold a position.
+    pub fn is_complete(&self) -> bool {
+        self.halt_reason.is_none()
+            && self.legs.iter().all(|leg| {
+                leg.error.is_none()
+                    && (leg.position.is_some() || (leg.abandoned && leg.fills.is_empty()))
+            })
+    }
+
+    /// Net filled notional across the group (longs - shorts)
+    pub fn net_exposure(&self) -> Decimal {
+        self.legs.iter().map(|leg| leg.signed_filled_usdc()).sum()
+    }
+
+    /// Deviation of the filled notional from the group's intended long/short ratio.
+    ///
+    /// Equals `net_exposure()` for delta-neutral groups. Beta-weighted groups are meant
+    /// to be unbalanced, so their net exposure is compared to the target net scaled by
+    /// the share of the group already filled.
+    pub fn exposure_drift(&self) -> Decimal {
+        let target_gross: Decimal = self.legs.iter().map(|leg| leg.allocation.usdc_amount).sum();
+        let filled_gross: Decimal = self.legs.iter().map(|leg| leg.filled_usdc).sum();
+        let target_net: Decimal = self.legs.iter().map(|leg| leg.signed_target_usdc()).sum();
+
+        if target_gross.is_zero() {
+            return self.net_exposure();
+        }
+
+        self.net_exposure() - target_net * filled_gross / target_gross
+    }
+
+    /// Notional-weighted slippage across all fills of the group (in bps)
+    pub fn slippage_bps(&self) -> Option<Decimal> {
+        weighted_slippage(
+            self.legs
+                .iter()
+                .flat_map(|leg| leg.fills.iter().map(move |fill| (fill, leg.allocation.side))),
+        )
+    }
+
+    /// Worst slippage of a single leg (in bps)
+    pub fn max_leg_slippage_bps(&self) -> Option<Decimal> {
+        self.legs.iter().filter_map(|leg| leg.slippage_bps()).max()
+    }
+
+    /// Shrink every leg to what it already filled, so no further orders are sent.
+    /// Legs without fills are abandoned and not expected to hold a position.
+    fn truncate_to_filled(&mut self) {
+        for leg in self.legs.iter_mut() {
+            leg.allocation.usdc_amount = leg.filled_usdc;
+            if leg.fills.is_empty() {
+                leg.abandoned = true;
+            }
+        }
+    }
+
+    /// Number of legs that have at least one confirmed fill
+    pub fn opened_count(&self) -> usize {
+        self.legs.iter().filter(|leg| !leg.fills.is_empty()).count()
+    }
+
+    /// Move the remaining notional of a failed leg to same-side, same-token legs with spare capacity.
+    ///
+    /// The failed leg keeps whatever it already filled and receives no further orders.
+    ///
+    /// # Returns
+    /// * `Some(Vec<(usize, Decimal)>)` - Receiving leg indices with the notional each took over
+    /// * `None` - Not enough same-side capacity, neutrality cannot be restored
+    fn redistribute(&mut self, failed_idx: usize) -> Option<Vec<(usize, Decimal)>> {
+        let remaining = self.legs[failed_idx].remaining_usdc();
+        let side = self.legs[failed_idx].allocation.side;
+        let token = self.legs[failed_idx].token.clone();
+
+        let receivers: Vec<(usize, Decimal)> = self
+            .legs
+            .iter()
+            .enumerate()
+            .filter(|(i, leg)| {
+                *i != failed_idx
+                    && leg.allocation.side == side
+                    && leg.token == token
+                    && !leg.abandoned
+                    && leg.error.is_none()
+            })
+            .map(|(i, leg)| (i, leg.spare_capacity()))
+            .filter(|(_, spare)| *spare > Decimal::ZERO)
+            .collect();
+
+        let total_spare: Decimal = receivers.iter().map(|(_, spare)| *spare).sum();
+        if total_spare < remaining {
+            return None;
+        }
+
+        // Split proportionally to spare capacity so no receiver is pushed past its limit
+        let shares: Vec<(usize, Decimal)> = receivers
+            .into_iter()
+            .map(|(i, spare)| (i, remaining * spare / total_spare))
+            .collect();
+
+        for (i, share) in &shares {
+            self.legs[*i].allocation.usdc_amount += *share;
+        }
+
+        let failed = &mut self.legs[failed_idx];
+        failed.allocation.usdc_amount = failed.filled_usdc;
+        failed.abandoned = true;
+
+        Some(shares)
+    }
+
+    /// Take the first error that stopped the group, if any
+    pub fn take_error(&mut self) -> Option<TradingError> {
+        if let Some(e) = self.halt_reason.take() {
+            return Some(e);
+        }
+
+        self.legs.iter_mut().find_map(|leg| leg.error.take())
+    }
+}
+
+/// Splits legs into child orders and executes them in paired rounds.
+///
+/// Every round sends one child order for every leg of the group concurrently, with
+/// the same share of each leg's notional, so long and short fills stay paired and the
+/// group's net exposure never drifts by more than one round of child orders.
+/// The kill switch and wallet leases are checked before every round, so an activation
+/// or a lapsed lease stops entries and gradual exits without waiting for the remaining
+/// rounds and their delays.
+pub struct ExecutionScheduler {
+    config: ExecutionConfig,
+    kill_switch: KillSwitch,
+    wallet_leases: WalletLeases,
+}
+
+impl ExecutionScheduler {
+    pub fn new(config: ExecutionConfig, kill_switch: KillSwitch, wallet_leases: WalletLeases) -> Self {
+        Self { config, kill_switch, wallet_leases }
+    }
+
+    /// Reason to stop before the next round of child orders, if any.
+    /// If the kill switch state cannot be read, execution stops as if it were active.
+    ///
+    /// # Arguments
+    /// * `wallet_ids` - Wallets whose leases must still be held
+    async fn stop_reason(&self, wallet_ids: &[u8]) -> Option<TradingError> {
+        match self.kill_switch.active_state().await {
+            Ok(Some(state)) => return Some(TradingError::KillSwitchActive(state.reason.unwrap_or_default())),
+            Ok(None) => {}
+            Err(e) => return Some(TradingError::KillSwitchActive(format!("kill switch state unavailable: {}", e))),
+        }
+
+        let held = self.wallet_leases.held();
+        let lost: Vec<u8> = wallet_ids.iter().filter(|id| !held.contains(id)).copied().collect();
+        if lost.is_empty() {
+            None
+        } else {
+            Some(TradingError::WalletLeaseUnavailable(format!("lease lapsed on wallets {:?}", lost)))
+        }
+    }
+
+    /// Maximum |long - short| notional tolerated during entry and exit
+    fn max_net_exposure(&self) -> Decimal {
+        Decimal::from_f64(self.config.max_net_exposure_usdc).unwrap_or(Decimal::ZERO)
+    }
+
+    /// Number of child orders per leg, reduced so no child is below `min_slice_usdc`
+    pub fn slice_count(&self, smallest_leg_usdc: Decimal) -> usize {
+        if self.config.mode == ExecutionMode::Single {
+            return 1;
+        }
+
+        let min_slice = Decimal::from_f64(self.config.min_slice_usdc).unwrap_or(Decimal::ONE);
+        let max_by_size = (smallest_leg_usdc / min_slice)
+            .floor()
+            .to_string()
+            .parse::<usize>()
+            .unwrap_or(1);
+
+        self.config.slices.min(max_by_size).max(1)
+    }
+
+    /// Share of each leg's notional executed in every round (sums to 1)
+    fn round_weights(&self, slices: usize) -> Vec<Decimal> {
+        let mut rng = rand::thread_rng();
+
+        let raw: Vec<f64> = match self.config.mode {
+            ExecutionMode::Single | ExecutionMode::Twap => vec![1.0; slices],
+            ExecutionMode::Randomized => (0..slices).map(|_| rng.gen_range(0.5..1.5)).collect(),
+        };
+        let total: f64 = raw.iter().sum();
+
+        raw.iter()
+            .map(|w| Decimal::from_f64(w / total).unwrap_or(Decimal::ZERO))
+            .collect()
+    }
+
+    /// Sleep for a random delay between two rounds
+    async fn pause(&self) {
+        let delay = rand::thread_rng()
+            .gen_range(self.config.min_slice_delay_seconds..=self.config.max_slice_delay_seconds);
+
+        if delay > 0 {
+            info!("⏳ Waiting {}s before next child order round...", delay);
+            sleep(Duration::from_secs(delay)).await;
+        }
+    }
+
+    /// Open all legs of a group in paired, staggered rounds of child orders
+    ///
+    /// # Arguments
+    /// * `close_at` - Scheduled close time for the resulting positions
+    /// * `legs` - Token, allocation and Lighter client of every leg (one leg per wallet)
+    ///
+    /// # Returns
+    /// * `GroupExecution` - Per-leg fills, confirmed positions and errors.
+    ///   Failed child orders are retried and, if allowed, moved to same-side wallets.
+    ///   Execution stops when a leg cannot be repaired, the kill switch is activated or a
+    ///   wallet's lease lapses, so callers must check `is_complete()`.
+    pub async fn open_group(
+        &self,
+        close_at: DateTime<Utc>,
+        legs: Vec<(Token, WalletAllocation, LighterClient)>,
+    ) -> GroupExecution {
+        let mut group = GroupExecution {
+            legs: legs
+                .into_iter()
+                .map(|(token, allocation, client)| LegExecution::new(token, allocation, client))
+                .collect(),
+            halt_reason: None,
+            slippage_breaches: Vec::new(),
+        };
+
+        // Legs must start flat on this market, same requirement as `open_position`
+        let flat_checks = futures::future::join_all(
+            group.legs.iter().map(|leg| leg.client.get_market_position(&leg.token))
+        ).await;
+
+        let mut unusable = Vec::new();
+        for (i, (leg, check)) in group.legs.iter_mut().zip(flat_checks).enumerate() {
+            if let Ok(Some(position)) = check {
+                if position.position_value > Decimal::ZERO {
+                    leg.error = Some(TradingError::AtomicOperationFailed(format!(
+                        "Position already open on {}",
+                        position.symbol
+                    )));
+                    unusable.push(i);
+                }
+            }
+        }
+
+        // Wallets that cannot trade hand their whole leg to the others before anything is sent
+        for idx in unusable {
+            if !self.try_redistribute(&mut group, idx) {
+                return group;
+            }
+        }
+
+        let smallest_leg = group
+            .legs
+            .iter()
+            .filter(|leg| !leg.abandoned)
+            .map(|leg| leg.allocation.usdc_amount)
+            .min()
+            .unwrap_or(Decimal::ZERO);
+        let slices = self.slice_count(smallest_leg);
+        let weights = self.round_weights(slices);
+
+        info!("🧩 Executing {} legs in {} round(s) of child orders", group.legs.len(), slices);
+
+        let wallet_ids: Vec<u8> = group.legs.iter().map(|leg| leg.allocation.wallet_id).collect();
+
+        for round in 0..slices {
+            if let Some(reason) = self.stop_reason(&wallet_ids).await {
+                warn!("🛑 Halting remaining child orders before round {}/{}: {}", round + 1, slices, reason);
+                group.halt_reason = Some(reason);
+                return group;
+            }
+
+            let is_last = round + 1 == slices;
+            let fills_before: Vec<usize> = group.legs.iter().map(|leg| leg.fills.len()).collect();
+
+            // Each round executes its share of what is left, so redistributed notional
+            // is spread over the remaining rounds and the last round clears the rest
+            let remaining_weight: Decimal = weights[round..].iter().sum();
+            let amounts: Vec<Decimal> = group
+                .legs
+                .iter()
+                .map(|leg| {
+                    if leg.abandoned || is_last || remaining_weight.is_zero() {
+                        leg.remaining_usdc()
+                    } else {
+                        leg.remaining_usdc() * weights[round] / remaining_weight
+                    }
+                })
+                .collect();
+
+            let round_futures = group.legs.iter().zip(amounts.iter()).map(|(leg, &amount)| {
+                let side = leg.allocation.side;
+                let token = &leg.token;
+                let client = &leg.client;
+                let skip = leg.abandoned || amount <= Decimal::ZERO;
+
+                async move {
+                    if skip {
+                        return None;
+                    }
+                    Some(client.execute_order_slice(token, side, amount).await)
+                }
+            });
+
+            let results = futures::future::join_all(round_futures).await;
+            let mut failed = Vec::new();
+
+            for (i, (leg, result)) in group.legs.iter_mut().zip(results).enumerate() {
+                match result {
+                    Some(Ok(fill)) => {
+                        leg.filled_usdc += fill.amount_usdc;
+                        leg.fills.push(fill);
+                    }
+                    Some(Err(e)) => {
+                        error!("❌ Child order {}/{} failed for wallet #{}: {}", round + 1, slices, leg.allocation.wallet_id, e);
+                        leg.error = Some(e);
+                        failed.push(i);
+                    }
+                    None => {}
+                }
+            }
+
+            for idx in failed {
+                if !self.repair_leg(&mut group, idx, amounts[idx]).await {
+                    warn!("🛑 Halting remaining child orders after unrepairable round {}/{}", round + 1, slices);
+                    return group;
+                }
+            }
+
+            // Rounds are paired, so stopping after a breach leaves a smaller balanced group
+            let slippage_halt = self.check_slippage(&mut group, &fills_before) && !is_last;
+            if slippage_halt {
+                warn!("🛑 Halting remaining child orders after slippage breach in round {}/{}", round + 1, slices);
+                group.truncate_to_filled();
+            }
+
+            let net_exposure = group.net_exposure();
+            let drift = group.exposure_drift();
+            info!("   Round {}/{} done | net exposure: {:.2} USDC | drift: {:.2} USDC", round + 1, slices, net_exposure, drift);
+
+            if drift.abs() > self.max_net_exposure() {
+                group.halt_reason = Some(TradingError::AtomicOperationFailed(format!(
+                    "Exposure drift {:.2} USDC exceeds tolerance {:.2} USDC",
+                    drift, self.max_net_exposure()
+                )));
+                return group;
+            }
+
+            if slippage_halt {
+                break;
+            }
+
+            if !is_last {
+                self.pause().await;
+            }
+        }
+
+        // Confirm the final positions on the exchange
+        let confirmations = futures::future::join_all(
+            group.legs.iter().map(|leg| leg.client.get_market_position(&leg.token))
+        ).await;
+
+        for (leg, confirmation) in group.legs.iter_mut().zip(confirmations) {
+            if leg.abandoned && leg.fills.is_empty() {
+                continue;
+            }
+
+            match confirmation {
+                Ok(Some(pos)) if pos.position_value > Decimal::ZERO => {
+                    let slippage = leg
+                        .slippage_bps()
+                        .map_or("unknown".to_string(), |bps| format!("{:.1} bps", bps));
+                    info!("#{} | 🟢🟢 position opened in {} slice(s) | slippage: {}", leg.allocation.wallet_id, leg.fills.len(), slippage);
+                    leg.position = Some(leg.client.build_position(&leg.token, leg.allocation.side, close_at, &pos));
+                }
+                Ok(_) => {
+                    leg.error = Some(TradingError::ExchangeError(format!(
+                        "#{} | position on {} not found after executing all slices",
+                        leg.allocation.wallet_id,
+                        leg.token.symbol
+                    )));
+                }
+                Err(e) => leg.error = Some(e),
+            }
+        }
+
+        group
+    }
+
+    /// Record the fills of the current round that exceeded the slippage limit
+    ///
+    /// # Arguments
+    /// * `fills_before` - Number of fills of every leg before the round
+    ///
+    /// # Returns
+    /// * `true` - At least one child order of the round breached the limit
+    fn check_slippage(&self, group: &mut GroupExecution, fills_before: &[usize]) -> bool {
+        let max_slippage = Decimal::from_f64(self.config.max_slippage_bps).unwrap_or(Decimal::ZERO);
+        let mut breached = false;
+
+        for (leg, &before) in group.legs.iter().zip(fills_before) {
+            for fill in &leg.fills[before..] {
+                let Some(slippage) = fill.slippage_bps(leg.allocation.side) else {
+                    continue;
+                };
+
+                if slippage > max_slippage {
+                    warn!(
+                        "⚠️ #{} | {} filled at {} vs mid {}: slippage {:.1} bps exceeds {:.1} bps",
+                        leg.allocation.wallet_id,
+                        leg.token.symbol,
+                        fill.fill_price.unwrap_or_default(),
+                        fill.expected_price,
+                        slippage,
+                        max_slippage
+                    );
+                    group.slippage_breaches.push(SlippageBreach {
+                        wallet_id: leg.allocation.wallet_id,
+                        symbol: leg.token.get_symbol_string(Exchange::Lighter),
+                        slippage_bps: slippage,
+                    });
+                    breached = true;
+                }
+            }
+        }
+
+        breached
+    }
+
+    /// Repair a leg whose child order failed in the current round.
+    ///
+    /// 1. Retries the child order with a fresh price and nonce
+    /// 2. Otherwise moves the leg's remaining notional to same-side, same-token wallets and
+    ///    immediately sends the failed child's share from those wallets
+    ///
+    /// A child order that was accepted but could not be confirmed may still fill, so
+    /// neither is attempted for it: resending its share could fill the leg twice.
+    ///
+    /// # Returns
+    /// * `true` - The round is balanced again
+    /// * `false` - Neutrality cannot be restored, the leg keeps its error
+    async fn repair_leg(&self, group: &mut GroupExecution, idx: usize, amount: Decimal) -> bool {
+        let wallet_id = group.legs[idx].allocation.wallet_id;
+        let side = group.legs[idx].allocation.side;
+        let token = group.legs[idx].token.clone();
+
+        if let Some(e @ TradingError::OrderUnconfirmed(_)) = &group.legs[idx].error {
+            warn!("⚠️ Not repairing the leg of wallet #{}, its child order may still fill: {}", wallet_id, e);
+            return false;
+        }
+
+        for attempt in 1..=self.config.leg_retry_attempts {
+            sleep(Duration::from_secs(self.config.leg_retry_delay_seconds)).await;
+            info!("🔁 Retrying child order for wallet #{} (attempt {}/{})", wallet_id, attempt, self.config.leg_retry_attempts);
+
+            match group.legs[idx].client.execute_order_slice(&token, side, amount).await {
+                Ok(fill) => {
+                    let leg = &mut group.legs[idx];
+                    leg.filled_usdc += fill.amount_usdc;
+                    leg.fills.push(fill);
+                    leg.error = None;
+                    info!("✅ Child order for wallet #{} repaired on retry {}", wallet_id, attempt);
+                    return true;
+                }
+                Err(e @ TradingError::OrderUnconfirmed(_)) => {
+                    warn!("⚠️ Retry {}/{} for wallet #{} may still fill, not repairing the leg further: {}", attempt, self.config.leg_retry_attempts, wallet_id, e);
+                    group.legs[idx].error = Some(e);
+                    return false;
+                }
+                Err(e) => {
+                    warn!("⚠️ Retry {}/{} failed for wallet #{}: {}", attempt, self.config.leg_retry_attempts, wallet_id, e);
+                    group.legs[idx].error = Some(e);
+                }
+            }
+        }
+
+        let remaining_before = group.legs[idx].remaining_usdc();
+        if !self.try_redistribute(group, idx) {
+            return false;
+        }
+
+        // Receivers cover the failed child right away so this round stays paired
+        let compensations: Vec<(usize, Decimal)> = group
+            .legs
+            .iter()
+            .enumerate()
+            .filter(|(i, leg)| *i != idx && leg.allocation.side == side && leg.token == token && !leg.abandoned)
+            .map(|(i, leg)| (i, leg.remaining_usdc()))
+            .collect();
+        let receivers_remaining: Decimal = compensations.iter().map(|(_, r)| *r).sum();
+
+        if receivers_remaining.is_zero() || remaining_before.is_zero() {
+            return true;
+        }
+
+        let futures = compensations.iter().map(|&(i, remaining)| {
+            let client = &group.legs[i].client;
+            let token = &token;
+            let share = amount * remaining / receivers_remaining;
+            async move { (i, client.execute_order_slice(token, side, share).await) }
+        });
+
+        for (i, result) in futures::future::join_all(futures).await {
+            let leg = &mut group.legs[i];
+            match result {
+                Ok(fill) => {
+                    leg.filled_usdc += fill.amount_usdc;
+                    leg.fills.push(fill);
+                }
+                Err(e) => {
+                    error!("❌ Compensating order failed for wallet #{}: {}", leg.allocation.wallet_id, e);
+                    leg.error = Some(e);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Redistribute a failed leg if the policy allows it, logging the outcome
+    fn try_redistribute(&self, group: &mut GroupExecution, idx: usize) -> bool {
+        let wallet_id = group.legs[idx].allocation.wallet_id;
+
+        if !self.config.redistribute_failed_legs {
+            return false;
+        }
+
+        match group.redistribute(idx) {
+            Some(shares) => {
+                for (i, share) in shares {
+                    info!(
+                        "🔀 Moved {:.2} USDC from wallet #{} to wallet #{}",
+                        share, wallet_id, group.legs[i].allocation.wallet_id
+                    );
+                }
+                group.legs[idx].error = None;
+                true
+            }
+            None => {
+                warn!("⚠️ Not enough same-side capacity to take over wallet #{}'s leg", wallet_id);
+                false
+            }
+        }
+    }
+
+    /// Gradually unwind a wallet group's positions on a strategy's markets with paired reduce-only rounds.
+    ///
+    /// Each round closes the same share of every position, so longs and shorts shrink
+    /// together and the starting long/short ratio is preserved. The final remainder is
+    /// left for the caller to flatten, which also covers any error returned here.
+    ///
+    /// # Arguments
+    /// * `tokens` - Tokens whose markets are unwound (positions on other markets are untouched)
+    /// * `clients` - Wallet IDs with their Lighter clients, in the strategy's wallet order
+    ///
+    /// # Returns
+    /// * `Ok(())` - All slice rounds were executed (remainder still open)
+    /// * `Err(TradingError)` - A round failed, exposure drift exceeded tolerance, the kill switch is active or the strategy's lease lapsed
+    pub async fn unwind_group(&self, tokens: &[Token], clients: Vec<(u8, LighterClient)>) -> Result<(), TradingError> {
+        let slices = match self.config.mode {
+            ExecutionMode::Single => 1,
+            _ => self.config.slices,
+        };
+        let market_ids: Vec<i32> = tokens.iter().map(|t| t.get_market_index(Exchange::Lighter)).collect();
+        let mut initial_net: Option<Decimal> = None;
+
+        // The strategy belongs to the holder of its first wallet's lease
+        let owner: Vec<u8> = clients.iter().map(|(wallet_id, _)| *wallet_id).take(1).collect();
+
+        for round in 0..slices.saturating_sub(1) {
+            if let Some(reason) = self.stop_reason(&owner).await {
+                return Err(reason);
+            }
+
+            let positions: Vec<Vec<LighterPosition>> = futures::future::try_join_all(
+                clients.iter().map(|(_, client)| client.get_active_positions())
+            )
+            .await?
+            .into_iter()
+            .map(|wallet_positions| {
+                wallet_positions
+                    .into_iter()
+                    .filter(|p| market_ids.contains(&p.market_id))
+                    .collect()
+            })
+            .collect();
+
+            let net_exposure: Decimal = positions
+                .iter()
+                .flatten()
+                .map(|p| Decimal::from(p.sign) * p.position_value)
+                .sum();
+
+            // Every round should leave the same long/short ratio as at the start
+            let initial_net = *initial_net.get_or_insert(net_exposure);
+            let expected_net = initial_net * Decimal::from(slices - round) / Decimal::from(slices);
+            let drift = net_exposure - expected_net;
+
+            if drift.abs() > self.max_net_exposure() {
+                return Err(TradingError::AtomicOperationFailed(format!(
+                    "Exposure drift {:.2} USDC exceeds tolerance {:.2} USDC while unwinding",
+                    drift, self.max_net_exposure()
+                )));
+            }
+
+            // Close an equal share of what is left: 1/n, then 1/(n-1), ...
+            let fraction = Decimal::ONE / Decimal::from(slices - round);
+            info!("🧩 Unwind round {}/{} | closing {:.2}% of remaining size", round + 1, slices, fraction * Decimal::from(100));
+
+            let reduce_futures = clients.iter().zip(positions.iter()).map(|((_, client), wallet_positions)| {
+                async move {
+                    for position in wallet_positions {
+                        client.reduce_position_slice(position, fraction).await?;
+                    }
+                    Ok::<(), TradingError>(())
+                }
+            });
+
+            futures::future::try_join_all(reduce_futures).await?;
+            self.pause().await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        config::{KillSwitchConfig, LeaseConfig},
+        model::token::SupportedToken,
+        perp::lighter::{client::tests::offline_client, models::tests::fill},
+        storage::Storage,
+    };
+    use rust_decimal_macros::dec;
+
+    pub(crate) async fn scheduler(config: ExecutionConfig) -> ExecutionScheduler {
+        let storage = Storage::memory();
+        let kill_switch = KillSwitch::new(KillSwitchConfig::default(), storage.risk.clone()).await.unwrap();
+        let wallet_leases = WalletLeases::new(LeaseConfig::default(), Vec::new(), storage.leases);
+        ExecutionScheduler::new(config, kill_switch, wallet_leases)
+    }
+
+    /// ETH leg of `wallet_id` that filled `filled_usdc` of `usdc_amount`, in a wallet that can carry `capacity_usdc`
+    fn leg(wallet_id: u8, side: PositionSide, usdc_amount: Decimal, capacity_usdc: Decimal, filled_usdc: Decimal) -> LegExecution {
+        let allocation = WalletAllocation {
+            wallet_id,
+            side,
+            usdc_amount,
+            percentage: Decimal::ZERO,
+            capacity_usdc,
+            leverage: 2.0,
+        };
+
+        LegExecution {
+            filled_usdc,
+            ..LegExecution::new(Token::new(SupportedToken::ETH), allocation, offline_client(wallet_id))
+        }
+    }
+
+    fn group(legs: Vec<LegExecution>) -> GroupExecution {
+        GroupExecution {
+            legs,
+            halt_reason: None,
+            slippage_breaches: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn legs_are_split_into_slices_no_smaller_than_the_minimum() {
+        let twap = scheduler(ExecutionConfig {
+            mode: ExecutionMode::Twap,
+            slices: 5,
+            min_slice_usdc: 100.0,
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(twap.slice_count(dec!(1000)), 5);
+        assert_eq!(twap.slice_count(dec!(250)), 2);
+        assert_eq!(twap.slice_count(dec!(50)), 1);
+
+        let single = scheduler(ExecutionConfig { mode: ExecutionMode::Single, ..Default::default() }).await;
+        assert_eq!(single.slice_count(dec!(1000)), 1);
+    }
+
+    #[tokio::test]
+    async fn rounds_split_every_leg_into_shares_that_sum_to_one() {
+        let twap = scheduler(ExecutionConfig { mode: ExecutionMode::Twap, ..Default::default() }).await;
+        assert_eq!(twap.round_weights(4), vec![dec!(0.25); 4]);
+
+        let randomized = scheduler(ExecutionConfig { mode: ExecutionMode::Randomized, ..Default::default() }).await;
+        let weights = randomized.round_weights(6);
+        assert_eq!(weights.len(), 6);
+        assert!(weights.iter().all(|w| *w > Decimal::ZERO));
+        assert!((weights.iter().sum::<Decimal>() - Decimal::ONE).abs() < dec!(0.000001));
+    }
+
+    #[test]
+    fn paired_rounds_keep_a_delta_neutral_group_flat() {
+        // Half of every leg filled: the longs match the shorts
+        let mut paired = group(vec![
+            leg(1, PositionSide::Long, dec!(1000), dec!(2000), dec!(500)),
+            leg(2, PositionSide::Short, dec!(600), dec!(2000), dec!(300)),
+            leg(3, PositionSide::Short, dec!(400), dec!(2000), dec!(200)),
+        ]);
+        assert_eq!(paired.net_exposure(), Decimal::ZERO);
+        assert_eq!(paired.exposure_drift(), Decimal::ZERO);
+
+        // A long child filled without its short pair
+        paired.legs[0].filled_usdc = dec!(750);
+        assert_eq!(paired.net_exposure(), dec!(250));
+        assert_eq!(paired.exposure_drift(), dec!(250));
+    }
+
+    #[test]
+    fn failed_leg_is_redistributed_in_proportion_to_spare_capacity() {
+        let mut execution = group(vec![
+            leg(1, PositionSide::Long, dec!(1000), dec!(2000), dec!(400)),
+            leg(2, PositionSide::Long, dec!(500), dec!(1100), Decimal::ZERO),
+            leg(3, PositionSide::Long, dec!(500), dec!(800), Decimal::ZERO),
+            leg(4, PositionSide::Short, dec!(2000), dec!(5000), Decimal::ZERO),
+        ]);
+
+        // 600 left, split 2:1 between the 600 and 300 spare of the other longs
+        let shares = execution.redistribute(0).unwrap();
+        assert_eq!(shares, vec![(1, dec!(400)), (2, dec!(200))]);
+        assert_eq!(execution.legs[1].allocation.usdc_amount, dec!(900));
+        assert_eq!(execution.legs[2].allocation.usdc_amount, dec!(700));
+        assert_eq!(execution.legs[3].allocation.usdc_amount, dec!(2000));
+
+        // The failed leg keeps what it filled and is not expected to fill more
+        assert_eq!(execution.legs[0].allocation.usdc_amount, dec!(400));
+        assert!(execution.legs[0].abandoned);
+    }
+
+    #[test]
+    fn failed_leg_is_not_redistributed_without_enough_capacity() {
+        let mut execution = group(vec![
+            leg(1, PositionSide::Long, dec!(1000), dec!(2000), Decimal::ZERO),
+            leg(2, PositionSide::Long, dec!(500), dec!(900), Decimal::ZERO),
+            leg(3, PositionSide::Short, dec!(1500), dec!(5000), Decimal::ZERO),
+        ]);
+
+        assert!(execution.redistribute(0).is_none());
+        assert_eq!(execution.legs[0].allocation.usdc_amount, dec!(1000));
+        assert_eq!(execution.legs[1].allocation.usdc_amount, dec!(500));
+        assert!(!execution.legs[0].abandoned);
+    }
+
+    #[tokio::test]
+    async fn leg_with_an_unconfirmed_order_is_not_repaired() {
+        let scheduler = scheduler(ExecutionConfig::default()).await;
+        let mut execution = group(vec![
+            leg(1, PositionSide::Long, dec!(1000), dec!(2000), Decimal::ZERO),
+            leg(2, PositionSide::Long, dec!(500), dec!(5000), Decimal::ZERO),
+            leg(3, PositionSide::Short, dec!(1500), dec!(5000), Decimal::ZERO),
+        ]);
+        execution.legs[0].error = Some(TradingError::OrderUnconfirmed("0xa".to_string()));
+
+        // Its order may still fill, so its share is neither resent nor moved
+        assert!(!scheduler.repair_leg(&mut execution, 0, dec!(250)).await);
+        assert!(matches!(execution.legs[0].error, Some(TradingError::OrderUnconfirmed(_))));
+        assert!(!execution.legs[0].abandoned);
+        assert_eq!(execution.legs[1].allocation.usdc_amount, dec!(500));
+    }
+
+    #[test]
+    fn slippage_is_weighted_by_filled_notional() {
+        let mut execution = group(vec![
+            leg(1, PositionSide::Long, dec!(1000), dec!(2000), dec!(1000)),
+            leg(2, PositionSide::Short, dec!(1000), dec!(2000), dec!(1000)),
+        ]);
+        // 10 bps on 750 and 30 bps on 250, plus a fill of unknown price
+        execution.legs[0].fills = vec![
+            fill(dec!(750), dec!(3000), Some(dec!(3003))),
+            fill(dec!(250), dec!(3000), Some(dec!(3009))),
+            fill(dec!(500), dec!(3000), None),
+        ];
+        // Short sold 5 bps below the mid
+        execution.legs[1].fills = vec![fill(dec!(1000), dec!(3000), Some(dec!(2998.5)))];
+
+        assert_eq!(execution.legs[0].slippage_bps(), Some(dec!(15)));
+        assert_eq!(execution.legs[1].slippage_bps(), Some(dec!(5)));
+        assert_eq!(execution.slippage_bps(), Some(dec!(10)));
+        assert_eq!(execution.max_leg_slippage_bps(), Some(dec!(15)));
+    }
+}
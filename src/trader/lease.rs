@@ -0,0 +1,205 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::time::{sleep, Duration as TokioDuration};
+
+use crate::{config::LeaseConfig, error::TradingError, storage::traits::LeaseStore};
+
+/// Wallets leased by this instance as of the last renewal
+#[derive(Debug, Default)]
+struct HeldLeases {
+    wallet_ids: Vec<u8>,
+    /// When the leases lapse unless they are renewed
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// Leases on the wallets this instance trades
+///
+/// Several instances may share one database and the same wallets: each wallet is only planned
+/// with by the instance holding its lease, and each strategy is monitored by the instance
+/// holding the lease on its first wallet. Leases are renewed by a heartbeat, and
+/// if renewals keep failing until they lapse, the instance acts as if it held no wallet since
+/// another instance may have claimed them by then.
+#[derive(Clone)]
+pub struct WalletLeases {
+    config: LeaseConfig,
+    holder: String,
+    wallet_ids: Vec<u8>,
+    storage: Arc<dyn LeaseStore>,
+    held: Arc<Mutex<HeldLeases>>,
+}
+
+impl WalletLeases {
+    pub fn new(config: LeaseConfig, wallet_ids: Vec<u8>, storage: Arc<dyn LeaseStore>) -> Self {
+        let holder = config.holder();
+
+        Self {
+            config,
+            holder,
+            wallet_ids,
+            storage,
+            held: Arc::new(Mutex::new(HeldLeases::default())),
+        }
+    }
+
+    /// Name this instance holds its leases under
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Wallets this instance may trade: all of them if leasing is disabled, otherwise
+    /// those whose lease has not lapsed
+    pub fn held(&self) -> Vec<u8> {
+        if !self.config.enabled {
+            return self.wallet_ids.clone();
+        }
+
+        let held = self.lock();
+        match held.expires_at {
+            Some(expires_at) if expires_at > Utc::now() => held.wallet_ids.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether this instance owns a strategy over `wallet_ids`
+    ///
+    /// A strategy belongs to the holder of its first wallet's lease, so exactly one instance
+    /// monitors and closes it even when its other wallets are leased elsewhere.
+    pub fn owns(&self, wallet_ids: &[u8]) -> bool {
+        wallet_ids.first().is_some_and(|id| self.held().contains(id))
+    }
+
+    /// Renew the leases this instance holds, then claim free wallets up to `max_wallets`
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - Wallets held after the renewal
+    /// * `Err(TradingError)` - If the leases cannot be read or written
+    pub async fn renew(&self) -> Result<Vec<u8>, TradingError> {
+        if !self.config.enabled {
+            return Ok(self.wallet_ids.clone());
+        }
+
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.config.ttl_seconds as i64);
+        let previous = self.lock().wallet_ids.clone();
+
+        let mut held = self.storage.claim_wallets(&self.holder, &previous, now, expires_at).await?;
+
+        let room = self.config.max_wallets.unwrap_or(usize::MAX).saturating_sub(held.len());
+        if room > 0 {
+            // Leases left by a previous run of this instance are claimed back like free wallets
+            let taken: HashSet<u8> = self
+                .storage
+                .get_wallet_leases()
+                .await?
+                .into_iter()
+                .filter(|lease| lease.holder != self.holder && lease.is_live(now))
+                .map(|lease| lease.wallet_id)
+                .collect();
+            let free: Vec<u8> = self
+                .wallet_ids
+                .iter()
+                .copied()
+                .filter(|id| !taken.contains(id) && !held.contains(id))
+                .take(room)
+                .collect();
+
+            if !free.is_empty() {
+                held.extend(self.storage.claim_wallets(&self.holder, &free, now, expires_at).await?);
+                held.sort_unstable();
+            }
+        }
+
+        let gained: Vec<u8> = held.iter().filter(|id| !previous.contains(id)).copied().collect();
+        let lost: Vec<u8> = previous.iter().filter(|id| !held.contains(id)).copied().collect();
+        if !gained.is_empty() {
+            info!("🔐 Leased wallets {:?} as {}", gained, self.holder);
+        }
+        if !lost.is_empty() {
+            warn!("🔓 Lost the leases on wallets {:?} to another instance", lost);
+        }
+
+        *self.lock() = HeldLeases { wallet_ids: held.clone(), expires_at: Some(expires_at) };
+        Ok(held)
+    }
+
+    /// Renew the leases every `heartbeat_seconds` until the process exits
+    pub async fn heartbeat(self) {
+        if !self.config.enabled {
+            return;
+        }
+
+        loop {
+            sleep(TokioDuration::from_secs(self.config.heartbeat_seconds)).await;
+
+            if let Err(e) = self.renew().await {
+                error!("❌ Failed to renew wallet leases: {}", e);
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HeldLeases> {
+        self.held.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+
+    fn leases(storage: &Storage, instance_id: &str, max_wallets: Option<usize>) -> WalletLeases {
+        let config = LeaseConfig {
+            instance_id: Some(instance_id.to_string()),
+            max_wallets,
+            ..LeaseConfig::default()
+        };
+        WalletLeases::new(config, vec![1, 2, 3], storage.leases.clone())
+    }
+
+    #[tokio::test]
+    async fn instances_shard_the_wallets_and_own_strategies_by_their_first_wallet() {
+        let storage = Storage::memory();
+        let (a, b) = (leases(&storage, "a", Some(2)), leases(&storage, "b", Some(2)));
+
+        assert_eq!(a.renew().await.unwrap(), vec![1, 2]);
+        assert_eq!(b.renew().await.unwrap(), vec![3]);
+        assert_eq!(a.renew().await.unwrap(), vec![1, 2]);
+        assert_eq!(b.held(), vec![3]);
+
+        assert!(a.owns(&[1, 3]));
+        assert!(!b.owns(&[1, 3]));
+        assert!(b.owns(&[3, 1]));
+        assert!(!a.owns(&[]));
+    }
+
+    #[tokio::test]
+    async fn lapsed_leases_are_claimed_and_own_leases_are_taken_back() {
+        let storage = Storage::memory();
+        let now = Utc::now();
+        storage.leases.claim_wallets("b", &[1], now - Duration::minutes(10), now - Duration::minutes(5)).await.unwrap();
+        storage.leases.claim_wallets("a", &[2], now, now + Duration::minutes(5)).await.unwrap();
+        storage.leases.claim_wallets("c", &[3], now, now + Duration::minutes(5)).await.unwrap();
+
+        // A restarted "a" gets wallet 2 back and takes over the lapsed wallet 1, but not the live wallet 3
+        let a = leases(&storage, "a", None);
+        assert!(a.held().is_empty());
+        assert_eq!(a.renew().await.unwrap(), vec![1, 2]);
+        assert!(!a.owns(&[3]));
+    }
+
+    #[tokio::test]
+    async fn every_wallet_is_held_when_leasing_is_disabled() {
+        let storage = Storage::memory();
+        let config = LeaseConfig { enabled: false, ..LeaseConfig::default() };
+        let leases = WalletLeases::new(config, vec![1, 2, 3], storage.leases.clone());
+
+        assert_eq!(leases.held(), vec![1, 2, 3]);
+        assert_eq!(leases.renew().await.unwrap(), vec![1, 2, 3]);
+        assert!(leases.owns(&[2]));
+        assert!(storage.leases.get_wallet_leases().await.unwrap().is_empty());
+    }
+}
@@ -1,4 +1,9 @@
 pub mod wallet;
 pub mod client;
 pub mod strategy;
-pub mod monitor;
\ No newline at end of file
+pub mod monitor;
+pub mod execution;
+pub mod pair;pub mod scheduler;
+pub mod estimator;
+pub mod reconcile;
+pub mod lease;
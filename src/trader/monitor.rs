@@ -1,31 +1,30 @@
 #![allow(unused)]
 
 use crate::{
-    error::TradingError, storage::{storage_position::PositionStorage, storage_strategy::StrategyStorage}, trader::{
+    error::TradingError, storage::{traits::{PositionStore, StrategyStore}, Storage}, trader::{
         wallet::Wallet,
     }
 };
 
-use std::collections::HashMap;
-use sqlx::PgPool;
+use std::{collections::HashMap, sync::Arc};
 
 
 
 /// Strategy monitor that checks and closes strategies
 pub struct StrategyMonitor {
-    position_storage: PositionStorage,
-    strategy_storage: StrategyStorage,
+    position_storage: Arc<dyn PositionStore>,
+    strategy_storage: Arc<dyn StrategyStore>,
     wallets: HashMap<u8, Wallet>,
 }
 
 impl StrategyMonitor {
     /// Create a new strategy monitor
     pub async fn new(
-        pool: PgPool,
+        storage: &Storage,
         wallets: Vec<Wallet>,
     ) -> Result<Self, TradingError> {
-        let position_storage = PositionStorage::new(pool.clone()).await?;
-        let strategy_storage = StrategyStorage::new(pool).await?;
+        let position_storage = storage.positions.clone();
+        let strategy_storage = storage.strategies.clone();
         
         let wallets_map = wallets.into_iter()
             .map(|w| (w.id, w))
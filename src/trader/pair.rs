@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    config::PairConfig,
+    error::TradingError,
+    model::{token::Token, Exchange, PositionSide},
+    perp::lighter::client::LighterClient,
+    trader::strategy::WalletAllocation,
+};
+
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal};
+
+/// Close prices keyed by candle timestamp
+type CloseSeries = BTreeMap<i64, f64>;
+
+/// A long token hedged by a basket of tokens whose returns are correlated with it
+pub struct CorrelatedPair {
+    pub long_token: Token,
+    /// Candidate basket, most correlated token first
+    pub basket: Vec<Token>,
+    /// Correlation of the long token's returns with the equal-weighted basket
+    pub correlation: f64,
+    long_closes: CloseSeries,
+    basket_closes: Vec<CloseSeries>,
+}
+
+/// Token assignment and hedge ratio of a correlated pair for one set of allocations
+pub struct PairHedge {
+    /// Token traded by each allocation (same order as the allocations)
+    pub tokens: Vec<Token>,
+    /// Basket tokens that received at least one short wallet
+    pub basket: Vec<Token>,
+    /// Short notional per unit of long notional
+    pub hedge_ratio: Decimal,
+}
+
+impl CorrelatedPair {
+    /// Build a basket of the candidates most correlated with `long_token`
+    ///
+    /// # Arguments
+    /// * `client` - Any Lighter client, used to fetch candles
+    /// * `long_token` - Token bought by the pair
+    /// * `candidates` - Tokens that may be shorted against it
+    /// * `config` - Pair settings (candles, basket size, minimum correlation)
+    ///
+    /// # Returns
+    /// * `Ok(Some(CorrelatedPair))` - A basket meeting `min_correlation`
+    /// * `Ok(None)` - No candidate is correlated enough
+    /// * `Err(TradingError)` - Candles for the long token could not be fetched
+    pub async fn find(
+        client: &LighterClient,
+        long_token: &Token,
+        candidates: &[Token],
+        config: &PairConfig,
+    ) -> Result<Option<Self>, TradingError> {
+        let long_closes = fetch_closes(client, long_token, config).await?;
+        let mut ranked: Vec<(f64, Token, CloseSeries)> = Vec::new();
+
+        for candidate in candidates.iter().filter(|t| *t != long_token) {
+            let closes = match fetch_closes(client, candidate, config).await {
+                Ok(closes) => closes,
+                Err(e) => {
+                    warn!("⚠️ Skipping {} as pair candidate: {}", candidate.symbol, e);
+                    continue;
+                }
+            };
+
+            let (long_returns, candidate_returns) = aligned_returns(&long_closes, &[(1.0, &closes)]);
+            if let Some((_, correlation)) = beta_and_correlation(&long_returns, &candidate_returns) {
+                info!("   {} vs {} | correlation: {:.3}", long_token.symbol, candidate.symbol, correlation);
+                if correlation >= config.min_correlation {
+                    ranked.push((correlation, candidate.clone(), closes));
+                }
+            }
+        }
+
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(config.basket_size);
+
+        if ranked.is_empty() {
+            return Ok(None);
+        }
+
+        let weighted: Vec<(f64, &CloseSeries)> = ranked.iter().map(|(_, _, closes)| (1.0, closes)).collect();
+        let (long_returns, basket_returns) = aligned_returns(&long_closes, &weighted);
+        let correlation = match beta_and_correlation(&long_returns, &basket_returns) {
+            Some((_, correlation)) if correlation >= config.min_correlation => correlation,
+            _ => return Ok(None),
+        };
+
+        let (basket, basket_closes) = ranked.into_iter().map(|(_, token, closes)| (token, closes)).unzip();
+
+        Ok(Some(Self {
+            long_token: long_token.clone(),
+            basket,
+            correlation,
+            long_closes,
+            basket_closes,
+        }))
+    }
+
+    /// Assign basket tokens to the short allocations and scale both sides to the hedge ratio.
+    ///
+    /// Short wallets are spread over the basket round-robin, one token per wallet so no
+    /// wallet sends two orders at once. Beta is computed for the resulting basket weights,
+    /// and the larger side is scaled down so that shorts = beta × longs without exceeding
+    /// any wallet's capacity.
+    ///
+    /// # Arguments
+    /// * `allocations` - Balanced allocations, scaled in place
+    ///
+    /// # Returns
+    /// * `Ok(PairHedge)` - Token per allocation and the hedge ratio
+    /// * `Err(TradingError)` - If there are no shorts or beta is not positive
+    pub fn hedge(&self, allocations: &mut [WalletAllocation]) -> Result<PairHedge, TradingError> {
+        let mut tokens = Vec::with_capacity(allocations.len());
+        let mut weights = vec![0.0; self.basket.len()];
+        let mut short_count = 0;
+
+        for allocation in allocations.iter() {
+            match allocation.side {
+                PositionSide::Long => tokens.push(self.long_token.clone()),
+                PositionSide::Short => {
+                    let k = short_count % self.basket.len();
+                    weights[k] += allocation.usdc_amount.to_f64().unwrap_or(0.0);
+                    tokens.push(self.basket[k].clone());
+                    short_count += 1;
+                }
+            }
+        }
+
+        let total_weight: f64 = weights.iter().sum();
+        if total_weight <= 0.0 {
+            return Err(TradingError::InvalidInput("Correlated pair has no short allocations".into()));
+        }
+
+        let used: Vec<usize> = (0..self.basket.len()).filter(|&k| weights[k] > 0.0).collect();
+        let weighted: Vec<(f64, &CloseSeries)> = used
+            .iter()
+            .map(|&k| (weights[k] / total_weight, &self.basket_closes[k]))
+            .collect();
+
+        let (long_returns, basket_returns) = aligned_returns(&self.long_closes, &weighted);
+        let (beta, _) = beta_and_correlation(&long_returns, &basket_returns).ok_or_else(|| {
+            TradingError::MarketDataUnavailable(format!("Not enough candles to compute beta for {}", self.long_token.symbol))
+        })?;
+
+        if beta <= 0.0 {
+            return Err(TradingError::InvalidInput(format!(
+                "Beta of {} against its basket is not positive: {:.3}",
+                self.long_token.symbol, beta
+            )));
+        }
+
+        let hedge_ratio = Decimal::from_f64(beta)
+            .ok_or_else(|| TradingError::InvalidInput(format!("Invalid beta: {}", beta)))?
+            .round_dp(4);
+
+        // Only ever scale down, so no wallet is pushed past its capacity
+        let (scaled_side, factor) = if hedge_ratio >= Decimal::ONE {
+            (PositionSide::Long, Decimal::ONE / hedge_ratio)
+        } else {
+            (PositionSide::Short, hedge_ratio)
+        };
+
+        for allocation in allocations.iter_mut().filter(|a| a.side == scaled_side) {
+            allocation.usdc_amount *= factor;
+            allocation.percentage *= factor;
+        }
+
+        Ok(PairHedge {
+            tokens,
+            basket: used.iter().map(|&k| self.basket[k].clone()).collect(),
+            hedge_ratio,
+        })
+    }
+}
+
+/// Fetch close prices for a token's market
+async fn fetch_closes(client: &LighterClient, token: &Token, config: &PairConfig) -> Result<CloseSeries, TradingError> {
+    let candles = client
+        .get_candles(token, &config.candle_resolution, config.lookback_candles)
+        .await?;
+
+    Ok(candles.into_iter().map(|c| (c.timestamp, c.close)).collect())
+}
+
+/// Log returns of the long token and of a weighted basket over their common timestamps
+fn aligned_returns(long: &CloseSeries, basket: &[(f64, &CloseSeries)]) -> (Vec<f64>, Vec<f64>) {
+    let timestamps: Vec<i64> = long
+        .keys()
+        .filter(|ts| basket.iter().all(|(_, closes)| closes.contains_key(ts)))
+        .cloned()
+        .collect();
+
+    let mut long_returns = Vec::new();
+    let mut basket_returns = Vec::new();
+
+    for window in timestamps.windows(2) {
+        let (prev, next) = (window[0], window[1]);
+
+        long_returns.push((long[&next] / long[&prev]).ln());
+        basket_returns.push(
+            basket
+                .iter()
+                .map(|(weight, closes)| weight * (closes[&next] / closes[&prev]).ln())
+                .sum(),
+        );
+    }
+
+    (long_returns, basket_returns)
+}
+
+/// Beta of `x` regressed on `y`, and the correlation between them
+fn beta_and_correlation(x: &[f64], y: &[f64]) -> Option<(f64, f64)> {
+    let n = x.len().min(y.len());
+    if n < 2 {
+        return None;
+    }
+
+    let mean_x = x[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = y[..n].iter().sum::<f64>() / n as f64;
+
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        let (dx, dy) = (x[i] - mean_x, y[i] - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x <= 0.0 || var_y <= 0.0 || !cov.is_finite() {
+        return None;
+    }
+
+    Some((cov / var_y, cov / (var_x.sqrt() * var_y.sqrt())))
+}
+
+/// Symbols of a list of tokens on Lighter
+pub fn lighter_symbols(tokens: &[Token]) -> Vec<String> {
+    tokens.iter().map(|t| t.get_symbol_string(Exchange::Lighter)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::token::SupportedToken;
+    use rust_decimal_macros::dec;
+
+    /// Closes whose log returns are `scale` times the given returns
+    fn closes(returns: &[f64], scale: f64) -> CloseSeries {
+        let mut log_price = 0.0;
+        let mut series = BTreeMap::from([(0, 100.0)]);
+        for (i, r) in returns.iter().enumerate() {
+            log_price += scale * r;
+            series.insert(i as i64 + 1, 100.0 * f64::exp(log_price));
+        }
+        series
+    }
+
+    /// ETH against a SOL/HYPE basket, with ETH moving `beta` times as much as the basket
+    fn pair(beta: f64) -> CorrelatedPair {
+        let returns = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02, -0.005];
+        CorrelatedPair {
+            long_token: Token::new(SupportedToken::ETH),
+            basket: vec![Token::new(SupportedToken::SOL), Token::new(SupportedToken::HYPE)],
+            correlation: 1.0,
+            long_closes: closes(&returns, beta),
+            basket_closes: vec![closes(&returns, 1.0), closes(&returns, 1.0)],
+        }
+    }
+
+    fn allocation(wallet_id: u8, side: PositionSide, usdc_amount: Decimal) -> WalletAllocation {
+        WalletAllocation {
+            wallet_id,
+            side,
+            usdc_amount,
+            percentage: Decimal::ONE_HUNDRED,
+            capacity_usdc: usdc_amount,
+            leverage: 2.0,
+        }
+    }
+
+    #[test]
+    fn beta_is_the_regression_slope_and_correlation_its_sign() {
+        let y = [0.01, -0.02, 0.015, 0.005];
+
+        let (beta, correlation) = beta_and_correlation(&y.map(|r| 2.0 * r), &y).unwrap();
+        assert!((beta - 2.0).abs() < 1e-9);
+        assert!((correlation - 1.0).abs() < 1e-9);
+
+        let (beta, correlation) = beta_and_correlation(&y.map(|r| -r), &y).unwrap();
+        assert!((beta + 1.0).abs() < 1e-9);
+        assert!((correlation + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_beta_without_enough_varying_returns() {
+        assert!(beta_and_correlation(&[0.01], &[0.02]).is_none());
+        assert!(beta_and_correlation(&[0.01, 0.02, 0.03], &[0.01, 0.01, 0.01]).is_none());
+    }
+
+    #[test]
+    fn longs_are_scaled_down_when_beta_is_above_one() {
+        let mut allocations = vec![
+            allocation(1, PositionSide::Long, dec!(1000)),
+            allocation(2, PositionSide::Short, dec!(600)),
+            allocation(3, PositionSide::Short, dec!(400)),
+        ];
+
+        let hedge = pair(2.0).hedge(&mut allocations).unwrap();
+        assert_eq!(hedge.hedge_ratio, dec!(2));
+        let (eth, sol, hype) = (Token::new(SupportedToken::ETH), Token::new(SupportedToken::SOL), Token::new(SupportedToken::HYPE));
+        assert_eq!(hedge.tokens, vec![eth, sol.clone(), hype.clone()]);
+        assert_eq!(hedge.basket, vec![sol, hype]);
+
+        // Shorts = 2 × longs, without growing any wallet
+        assert_eq!(allocations.iter().map(|a| a.usdc_amount).collect::<Vec<_>>(), vec![dec!(500), dec!(600), dec!(400)]);
+    }
+
+    #[test]
+    fn shorts_are_scaled_down_when_beta_is_below_one() {
+        let mut allocations = vec![
+            allocation(1, PositionSide::Long, dec!(1000)),
+            allocation(2, PositionSide::Short, dec!(1000)),
+        ];
+
+        let hedge = pair(0.5).hedge(&mut allocations).unwrap();
+        assert_eq!(hedge.hedge_ratio, dec!(0.5));
+        assert_eq!(hedge.basket, vec![Token::new(SupportedToken::SOL)]);
+        assert_eq!(allocations[0].usdc_amount, dec!(1000));
+        assert_eq!(allocations[1].usdc_amount, dec!(500));
+        assert_eq!(allocations[1].percentage, dec!(50));
+    }
+
+    #[test]
+    fn no_hedge_without_shorts() {
+        let mut allocations = vec![allocation(1, PositionSide::Long, dec!(1000))];
+        assert!(pair(1.0).hedge(&mut allocations).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::{
+    model::{Position, PositionSide},
+    perp::lighter::models::LighterPosition,
+};
+
+/// Kind of difference between the database and the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// Live position that no active strategy owns
+    Orphan,
+    /// Stored position of an active strategy that is flat on the exchange
+    Missing,
+    /// Live position whose entry notional differs from the stored size
+    Size,
+    /// Live position on the opposite side of the stored one
+    Side,
+}
+
+impl std::fmt::Display for MismatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MismatchKind::Orphan => write!(f, "orphan on exchange"),
+            MismatchKind::Missing => write!(f, "missing on exchange"),
+            MismatchKind::Size => write!(f, "size mismatch"),
+            MismatchKind::Side => write!(f, "side mismatch"),
+        }
+    }
+}
+
+/// A market an active strategy holds on one wallet
+///
+/// Strategies opened before their positions were stored only know their wallets and
+/// markets: they keep those positions from being reported as orphans, but their side
+/// and size cannot be checked.
+#[derive(Debug, Clone)]
+pub struct ExpectedPosition {
+    pub strategy_id: String,
+    pub wallet_id: u8,
+    pub symbol: String,
+    /// Side and notional of the stored position, if any
+    pub stored: Option<(PositionSide, Decimal)>,
+}
+
+impl ExpectedPosition {
+    pub fn from_position(strategy_id: &str, position: &Position) -> Self {
+        Self {
+            strategy_id: strategy_id.to_string(),
+            wallet_id: position.wallet_id,
+            symbol: position.symbol.clone(),
+            stored: Some((position.side, position.size)),
+        }
+    }
+}
+
+/// A position currently open on the exchange
+#[derive(Debug, Clone)]
+pub struct LivePosition {
+    pub wallet_id: u8,
+    pub symbol: String,
+    pub side: PositionSide,
+    /// Entry notional (size times average entry price, in USDC)
+    pub size_usdc: Decimal,
+}
+
+impl LivePosition {
+    pub fn from_lighter(wallet_id: u8, position: &LighterPosition) -> Self {
+        Self {
+            wallet_id,
+            symbol: position.symbol.clone(),
+            side: if position.sign < 0 { PositionSide::Short } else { PositionSide::Long },
+            size_usdc: position.position.abs() * position.avg_entry_price,
+        }
+    }
+}
+
+/// One difference found by a reconciliation
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub kind: MismatchKind,
+    pub wallet_id: u8,
+    pub symbol: String,
+    /// Active strategy owning the market, if any
+    pub strategy_id: Option<String>,
+    pub expected: Option<(PositionSide, Decimal)>,
+    pub live: Option<(PositionSide, Decimal)>,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} {} | {}", self.wallet_id, self.symbol, self.kind)?;
+        if let Some((side, size)) = self.expected {
+            write!(f, " | stored: {} {:.2} USDC", side, size)?;
+        }
+        if let Some((side, size)) = self.live {
+            write!(f, " | live: {} {:.2} USDC", side, size)?;
+        }
+        if let Some(strategy_id) = &self.strategy_id {
+            write!(f, " | strategy {}", strategy_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one reconciliation
+#[derive(Debug, Clone)]
+pub struct ReconciliationReport {
+    pub checked_at: DateTime<Utc>,
+    pub wallets: usize,
+    pub strategies: usize,
+    pub live_positions: usize,
+    pub mismatches: Vec<Mismatch>,
+    /// Mismatches whose remediation succeeded
+    pub remediated: usize,
+    /// Mismatches whose remediation failed
+    pub failed: usize,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn count(&self, kind: MismatchKind) -> usize {
+        self.mismatches.iter().filter(|m| m.kind == kind).count()
+    }
+
+    /// One-line summary for logs
+    pub fn summary(&self) -> String {
+        format!(
+            "{} wallets | {} strategies | {} live positions | {} orphan | {} missing | {} size | {} side | {} remediated | {} failed",
+            self.wallets,
+            self.strategies,
+            self.live_positions,
+            self.count(MismatchKind::Orphan),
+            self.count(MismatchKind::Missing),
+            self.count(MismatchKind::Size),
+            self.count(MismatchKind::Side),
+            self.remediated,
+            self.failed
+        )
+    }
+}
+
+/// Compare the positions active strategies should hold with the live positions
+///
+/// Stored positions of the same strategy on the same wallet and market are netted
+/// before they are compared.
+///
+/// # Arguments
+/// * `expected` - Markets held by active strategies
+/// * `live` - Open positions of every wallet
+/// * `size_tolerance_pct` - Relative notional difference reported as a size mismatch
+pub fn find_mismatches(expected: &[ExpectedPosition], live: &[LivePosition], size_tolerance_pct: Decimal) -> Vec<Mismatch> {
+    // Owning strategy and net signed stored notional, per (wallet, market)
+    let mut owned: HashMap<(u8, &str), (&str, Option<Decimal>)> = HashMap::new();
+    for position in expected {
+        let entry = owned
+            .entry((position.wallet_id, position.symbol.as_str()))
+            .or_insert((position.strategy_id.as_str(), None));
+
+        if let Some((side, size)) = position.stored {
+            *entry.1.get_or_insert(Decimal::ZERO) += signed(side, size);
+        }
+    }
+
+    let mut mismatches = Vec::new();
+
+    for position in live {
+        let key = (position.wallet_id, position.symbol.as_str());
+        let live_size = Some((position.side, position.size_usdc));
+
+        let Some((strategy_id, stored)) = owned.get(&key) else {
+            mismatches.push(Mismatch {
+                kind: MismatchKind::Orphan,
+                wallet_id: position.wallet_id,
+                symbol: position.symbol.clone(),
+                strategy_id: None,
+                expected: None,
+                live: live_size,
+            });
+            continue;
+        };
+
+        let Some(stored) = stored else { continue };
+        let expected_size = Some((side_of(*stored), stored.abs()));
+
+        let kind = if side_of(*stored) != position.side {
+            Some(MismatchKind::Side)
+        } else if stored.is_zero() || (position.size_usdc - stored.abs()).abs() / stored.abs() * Decimal::ONE_HUNDRED > size_tolerance_pct {
+            Some(MismatchKind::Size)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            mismatches.push(Mismatch {
+                kind,
+                wallet_id: position.wallet_id,
+                symbol: position.symbol.clone(),
+                strategy_id: Some(strategy_id.to_string()),
+                expected: expected_size,
+                live: live_size,
+            });
+        }
+    }
+
+    for ((wallet_id, symbol), (strategy_id, stored)) in owned {
+        let Some(stored) = stored else { continue };
+        if stored.is_zero() || live.iter().any(|p| p.wallet_id == wallet_id && p.symbol == symbol) {
+            continue;
+        }
+
+        mismatches.push(Mismatch {
+            kind: MismatchKind::Missing,
+            wallet_id,
+            symbol: symbol.to_string(),
+            strategy_id: Some(strategy_id.to_string()),
+            expected: Some((side_of(stored), stored.abs())),
+            live: None,
+        });
+    }
+
+    mismatches.sort_by(|a, b| (a.wallet_id, &a.symbol).cmp(&(b.wallet_id, &b.symbol)));
+    mismatches
+}
+
+fn signed(side: PositionSide, size: Decimal) -> Decimal {
+    match side {
+        PositionSide::Long => size,
+        PositionSide::Short => -size,
+    }
+}
+
+fn side_of(signed_size: Decimal) -> PositionSide {
+    if signed_size.is_sign_negative() { PositionSide::Short } else { PositionSide::Long }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn expected(strategy_id: &str, wallet_id: u8, symbol: &str, stored: Option<(PositionSide, Decimal)>) -> ExpectedPosition {
+        ExpectedPosition { strategy_id: strategy_id.to_string(), wallet_id, symbol: symbol.to_string(), stored }
+    }
+
+    fn live(wallet_id: u8, symbol: &str, side: PositionSide, size_usdc: Decimal) -> LivePosition {
+        LivePosition { wallet_id, symbol: symbol.to_string(), side, size_usdc }
+    }
+
+    #[test]
+    fn matching_positions_are_clean() {
+        let expected = [
+            expected("s1", 1, "ETH", Some((PositionSide::Long, dec!(100)))),
+            expected("s1", 2, "ETH", Some((PositionSide::Short, dec!(100)))),
+        ];
+        let live = [
+            live(1, "ETH", PositionSide::Long, dec!(102)),
+            live(2, "ETH", PositionSide::Short, dec!(98)),
+        ];
+
+        assert!(find_mismatches(&expected, &live, dec!(5)).is_empty());
+    }
+
+    #[test]
+    fn reports_every_kind_sorted_by_wallet() {
+        let expected = [
+            expected("s1", 1, "ETH", Some((PositionSide::Long, dec!(100)))),
+            expected("s1", 2, "ETH", Some((PositionSide::Short, dec!(100)))),
+            expected("s1", 3, "ETH", Some((PositionSide::Short, dec!(100)))),
+        ];
+        let live = [
+            live(4, "SOL", PositionSide::Long, dec!(50)),
+            live(3, "ETH", PositionSide::Long, dec!(100)),
+            live(1, "ETH", PositionSide::Long, dec!(120)),
+        ];
+
+        let mismatches = find_mismatches(&expected, &live, dec!(5));
+        let kinds: Vec<(u8, MismatchKind)> = mismatches.iter().map(|m| (m.wallet_id, m.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![(1, MismatchKind::Size), (2, MismatchKind::Missing), (3, MismatchKind::Side), (4, MismatchKind::Orphan)]
+        );
+
+        assert_eq!(mismatches[1].strategy_id.as_deref(), Some("s1"));
+        assert_eq!(mismatches[1].expected, Some((PositionSide::Short, dec!(100))));
+        assert_eq!(mismatches[3].strategy_id, None);
+    }
+
+    #[test]
+    fn nets_stored_positions_of_a_market() {
+        // Two legs of the same strategy on one wallet and market net to a 40 USDC short
+        let expected = [
+            expected("s1", 1, "ETH", Some((PositionSide::Long, dec!(60)))),
+            expected("s1", 1, "ETH", Some((PositionSide::Short, dec!(100)))),
+        ];
+
+        assert!(find_mismatches(&expected, &[live(1, "ETH", PositionSide::Short, dec!(40))], dec!(5)).is_empty());
+
+        let mismatches = find_mismatches(&expected, &[], dec!(5));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].kind, MismatchKind::Missing);
+        assert_eq!(mismatches[0].expected, Some((PositionSide::Short, dec!(40))));
+    }
+
+    #[test]
+    fn markets_without_stored_positions_are_not_orphans() {
+        let expected = [expected("s1", 1, "ETH", None)];
+
+        assert!(find_mismatches(&expected, &[live(1, "ETH", PositionSide::Long, dec!(75))], dec!(5)).is_empty());
+        assert!(find_mismatches(&expected, &[], dec!(5)).is_empty());
+    }
+}
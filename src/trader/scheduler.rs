@@ -0,0 +1,384 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use rand::seq::SliceRandom;
+use tokio::time::{sleep, Duration as TokioDuration};
+
+use crate::{
+    config::SchedulerConfig,
+    error::TradingError,
+    storage::storage_strategy::StrategyMetadata,
+    trader::client::TraderClient,
+};
+
+/// Deploys wallets continuously in cohorts with staggered lifecycles
+///
+/// Monitoring and launches run as two concurrent loops. Every tick the monitor checks the
+/// open strategies of all cohorts, and the launcher opens a new cohort, when a strategy
+/// slot is free, from wallets that are idle and past their cooldown. A launch executes
+/// child orders in rounds with delays, so it never holds up risk checks on live cohorts.
+/// Cohort launches are spaced by `min_cohort_interval_seconds` so their close times do
+/// not line up. Only wallets this instance holds a lease on are used, so several
+/// instances can run against the same database.
+pub struct CohortScheduler<'a> {
+    trader: &'a TraderClient,
+    config: SchedulerConfig,
+    cooldown: Duration,
+    /// Whether the kill switch has been engaged (wallets flattened, strategies killed)
+    killed: bool,
+    last_reconciliation: Option<DateTime<Utc>>,
+    last_snapshot: Option<DateTime<Utc>>,
+}
+
+impl<'a> CohortScheduler<'a> {
+    /// Create a scheduler for the trader's wallets, using its configuration
+    pub fn new(trader: &'a TraderClient) -> Self {
+        let config = trader.config().scheduler.clone();
+        let cooldown = Duration::seconds(trader.config().trading.cooldown_seconds as i64);
+
+        Self {
+            trader,
+            config,
+            cooldown,
+            killed: false,
+            last_reconciliation: None,
+            last_snapshot: None,
+        }
+    }
+
+    /// Run the scheduler until a critical error occurs
+    ///
+    /// Strategies already active in the database are adopted as running cohorts, so a
+    /// restart resumes monitoring instead of waiting for them to finish. Wallets are leased
+    /// before anything else, and the leases are renewed in the background from then on.
+    /// If monitoring fails, the launcher finishes its current launch before the error is returned.
+    ///
+    /// # Returns
+    /// * `Err(TradingError)` - If wallets cannot be leased, strategies cannot be loaded or a status update fails
+    pub async fn run(&mut self) -> Result<(), TradingError> {
+        info!(
+            "🗓️ Starting cohort scheduler | max strategies: {} | cohort size: {}-{} | wallet cooldown: {}s",
+            self.config.max_concurrent_strategies,
+            self.config.min_cohort_size,
+            self.config.max_cohort_size,
+            self.cooldown.num_seconds()
+        );
+
+        let leases = self.trader.wallet_leases();
+        let held = leases.renew().await?;
+        info!("🔐 Holding leases on {}/{} wallets as {}", held.len(), self.trader.wallets.len(), leases.holder());
+        tokio::spawn(leases.clone().heartbeat());
+
+        info!("🔍 Checking for failed strategies from previous runs...");
+        self.trader.retry_failed_strategies().await?;
+
+        let stopping = AtomicBool::new(false);
+        let mut launcher = CohortLauncher {
+            trader: self.trader,
+            config: self.config.clone(),
+            cooldown: self.cooldown,
+            last_closed: HashMap::new(),
+            last_launch: None,
+            halted: false,
+        };
+
+        let monitoring = async {
+            let result = self.monitor().await;
+            stopping.store(true, Ordering::SeqCst);
+            result
+        };
+        let (result, ()) = tokio::join!(monitoring, launcher.run(&stopping));
+
+        result
+    }
+
+    /// Monitor the open strategies every tick until a critical error occurs
+    async fn monitor(&mut self) -> Result<(), TradingError> {
+        loop {
+            let tick = TokioDuration::from_secs(self.config.tick_seconds);
+
+            if self.kill_switch_active().await {
+                self.wait(tick).await;
+                continue;
+            }
+
+            self.reconcile_if_due().await;
+            self.snapshot_wallets_if_due().await;
+
+            let active = self.own_active_strategies().await?;
+
+            let closed = if active.is_empty() {
+                Vec::new()
+            } else {
+                self.trader.monitor_pass(&active).await?
+            };
+
+            let closed_ids: HashSet<&str> = closed.iter().map(|s| s.id.as_str()).collect();
+            let running: Vec<StrategyMetadata> = active
+                .iter()
+                .filter(|s| !closed_ids.contains(s.id.as_str()))
+                .cloned()
+                .collect();
+
+            // Volatile markets may need more frequent risk checks than the scheduler tick
+            let interval = if running.is_empty() {
+                tick
+            } else {
+                tick.min(self.trader.risk_check_interval(&running))
+            };
+            self.wait(interval).await;
+        }
+    }
+
+    /// Active strategies owned by this instance, i.e. whose first wallet it holds a lease on
+    async fn own_active_strategies(&self) -> Result<Vec<StrategyMetadata>, TradingError> {
+        let active = self.trader.get_active_strategies().await?;
+        let leases = self.trader.wallet_leases();
+
+        Ok(active
+            .into_iter()
+            .filter(|s| leases.owns(&s.wallet_ids))
+            .collect())
+    }
+
+    /// Reconcile stored and live positions at startup and then every `reconciliation.interval_minutes`.
+    /// Failures are logged and retried on the next tick.
+    async fn reconcile_if_due(&mut self) {
+        let config = &self.trader.config().reconciliation;
+        if !config.enabled {
+            return;
+        }
+
+        let now = Utc::now();
+        let interval = Duration::minutes(config.interval_minutes as i64);
+        if self.last_reconciliation.is_some_and(|last| now - last < interval) {
+            return;
+        }
+
+        match self.trader.reconcile_positions().await {
+            Ok(_) => self.last_reconciliation = Some(now),
+            Err(e) => error!("❌ Position reconciliation failed: {}", e),
+        }
+    }
+
+    /// Snapshot wallet balances and points at startup and then every `snapshots.interval_minutes`.
+    /// Failures are logged and retried on the next tick.
+    async fn snapshot_wallets_if_due(&mut self) {
+        let config = &self.trader.config().snapshots;
+        if !config.enabled {
+            return;
+        }
+
+        let now = Utc::now();
+        let interval = Duration::minutes(config.interval_minutes as i64);
+        if self.last_snapshot.is_some_and(|last| now - last < interval) {
+            return;
+        }
+
+        match self.trader.snapshot_wallets().await {
+            Ok(_) => self.last_snapshot = Some(now),
+            Err(e) => error!("❌ Wallet snapshot failed: {}", e),
+        }
+    }
+
+    /// Sleep until the next tick, waking up early when the kill switch changes
+    async fn wait(&self, interval: TokioDuration) {
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = self.trader.kill_switch().notified() => {}
+        }
+    }
+
+    /// Whether the kill switch is active, engaging it the first time it is seen.
+    /// Engaging is retried every tick until every wallet is flat. If the kill switch
+    /// state cannot be read, the tick is skipped.
+    async fn kill_switch_active(&mut self) -> bool {
+        match self.trader.kill_switch().active_state().await {
+            Ok(Some(state)) => {
+                if !self.killed {
+                    match self.trader.engage_kill_switch(&state).await {
+                        Ok(()) => self.killed = true,
+                        Err(e) => error!("❌ Failed to engage kill switch, retrying next tick: {}", e),
+                    }
+                }
+                true
+            }
+            Ok(None) => {
+                if self.killed {
+                    info!("🟢 Kill switch cleared, resuming the scheduler");
+                }
+                self.killed = false;
+                false
+            }
+            Err(e) => {
+                error!("❌ Failed to check kill switch: {}", e);
+                true
+            }
+        }
+    }
+}
+
+/// Opens new cohorts for the scheduler, concurrently with its monitoring
+struct CohortLauncher<'a> {
+    trader: &'a TraderClient,
+    config: SchedulerConfig,
+    cooldown: Duration,
+    /// When each wallet's last strategy closed
+    last_closed: HashMap<u8, DateTime<Utc>>,
+    last_launch: Option<DateTime<Utc>>,
+    /// Whether the circuit breaker was tripped on the last tick
+    halted: bool,
+}
+
+impl CohortLauncher<'_> {
+    /// Launch cohorts every tick until `stopping` is set. Launches are skipped while the
+    /// kill switch is active; engaging it is left to the monitor.
+    async fn run(&mut self, stopping: &AtomicBool) {
+        let tick = TokioDuration::from_secs(self.config.tick_seconds);
+
+        while !stopping.load(Ordering::SeqCst) {
+            let killed = match self.trader.kill_switch().active_state().await {
+                Ok(state) => state.is_some(),
+                Err(e) => {
+                    error!("❌ Failed to check kill switch, not launching this tick: {}", e);
+                    true
+                }
+            };
+
+            if !killed && !self.circuit_breaker_halted().await {
+                match self.trader.get_active_strategies().await {
+                    Ok(active) => self.launch_cohort_if_due(&active).await,
+                    Err(e) => error!("❌ Failed to load active strategies, not launching this tick: {}", e),
+                }
+            }
+
+            sleep(tick).await;
+        }
+    }
+
+    /// Whether new cohorts are halted by the circuit breaker, logging only on state changes.
+    /// If the breaker state cannot be read, launches are skipped for this tick.
+    async fn circuit_breaker_halted(&mut self) -> bool {
+        match self.trader.check_circuit_breaker().await {
+            Ok(Some(reason)) => {
+                if !self.halted {
+                    warn!("🔌 Circuit breaker tripped, not opening new cohorts: {}", reason);
+                }
+                self.halted = true;
+                true
+            }
+            Ok(None) => {
+                if self.halted {
+                    info!("🔌 Circuit breaker reset, resuming cohort launches");
+                }
+                self.halted = false;
+                false
+            }
+            Err(e) => {
+                error!("❌ Failed to check circuit breaker: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Open a new cohort if a strategy slot is free, the launch interval has passed
+    /// and enough wallets are idle. Failures are logged and retried on a later tick.
+    ///
+    /// # Arguments
+    /// * `active` - Active strategies of every instance; only this instance's own count against its slots
+    async fn launch_cohort_if_due(&mut self, active: &[StrategyMetadata]) {
+        let leases = self.trader.wallet_leases();
+        let running: Vec<&StrategyMetadata> = active.iter().filter(|s| leases.owns(&s.wallet_ids)).collect();
+        let slots = self.config.max_concurrent_strategies.saturating_sub(running.len());
+        if slots == 0 {
+            return;
+        }
+
+        let now = Utc::now();
+        let interval = Duration::seconds(self.config.min_cohort_interval_seconds as i64);
+        if self.last_launch.is_some_and(|launched_at| now - launched_at < interval) {
+            return;
+        }
+
+        // Closes by the monitor or other instances are only visible in storage
+        if let Err(e) = self.load_cooldowns().await {
+            error!("❌ Failed to load wallet cooldowns, not launching this tick: {}", e);
+            return;
+        }
+
+        let idle = self.idle_wallets(active, now);
+        if idle.len() < self.config.min_cohort_size {
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+        let size = idle.len().min(self.config.max_cohort_size);
+        let cohort: Vec<u8> = idle.choose_multiple(&mut rng, size).cloned().collect();
+
+        // Count the attempt even if it fails, so a broken wallet is not retried every tick
+        self.last_launch = Some(now);
+
+        info!(
+            "🚀 Launching cohort with wallets {:?} | running strategies: {} | free slots: {}",
+            cohort, running.len(), slots
+        );
+
+        if let Err(e) = self.trader.close_stray_positions(&cohort).await {
+            error!("❌ Failed to clear stray positions for cohort {:?}: {}", cohort, e);
+            return;
+        }
+
+        match self.trader.open_cohort(&cohort, slots).await {
+            Ok(strategies) => {
+                info!("✅ Cohort {:?} opened {} strategy(ies)", cohort, strategies.len());
+            }
+            Err(e) => {
+                error!("❌ Failed to open cohort {:?}: {}", cohort, e);
+            }
+        }
+    }
+
+    /// Leased wallets that are not part of any active strategy, past their cooldown and not the reserve wallet.
+    /// A wallet can be leased here while it trades in a strategy owned by another instance.
+    fn idle_wallets(&self, active: &[StrategyMetadata], now: DateTime<Utc>) -> Vec<u8> {
+        let mut busy: HashSet<u8> = active.iter().flat_map(|s| s.wallet_ids.iter().cloned()).collect();
+
+        // The reserve wallet only funds collateral rescues
+        if let Some(reserve_id) = self.trader.config().rescue.reserve_wallet_id {
+            busy.insert(reserve_id);
+        }
+
+        self.trader
+            .wallet_leases()
+            .held()
+            .into_iter()
+            .filter(|id| !busy.contains(id))
+            .filter(|id| {
+                self.last_closed
+                    .get(id)
+                    .is_none_or(|closed_at| now - *closed_at >= self.cooldown)
+            })
+            .collect()
+    }
+
+    /// Refresh wallet cooldowns from the close times of stored strategies
+    async fn load_cooldowns(&mut self) -> Result<(), TradingError> {
+        for strategy in self.trader.get_all_strategies().await? {
+            let Some(closed_at) = strategy.closed_at else {
+                continue;
+            };
+
+            for wallet_id in strategy.wallet_ids {
+                let entry = self.last_closed.entry(wallet_id).or_insert(closed_at);
+                if closed_at > *entry {
+                    *entry = closed_at;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
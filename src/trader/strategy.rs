@@ -5,7 +5,7 @@ use rust_decimal::{prelude::FromPrimitive, Decimal};
 use rand::Rng;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
-use crate::{error::TradingError, model::{Position, PositionSide}};
+use crate::{config::TradingConfig, error::TradingError, model::{Position, PositionSide}, trader::estimator::CostEstimate};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingStrategy {
@@ -22,6 +22,49 @@ pub struct TradingStrategy {
     pub closed_at: Option<DateTime<Utc>>,
     pub realized_pnl: Option<Decimal>,
     pub status: StrategyStatus,
+    pub strategy_type: StrategyType,
+    /// Tokens shorted against `token_symbol` (correlated pairs only)
+    pub hedge_symbols: Vec<String>,
+    /// Short notional per unit of long notional (correlated pairs only)
+    pub hedge_ratio: Option<Decimal>,
+    /// Pre-trade estimate of the round-trip cost (in USDC)
+    pub estimated_cost_usdc: Option<Decimal>,
+    /// Pre-trade estimate of the cost per unit of volume (in basis points)
+    pub estimated_cost_bps: Option<Decimal>,
+    /// Notional-weighted entry slippage against the order book mid (in basis points)
+    pub entry_slippage_bps: Option<Decimal>,
+    /// Worst entry slippage of a single leg (in basis points)
+    pub max_leg_slippage_bps: Option<Decimal>,
+}
+
+/// How a strategy hedges its long side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StrategyType {
+    /// Long and short the same token across wallets
+    DeltaNeutral,
+    /// Long one token, short a beta-weighted basket of correlated tokens
+    CorrelatedPair,
+}
+
+impl std::fmt::Display for StrategyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrategyType::DeltaNeutral => write!(f, "DELTA_NEUTRAL"),
+            StrategyType::CorrelatedPair => write!(f, "CORRELATED_PAIR"),
+        }
+    }
+}
+
+impl std::str::FromStr for StrategyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "DELTA_NEUTRAL" => Ok(StrategyType::DeltaNeutral),
+            "CORRELATED_PAIR" => Ok(StrategyType::CorrelatedPair),
+            _ => Err(format!("Invalid StrategyType: {}", s)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,7 +72,9 @@ pub enum StrategyStatus {
     Running,
     Closing,
     Closed,
-    Failed
+    Failed,
+    /// Flattened by the kill switch
+    Killed,
 }
 
 impl StrategyStatus {
@@ -40,7 +85,7 @@ impl StrategyStatus {
 
     /// Check if status represents a completed strategy
     pub fn is_completed(&self) -> bool {
-        matches!(self, StrategyStatus::Closed | StrategyStatus::Failed)
+        matches!(self, StrategyStatus::Closed | StrategyStatus::Failed | StrategyStatus::Killed)
     }
 
     /// Check if status represents a strategy that can be traded
@@ -62,6 +107,26 @@ impl StrategyStatus {
     pub fn is_closed(&self) -> bool {
         matches!(self, StrategyStatus::Closed)
     }
+
+    /// Check if a strategy in this status may be moved to `next`
+    ///
+    /// | From    | To                                      |
+    /// |---------|-----------------------------------------|
+    /// | Running | Closing, Failed, Killed                 |
+    /// | Closing | Closing (retry), Closed, Failed, Killed |
+    /// | Failed  | Closing (retry)                         |
+    /// | Closed  | -                                       |
+    /// | Killed  | -                                       |
+    pub fn can_transition_to(&self, next: StrategyStatus) -> bool {
+        use StrategyStatus::*;
+
+        matches!(
+            (self, next),
+            (Running, Closing | Failed | Killed)
+                | (Closing, Closing | Closed | Failed | Killed)
+                | (Failed, Closing)
+        )
+    }
 }
 
 
@@ -72,6 +137,7 @@ impl std::fmt::Display for StrategyStatus {
             StrategyStatus::Closing => write!(f, "CLOSING"),
             StrategyStatus::Closed => write!(f, "CLOSED"),
             StrategyStatus::Failed => write!(f, "FAILED"),
+            StrategyStatus::Killed => write!(f, "KILLED"),
         }
     }
 }
@@ -85,6 +151,7 @@ impl std::str::FromStr for StrategyStatus {
             "CLOSING" => Ok(StrategyStatus::Closing),
             "CLOSED" => Ok(StrategyStatus::Closed),
             "FAILED" => Ok(StrategyStatus::Failed),
+            "KILLED" => Ok(StrategyStatus::Killed),
             _ => Err(format!("Invalid StrategyStatus: {}", s)),
         }
     }
@@ -97,6 +164,10 @@ pub struct WalletAllocation {
     pub side: PositionSide,
     pub usdc_amount: Decimal,
     pub percentage: Decimal,
+    /// Maximum position size the wallet can carry at the chosen leverage and `min_collateral_ratio`
+    pub capacity_usdc: Decimal,
+    /// Leverage chosen for the plan, applied to the leg's market before opening
+    pub leverage: f64,
 }
 
 impl TradingStrategy {
@@ -145,7 +216,14 @@ impl TradingStrategy {
             close_at,
             closed_at,
             realized_pnl,
-            status
+            status,
+            strategy_type: StrategyType::DeltaNeutral,
+            hedge_symbols: Vec::new(),
+            hedge_ratio: None,
+            estimated_cost_usdc: None,
+            estimated_cost_bps: None,
+            entry_slippage_bps: None,
+            max_leg_slippage_bps: None,
         })
     }
 
@@ -167,15 +245,15 @@ impl TradingStrategy {
     
     /// Generate balanced long/short allocations from wallet balances
     /// Ensures total long value ≈ total short value for market neutrality
-    /// Applies random leverage between 2x and 4x to each wallet allocation
+    /// Applies random leverage between `min_leverage` and `max_leverage` to each wallet allocation,
+    /// keeping every wallet at `min_collateral_ratio` or above once opened
     pub fn generate_balanced_allocations(
         wallet_balances: &Vec<(u8, Decimal)>,
+        trading: &TradingConfig,
     ) -> Result<Vec<WalletAllocation>, TradingError> {
         use rand::seq::SliceRandom;
         use rand::Rng;
 
-        let config = crate::config::AppConfig::load()?;
-
         if wallet_balances.len() < 3 {
             return Err(TradingError::InvalidInput(
                 "At least 3 wallets are required".into()
@@ -219,10 +297,14 @@ impl TradingStrategy {
         let mut allocations = Vec::new();
 
         // Generate a single leverage factor that will be applied to BOTH sides for neutrality
-        let leverage = rng.gen_range(config.trading.min_leverage..=config.trading.max_leverage);
+        let leverage = rng.gen_range(trading.min_leverage..=trading.max_leverage);
+
+        // The market's initial margin is 1 / leverage, so a wallet filled to balance × leverage
+        // would sit at a collateral ratio of 1.0: size for the minimum ratio instead
+        let exposure = leverage / trading.min_collateral_ratio.max(1.0);
 
         // Calculate total allocation for each side to ensure exact balance
-        let total_allocation_per_side = tradeable_amount * Decimal::from_f64(leverage).unwrap();
+        let total_allocation_per_side = tradeable_amount * Decimal::from_f64(exposure).unwrap();
 
         // Generate allocations for longs with capacity-aware distribution
         let long_allocations = Self::distribute_allocation(
@@ -230,6 +312,7 @@ impl TradingStrategy {
             &wallet_balances,
             total_allocation_per_side,
             leverage,
+            exposure,
             PositionSide::Long,
         )?;
 
@@ -239,6 +322,7 @@ impl TradingStrategy {
             &wallet_balances,
             total_allocation_per_side,
             leverage,
+            exposure,
             PositionSide::Short,
         )?;
 
@@ -246,7 +330,7 @@ impl TradingStrategy {
         allocations.extend(short_allocations);
 
         // Log the allocation strategy
-        info!("Generated RANDOMIZED balanced allocation strategy with leverage ({:.1}x-{:.1}x):", config.trading.min_leverage, config.trading.max_leverage);
+        info!("Generated RANDOMIZED balanced allocation strategy with leverage ({:.1}x-{:.1}x):", trading.min_leverage, trading.max_leverage);
         let long_total: Decimal = allocations.iter()
             .filter(|a| a.side == PositionSide::Long)
             .map(|a| a.usdc_amount)
@@ -289,6 +373,7 @@ impl TradingStrategy {
         wallet_balances: &[(u8, Decimal)],
         total_allocation: Decimal,
         leverage: f64,
+        exposure: f64,
         side: PositionSide,
     ) -> Result<Vec<WalletAllocation>, TradingError> {
         let mut rng = rand::thread_rng();
@@ -301,12 +386,12 @@ impl TradingStrategy {
             .collect();
         let total_weight: f64 = weights.iter().sum();
         
-        // Calculate each wallet's capacity (max they can take after leverage)
+        // Calculate each wallet's capacity (max they can take while keeping the minimum collateral ratio)
         let wallet_capacities: Vec<Decimal> = wallet_indices
             .iter()
             .map(|&idx| {
                 let balance = wallet_balances[idx].1;
-                balance * Decimal::from_f64(leverage).unwrap()
+                balance * Decimal::from_f64(exposure).unwrap()
             })
             .collect();
         
@@ -379,6 +464,8 @@ impl TradingStrategy {
                 side,
                 usdc_amount,
                 percentage,
+                capacity_usdc: wallet_capacities[i],
+                leverage,
             });
         }
         
@@ -392,7 +479,8 @@ impl TradingStrategy {
         token_symbol: &str,
         allocations: &[WalletAllocation],
         wallet_balances: &[(u8, Decimal)],
-        duration_minutes: i64
+        duration_minutes: i64,
+        estimate: Option<&CostEstimate>,
     ) {
         println!("\nSTRATEGY PREVIEW\n");
         
@@ -419,7 +507,65 @@ impl TradingStrategy {
             println!("   {}. Wallet #{} - ${:.2} USDC ({:.1}%)", 
                 i + 1, allocation.wallet_id, allocation.usdc_amount, allocation.percentage);
         }
-        
+
+        if let Some(estimate) = estimate {
+            println!("\n🧾 Estimated Round-Trip Cost:");
+            println!("   Fees: ${:.2} USDC", estimate.fee_usdc);
+            println!("   Spread/slippage: ${:.2} USDC", estimate.spread_usdc);
+            println!("   Funding: ${:.2} USDC", estimate.funding_usdc);
+            println!("   Total: ${:.2} USDC ({:.2} bps of ${:.2} volume)", 
+                estimate.total_usdc, estimate.cost_bps, estimate.volume_usdc);
+            if let Some(volume_per_usdc) = estimate.volume_per_usdc() {
+                println!("   Efficiency: ${:.0} volume per $1 of cost", volume_per_usdc);
+            }
+        }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perp::lighter::{client::initial_margin_fraction, models::tests::account};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn freshly_sized_wallets_meet_the_default_collateral_ratio() {
+        let trading: TradingConfig = serde_json::from_value(serde_json::json!({})).unwrap();
+        let min_ratio = Decimal::from_f64(trading.min_collateral_ratio).unwrap();
+        let balances = vec![(1, dec!(120)), (2, dec!(80)), (3, dec!(250)), (4, dec!(95)), (5, dec!(400))];
+
+        for _ in 0..50 {
+            for allocation in TradingStrategy::generate_balanced_allocations(&balances, &trading).unwrap() {
+                let balance = balances.iter().find(|(id, _)| *id == allocation.wallet_id).unwrap().1;
+                let fraction = Decimal::from(initial_margin_fraction(allocation.leverage).unwrap()) / Decimal::ONE_HUNDRED;
+                let ratio = account(balance, &[(allocation.usdc_amount, fraction)]).collateral_ratio().unwrap();
+
+                assert!(ratio >= min_ratio, "wallet #{} opens at a collateral ratio of {}", allocation.wallet_id, ratio);
+            }
+        }
+    }
+
+    #[test]
+    fn status_transitions() {
+        use StrategyStatus::*;
+
+        let all = [Running, Closing, Closed, Failed, Killed];
+        let allowed: &[(StrategyStatus, StrategyStatus)] = &[
+            (Running, Closing),
+            (Running, Failed),
+            (Running, Killed),
+            (Closing, Closing),
+            (Closing, Closed),
+            (Closing, Failed),
+            (Closing, Killed),
+            (Failed, Closing),
+        ];
+
+        for from in all {
+            for to in all {
+                assert_eq!(from.can_transition_to(to), allowed.contains(&(from, to)), "{} -> {}", from, to);
+            }
+        }
+    }
+}
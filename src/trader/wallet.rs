@@ -2,7 +2,8 @@ use std::{fs::File, io::BufReader};
 use serde::{Deserialize, Serialize};
 use anyhow::{Context, Result};
 use solana_sdk::signature::{Keypair, Signer};
-use crate::{config::AppConfig, error::TradingError, helpers::encode, perp::lighter::client::LighterClient, storage::database::Database};
+use crate::{config::AppConfig, error::TradingError, helpers::encode, perp::lighter::client::LighterClient, storage::{database::Database, traits::OrderStore}};
+use std::sync::Arc;
 
 /// Wallet struct containing API secrets for authentication with exchanges
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,8 +24,9 @@ pub struct WalletTradingClient {
 }
 
 impl WalletTradingClient {
-    pub async fn new(wallet: Wallet) -> Result<Self, TradingError> {
-        let lighter_client = LighterClient::new(&wallet).await?;
+    /// Create the wallet's exchange clients, recording their orders in `order_ledger`
+    pub async fn new(wallet: Wallet, order_ledger: Arc<dyn OrderStore>) -> Result<Self, TradingError> {
+        let lighter_client = LighterClient::new(&wallet).await?.with_order_ledger(order_ledger);
 
         Ok(WalletTradingClient { wallet, lighter_client })
     }